@@ -1,3 +1,10 @@
+use crate::Cube;
+use std::{
+    fmt::{Display, Formatter},
+    iter::Peekable,
+    str::Chars,
+};
+
 /// Pretty self explanatory, but it describes the type of move.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum MoveType {
@@ -6,6 +13,28 @@ pub enum MoveType {
     Double,
 }
 
+impl MoveType {
+    /// Clockwise quarter turns, from 1 to 3.
+    pub fn quarter_turns(&self) -> u8 {
+        match self {
+            MoveType::Normal => 1,
+            MoveType::Double => 2,
+            MoveType::Prime => 3,
+        }
+    }
+
+    /// The move type turning `turns` clockwise quarter turns, `None` when
+    /// that's no turn at all.
+    pub fn from_quarter_turns(turns: u8) -> Option<Self> {
+        match turns % 4 {
+            1 => Some(MoveType::Normal),
+            2 => Some(MoveType::Double),
+            3 => Some(MoveType::Prime),
+            _ => None,
+        }
+    }
+}
+
 /// Describes which side should be moves.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Side {
@@ -17,40 +46,232 @@ pub enum Side {
     Back,
 }
 
-/// Describes a move, both by [`MoveType`] and [`Side`].
+impl Side {
+    pub fn opposite(&self) -> Self {
+        match self {
+            Side::Up => Side::Down,
+            Side::Down => Side::Up,
+            Side::Right => Side::Left,
+            Side::Left => Side::Right,
+            Side::Front => Side::Back,
+            Side::Back => Side::Front,
+        }
+    }
+
+    /// Where this side ends up when the whole cube is rotated a clockwise
+    /// quarter turn around `axis`, so `Front` goes `Up` around `Right`.
+    pub fn rotated(&self, axis: &Side) -> Self {
+        use Side::*;
+
+        // The sides around each of Right, Up and Front, in the order a
+        // clockwise turn of it moves them.
+        let cycle = match axis {
+            Right | Left => [Front, Up, Back, Down],
+            Up | Down => [Front, Left, Back, Right],
+            Front | Back => [Up, Right, Down, Left],
+        };
+        let steps = match axis {
+            Right | Up | Front => 1,
+            Left | Down | Back => 3,
+        };
+
+        match cycle.iter().position(|side| side == self) {
+            Some(i) => cycle[(i + steps) % 4].clone(),
+            None => self.clone(),
+        }
+    }
+}
+
+/// Which layers a move turns.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Layers {
+    /// Just the face on its side, like `R`.
+    Outer,
+    /// The whole cube, turning the same way as the side: `x` turns with
+    /// `R`, `y` with `U` and `z` with `F`.
+    Whole,
+    /// The face and the middle layer next to it, like `r`.
+    Wide,
+    /// Just the middle layer, turning the same way as the side: `M` turns
+    /// with `L`, `E` with `D` and `S` with `F`.
+    Slice,
+}
+
+/// Describes a move, by [`MoveType`], [`Side`] and [`Layers`].
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Move {
     pub side: Side,
     pub ty: MoveType,
+    pub layers: Layers,
 }
 
 impl Move {
+    /// A turn of the face on `side`.
     pub fn new(side: Side, ty: MoveType) -> Self {
-        Self { side, ty }
+        Self {
+            side,
+            ty,
+            layers: Layers::Outer,
+        }
+    }
+
+    /// A rotation of the whole cube the way `side` turns.
+    pub fn rotation(side: Side, ty: MoveType) -> Self {
+        Self {
+            side,
+            ty,
+            layers: Layers::Whole,
+        }
     }
 
+    /// Parses a single move, in any of the forms [`Algorithm::parse`] reads.
     pub fn parse(source: &str) -> Option<Self> {
-        let side = match source.chars().nth(0)? {
-            'U' => Side::Up,
-            'D' => Side::Down,
-            'R' => Side::Right,
-            'L' => Side::Left,
-            'F' => Side::Front,
-            'B' => Side::Back,
+        let mut chars = source.trim().chars().peekable();
+        let mv = Self::scan(chars.next()?, &mut chars)?;
+        match chars.next() {
+            None => Some(mv),
+            Some(_) => None,
+        }
+    }
+
+    /// Reads the move starting with `first`, taking its suffix from `rest`.
+    /// The turn can be written `2`, `3` or `'`, and both a count and a prime
+    /// like `2'`, with any of the quotes people use for a prime.
+    fn scan(first: char, rest: &mut Peekable<Chars>) -> Option<Self> {
+        let (side, layers) = match first {
+            'U' => (Side::Up, Layers::Outer),
+            'D' => (Side::Down, Layers::Outer),
+            'R' => (Side::Right, Layers::Outer),
+            'L' => (Side::Left, Layers::Outer),
+            'F' => (Side::Front, Layers::Outer),
+            'B' => (Side::Back, Layers::Outer),
+            'x' => (Side::Right, Layers::Whole),
+            'y' => (Side::Up, Layers::Whole),
+            'z' => (Side::Front, Layers::Whole),
+            'u' => (Side::Up, Layers::Wide),
+            'd' => (Side::Down, Layers::Wide),
+            'r' => (Side::Right, Layers::Wide),
+            'l' => (Side::Left, Layers::Wide),
+            'f' => (Side::Front, Layers::Wide),
+            'b' => (Side::Back, Layers::Wide),
+            'M' => (Side::Left, Layers::Slice),
+            'E' => (Side::Down, Layers::Slice),
+            'S' => (Side::Front, Layers::Slice),
             _ => return None,
         };
+        // Wide moves can also be written like `Rw`.
+        let layers = match (layers, rest.peek()) {
+            (Layers::Outer, Some('w')) => {
+                rest.next();
+                Layers::Wide
+            }
+            (layers, _) => layers,
+        };
 
-        let ty = match source.chars().nth(1) {
-            None => MoveType::Normal,
-            Some('\'') => MoveType::Prime,
-            Some('2') => MoveType::Double,
-            _ => return None,
+        let mut turns = 1;
+        if let Some(count) = rest.peek().and_then(|c| c.to_digit(10)) {
+            if !(1..=3).contains(&count) {
+                return None;
+            }
+            turns = count as u8;
+            rest.next();
+        }
+        if let Some('\'') | Some('’') | Some('‘') | Some('′') = rest.peek() {
+            turns = 4 - turns;
+            rest.next();
+        }
+
+        let ty = MoveType::from_quarter_turns(turns)?;
+        Some(Self { side, ty, layers })
+    }
+
+    pub fn is_rotation(&self) -> bool {
+        self.layers == Layers::Whole
+    }
+
+    /// The same move as turns of outer layers and a rotation, which is
+    /// what wide and slice moves come down to: `r` is `L x` and `M` is
+    /// `R L' x'`.
+    pub fn to_outer(&self) -> Vec<Self> {
+        let opposite = Self::new(self.side.opposite(), self.ty.clone());
+        let rotation = Self::rotation(self.side.clone(), self.ty.clone());
+        match self.layers {
+            Layers::Outer | Layers::Whole => vec![self.clone()],
+            Layers::Wide => vec![opposite, rotation],
+            Layers::Slice => vec![
+                opposite,
+                Self::new(self.side.clone(), self.ty.clone()).inverse(),
+                rotation,
+            ],
+        }
+    }
+
+    /// The move undoing this one.
+    pub fn inverse(&self) -> Self {
+        let ty = match self.ty {
+            MoveType::Normal => MoveType::Prime,
+            MoveType::Prime => MoveType::Normal,
+            MoveType::Double => MoveType::Double,
+        };
+
+        Self {
+            side: self.side.clone(),
+            ty,
+            layers: self.layers.clone(),
+        }
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let (name, ty) = match (&self.layers, &self.side) {
+            (Layers::Outer, Side::Up) => ('U', self.ty.clone()),
+            (Layers::Outer, Side::Down) => ('D', self.ty.clone()),
+            (Layers::Outer, Side::Right) => ('R', self.ty.clone()),
+            (Layers::Outer, Side::Left) => ('L', self.ty.clone()),
+            (Layers::Outer, Side::Front) => ('F', self.ty.clone()),
+            (Layers::Outer, Side::Back) => ('B', self.ty.clone()),
+            // Rotations are named after Right, Up and Front only, so the
+            // other sides turn the other way.
+            (Layers::Whole, Side::Right) => ('x', self.ty.clone()),
+            (Layers::Whole, Side::Up) => ('y', self.ty.clone()),
+            (Layers::Whole, Side::Front) => ('z', self.ty.clone()),
+            (Layers::Whole, Side::Left) => ('x', self.inverse().ty),
+            (Layers::Whole, Side::Down) => ('y', self.inverse().ty),
+            (Layers::Whole, Side::Back) => ('z', self.inverse().ty),
+            (Layers::Wide, Side::Up) => ('u', self.ty.clone()),
+            (Layers::Wide, Side::Down) => ('d', self.ty.clone()),
+            (Layers::Wide, Side::Right) => ('r', self.ty.clone()),
+            (Layers::Wide, Side::Left) => ('l', self.ty.clone()),
+            (Layers::Wide, Side::Front) => ('f', self.ty.clone()),
+            (Layers::Wide, Side::Back) => ('b', self.ty.clone()),
+            // Likewise slices are named after Left, Down and Front.
+            (Layers::Slice, Side::Left) => ('M', self.ty.clone()),
+            (Layers::Slice, Side::Down) => ('E', self.ty.clone()),
+            (Layers::Slice, Side::Front) => ('S', self.ty.clone()),
+            (Layers::Slice, Side::Right) => ('M', self.inverse().ty),
+            (Layers::Slice, Side::Up) => ('E', self.inverse().ty),
+            (Layers::Slice, Side::Back) => ('S', self.inverse().ty),
         };
+        write!(f, "{}", name)?;
 
-        Some(Self { side, ty })
+        match ty {
+            MoveType::Normal => Ok(()),
+            MoveType::Prime => write!(f, "'"),
+            MoveType::Double => write!(f, "2"),
+        }
     }
 }
 
+/// What [`Algorithm::equivalent_modulo`] overlooks when comparing.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Equivalence {
+    /// How the cube is held at the end.
+    pub rotation: bool,
+    /// A final turn of the U layer.
+    pub auf: bool,
+}
+
 /// A set of moves, executed in sequence.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Algorithm {
@@ -62,22 +283,212 @@ impl Algorithm {
         Self { moves }
     }
 
-    /// Parses from a string.
+    /// Parses from a string. Moves don't need spaces between them, and
+    /// brackets, `//` comments to the end of the line and `/* */` comments
+    /// are skipped.
     ///
     /// ```
+    /// # use cube::algoritm::Algorithm;
     /// let alg = Algorithm::parse("R U R' U'").unwrap();
+    /// let pasted = Algorithm::parse("(RUR’U’) // sexy move").unwrap();
+    /// assert_eq!(pasted, alg);
     /// ```
     pub fn parse(source: &str) -> Option<Self> {
-        let source = source.trim();
-
+        let mut chars = source.chars().peekable();
         let mut moves = Vec::new();
 
-        for s in source.split_whitespace() {
-            moves.push(Move::parse(s)?);
+        while let Some(c) = chars.next() {
+            match c {
+                '(' | ')' => {}
+                c if c.is_whitespace() => {}
+                '/' => match chars.next()? {
+                    '/' => {
+                        chars.find(|&c| c == '\n');
+                    }
+                    '*' => {
+                        let mut last = ' ';
+                        loop {
+                            let c = chars.next()?;
+                            if last == '*' && c == '/' {
+                                break;
+                            }
+                            last = c;
+                        }
+                    }
+                    _ => return None,
+                },
+                c => moves.push(Move::scan(c, &mut chars)?),
+            }
         }
 
         Some(Self { moves })
     }
+
+    /// The algorithm undoing this one.
+    pub fn inverse(&self) -> Self {
+        Self::new(self.moves.iter().rev().map(Move::inverse).collect())
+    }
+
+    /// The same moves with the rotations taken out, turning the faces that
+    /// the moves after them would have turned instead. Wide and slice moves
+    /// become outer turns.
+    ///
+    /// ```
+    /// # use cube::algoritm::Algorithm;
+    /// let alg = Algorithm::parse("y R U x2 F").unwrap();
+    /// assert_eq!(alg.without_rotations().to_string(), "B U L");
+    /// ```
+    pub fn without_rotations(&self) -> Self {
+        use Side::*;
+
+        // Where each of U D R L F B is now, in terms of where it started.
+        let mut frame = [Up, Down, Right, Left, Front, Back];
+        let index = |side: &Side| match side {
+            Up => 0,
+            Down => 1,
+            Right => 2,
+            Left => 3,
+            Front => 4,
+            Back => 5,
+        };
+
+        let mut moves = Vec::new();
+        for mv in self.moves.iter().flat_map(Move::to_outer) {
+            if mv.is_rotation() {
+                for _ in 0..mv.ty.quarter_turns() {
+                    // The side that gets to each place is the one that's
+                    // three quarter turns further back.
+                    let before = frame.clone();
+                    for (i, side) in [Up, Down, Right, Left, Front, Back].iter().enumerate() {
+                        let from = side.rotated(&mv.side).rotated(&mv.side).rotated(&mv.side);
+                        frame[i] = before[index(&from)].clone();
+                    }
+                }
+            } else {
+                moves.push(Move {
+                    side: frame[index(&mv.side)].clone(),
+                    ..mv.clone()
+                });
+            }
+        }
+
+        Self::new(moves)
+    }
+
+    /// The number of moves, leaving out rotations.
+    pub fn move_count(&self) -> usize {
+        self.moves.iter().filter(|mv| !mv.is_rotation()).count()
+    }
+
+    /// The 24 ways to hold the cube, as the rotations from the usual way,
+    /// starting with no rotation at all.
+    pub fn orientations() -> Vec<Self> {
+        let mut orientations = Vec::new();
+        for up in ["", "x", "x2", "x'", "z", "z'"].iter() {
+            for turn in ["", "y", "y2", "y'"].iter() {
+                orientations.push(Self::parse(&format!("{} {}", up, turn)).unwrap());
+            }
+        }
+        orientations
+    }
+
+    /// Whether both algorithms do the same to a solved cube.
+    ///
+    /// ```
+    /// # use cube::algoritm::Algorithm;
+    /// let sexy = Algorithm::parse("R U R' U'").unwrap();
+    /// let rotated = Algorithm::parse("y F U F' U' y'").unwrap();
+    /// assert!(sexy.equivalent(&rotated));
+    /// ```
+    pub fn equivalent(&self, other: &Self) -> bool {
+        self.equivalent_modulo(other, &Equivalence::default())
+    }
+
+    /// Whether both algorithms do the same to a solved cube, overlooking
+    /// the differences in `modulo`. A final U adjustment turns the layer
+    /// with the U centre, wherever it ends up.
+    pub fn equivalent_modulo(&self, other: &Self, modulo: &Equivalence) -> bool {
+        let state = |alg: &Self| {
+            let mut cube = Cube::default();
+            cube.execute_algorithm(alg.clone());
+            if modulo.rotation {
+                cube.reorient();
+            }
+            cube
+        };
+        let (mut mine, theirs) = (state(self), state(other));
+
+        if !modulo.auf {
+            return mine == theirs;
+        }
+        let up = Cube::default().get_face(Side::Up).mm;
+        let auf = match mine.centre_side(up) {
+            Some(side) => Move::new(side, MoveType::Normal),
+            None => return false,
+        };
+        (0..4).any(|_| {
+            mine.execute_move(auf.clone());
+            mine == theirs
+        })
+    }
+
+    /// Merges consecutive turns of the same side, also across a turn of
+    /// the opposite side since those commute, dropping turns that cancel.
+    ///
+    /// ```
+    /// # use cube::algoritm::Algorithm;
+    /// let alg = Algorithm::parse("R U U' L R2 F").unwrap();
+    /// assert_eq!(alg.simplify().to_string(), "R' L F");
+    /// ```
+    pub fn simplify(&self) -> Self {
+        let mut moves: Vec<Move> = Vec::new();
+
+        for mv in &self.moves {
+            let n = moves.len();
+            let same = |other: &Move| other.side == mv.side && other.layers == mv.layers;
+            let target = if n >= 1 && same(&moves[n - 1]) {
+                Some(n - 1)
+            } else if n >= 2
+                && mv.layers == Layers::Outer
+                && moves[n - 1].side == mv.side.opposite()
+                && moves[n - 1].layers == Layers::Outer
+                && same(&moves[n - 2])
+            {
+                Some(n - 2)
+            } else {
+                None
+            };
+
+            match target {
+                Some(i) => {
+                    let turns = moves[i].ty.quarter_turns() + mv.ty.quarter_turns();
+                    match MoveType::from_quarter_turns(turns) {
+                        Some(ty) => moves[i].ty = ty,
+                        None => {
+                            moves.remove(i);
+                        }
+                    }
+                }
+                None => moves.push(mv.clone()),
+            }
+        }
+
+        Self::new(moves)
+    }
+}
+
+/// Writes the moves separated by spaces, in the same notation [`Algorithm::parse`] reads.
+impl Display for Algorithm {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        for (i, mv) in self.moves.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", mv)?;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -99,4 +510,145 @@ mod tests {
 
         assert_eq!(parsed, Some(expected));
     }
+
+    #[test]
+    pub fn algoritm_display_round_trips() {
+        let source = "R U2 R' F' B D2 L";
+        let alg = Algorithm::parse(source).unwrap();
+
+        assert_eq!(alg.to_string(), source);
+        assert_eq!(alg.moves[2].inverse(), Move::new(Right, Normal));
+    }
+
+    #[test]
+    pub fn algoritm_inverse_and_simplify() {
+        let alg = Algorithm::parse("R U2 F'").unwrap();
+        assert_eq!(alg.inverse().to_string(), "F U2 R'");
+
+        let cancelling = Algorithm::parse("R U R' U' U R U' R'").unwrap();
+        assert!(cancelling.simplify().moves.is_empty());
+
+        let across = Algorithm::parse("U D U2 D' F").unwrap();
+        assert_eq!(across.simplify().to_string(), "U' F");
+
+        let rotations = Algorithm::parse("R x x R' y'").unwrap();
+        assert_eq!(rotations.simplify().to_string(), "R x2 R' y'");
+        assert_eq!(rotations.inverse().to_string(), "y R x' x' R'");
+    }
+
+    #[test]
+    pub fn algoritm_parsing_is_forgiving() {
+        let expected = Algorithm::parse("R U R' U' R2 F' B").unwrap();
+        for source in [
+            "RUR'U'R2F'B",
+            "R U R’ U‘ R2' F3 B",
+            "(R U R' U') // sexy\n R2 /* the\nrest */ F' B",
+            "  R U\n\tR'U' R2 F'B // done",
+        ]
+        .iter()
+        {
+            assert_eq!(
+                Algorithm::parse(source).as_ref(),
+                Some(&expected),
+                "{}",
+                source
+            );
+        }
+
+        assert_eq!(
+            Algorithm::parse("// just a comment"),
+            Some(Algorithm::new(vec![]))
+        );
+        assert_eq!(Move::parse("R3'"), Some(Move::new(Right, Normal)));
+        assert_eq!(Move::parse("RU"), None);
+        assert_eq!(Algorithm::parse("R4"), None);
+        assert_eq!(Algorithm::parse("R /* open"), None);
+        assert_eq!(Algorithm::parse("R / U"), None);
+        assert_eq!(Algorithm::parse("R''"), None);
+    }
+
+    #[test]
+    pub fn equivalence() {
+        let alg = |source| Algorithm::parse(source).unwrap();
+        let sune = alg("R U R' U R U2 R'");
+        let both = Equivalence {
+            rotation: true,
+            auf: true,
+        };
+
+        assert!(sune.equivalent(&alg("R U R' U R U2 R'")));
+        assert!(sune.equivalent(&alg("y F U F' U F U2 F' y'")));
+        assert!(!sune.equivalent(&alg("R U R' U R U2 R' x")));
+        assert!(sune.equivalent_modulo(
+            &alg("R U R' U R U2 R' x"),
+            &Equivalence {
+                rotation: true,
+                auf: false
+            }
+        ));
+
+        let auf = Equivalence {
+            rotation: false,
+            auf: true,
+        };
+        assert!(sune.equivalent_modulo(&alg("R U R' U R U2 R' U'"), &auf));
+        assert!(!sune.equivalent_modulo(&alg("R U R' U R U2 R' D"), &auf));
+        assert!(sune.equivalent_modulo(&alg("R U R' U R U2 R' U2 z2"), &both));
+        // The same case, but seen from another side.
+        assert!(!sune.equivalent_modulo(&alg("y R U R' U R U2 R'"), &both));
+
+        assert_eq!(Algorithm::orientations().len(), 24);
+    }
+
+    #[test]
+    pub fn rotations() {
+        let alg = Algorithm::parse("x y' z2 R").unwrap();
+        assert!(alg.moves[0].is_rotation());
+        assert_eq!(alg.move_count(), 1);
+        assert_eq!(Move::rotation(Left, Normal).to_string(), "x'");
+        assert_eq!(Algorithm::parse("x3"), Algorithm::parse("x'"));
+
+        assert_eq!(Front.rotated(&Right), Up);
+        assert_eq!(Front.rotated(&Up), Left);
+        assert_eq!(Up.rotated(&Front), Right);
+        assert_eq!(Up.rotated(&Back), Left);
+
+        // Rotating to where the moves happen is like turning another face.
+        let alg = Algorithm::parse("z R x' U y2 F").unwrap();
+        assert_eq!(alg.without_rotations().to_string(), "U B R");
+    }
+
+    #[test]
+    pub fn wide_and_slice_moves() {
+        let alg = Algorithm::parse("r U R' U' M' U R U' r' Rw E2 S' f b' l d u").unwrap();
+        assert_eq!(alg.to_string(), "r U R' U' M' U R U' r' r E2 S' f b' l d u");
+        assert_eq!(alg.move_count(), 17);
+        assert_eq!(Move::new(Right, Normal).inverse().to_string(), "R'");
+        assert_eq!(
+            Move {
+                side: Right,
+                ty: Normal,
+                layers: Layers::Slice
+            }
+            .to_string(),
+            "M'"
+        );
+
+        let same = |a: &str, b: &str| {
+            let (a, b) = (Algorithm::parse(a).unwrap(), Algorithm::parse(b).unwrap());
+            assert!(a.equivalent(&b), "{} isn't {}", a, b);
+        };
+        same("r", "L x");
+        same("l'", "R' x");
+        same("u2", "D2 y2");
+        same("f", "B z");
+        same("M", "R L' x'");
+        same("E'", "U' D y");
+        same("S", "F' B z");
+        same("r", "R M'");
+
+        // Without the rotations, slices are just the outer layers turning.
+        let alg = Algorithm::parse("M U M'").unwrap();
+        assert_eq!(alg.without_rotations().to_string(), "R L' B R' L");
+    }
 }
@@ -0,0 +1,28 @@
+//! ANSI escape sequences for colouring terminal output.
+
+use crate::{Colour, Cube};
+
+pub const RESET: &str = "\x1b[0m";
+
+/// The escape sequence setting the background to `colour`, with a
+/// foreground that stays readable on top of it.
+pub fn background(colour: Colour) -> &'static str {
+    match colour {
+        Colour::White => "\x1b[30;107m",
+        Colour::Yellow => "\x1b[30;103m",
+        Colour::Green => "\x1b[30;42m",
+        Colour::Blue => "\x1b[97;44m",
+        Colour::Red => "\x1b[97;41m",
+        Colour::Orange => "\x1b[30;48;5;208m",
+    }
+}
+
+/// Wraps `text` so it's printed on a `colour` background.
+pub fn paint(colour: Colour, text: &str) -> String {
+    format!("{}{}{}", background(colour), text, RESET)
+}
+
+/// The net laid out like `Display for Cube`, with coloured stickers.
+pub fn net(cube: &Cube) -> String {
+    cube.net_with(|_, _, colour| paint(colour, &format!(" {} ", colour)))
+}
@@ -0,0 +1,437 @@
+//! Memorising a cube for blindfolded solving: tracing where each sticker
+//! goes from a buffer piece, lettered with a scheme like Speffz.
+//!
+//! Every corner and edge sticker has a letter. Speffz letters the faces U,
+//! L, F, R, B and D in turn, four letters each, going clockwise from the top
+//! left corner, or the top edge, of each face as drawn in the net. A target
+//! is the sticker the one in the buffer belongs on; after swapping it there,
+//! the next target is where the sticker brought into the buffer belongs.
+//! When the buffer piece comes back before every piece is solved, the cycle
+//! is broken into the first unsolved piece. Pieces that are only twisted or
+//! flipped in place are left out of the cycles and listed instead.
+
+use crate::{
+    algoritm::Side,
+    cubie::{CubieCube, StateError, CORNER_FACELETS, CORNER_NAMES, EDGE_FACELETS, EDGE_NAMES},
+    Cube,
+};
+use std::fmt::{self, Display, Formatter};
+
+/// The faces in the order Speffz letters them.
+const FACES: [Side; 6] = [
+    Side::Up,
+    Side::Left,
+    Side::Front,
+    Side::Right,
+    Side::Back,
+    Side::Down,
+];
+
+/// The corner and edge stickers of a face, clockwise from the top left.
+const CORNER_STICKERS: [usize; 4] = [0, 2, 8, 6];
+const EDGE_STICKERS: [usize; 4] = [1, 5, 7, 3];
+
+/// The letters of the 24 corner stickers and the 24 edge stickers, in the
+/// order Speffz letters them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Scheme {
+    pub corners: [char; 24],
+    pub edges: [char; 24],
+}
+
+impl Scheme {
+    pub fn speffz() -> Self {
+        let letters = std::array::from_fn(|i| (b'A' + i as u8) as char);
+        Self {
+            corners: letters,
+            edges: letters,
+        }
+    }
+
+    /// Reads 24 letters used for both corners and edges, or 48, the corners'
+    /// then the edges'. Letters can't repeat within corners or edges.
+    ///
+    /// ```
+    /// # use cube::bld::Scheme;
+    /// let scheme = Scheme::parse("abcdefghijklmnopqrstuvwx").unwrap();
+    /// assert_eq!(scheme.edges[23], 'x');
+    /// assert!(Scheme::parse("aa").is_none());
+    /// ```
+    pub fn parse(letters: &str) -> Option<Self> {
+        let letters: Vec<char> = letters.chars().filter(|c| !c.is_whitespace()).collect();
+        let (corners, edges) = match letters.len() {
+            24 => (&letters[..], &letters[..]),
+            48 => letters.split_at(24),
+            _ => return None,
+        };
+        let unique = |letters: &[char]| (1..24).all(|i| !letters[..i].contains(&letters[i]));
+        if !unique(corners) || !unique(edges) {
+            return None;
+        }
+        Some(Self {
+            corners: std::array::from_fn(|i| corners[i]),
+            edges: std::array::from_fn(|i| edges[i]),
+        })
+    }
+}
+
+impl Default for Scheme {
+    fn default() -> Self {
+        Self::speffz()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BldOptions {
+    pub scheme: Scheme,
+    /// The positions of the buffer pieces, numbered as in [`crate::cubie`].
+    pub corner_buffer: usize,
+    pub edge_buffer: usize,
+}
+
+/// The Old Pochmann buffers, ULB and UR.
+impl Default for BldOptions {
+    fn default() -> Self {
+        Self {
+            scheme: Scheme::speffz(),
+            corner_buffer: 2,
+            edge_buffer: 0,
+        }
+    }
+}
+
+/// A piece that's in its place but twisted or flipped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Misoriented {
+    /// The position, numbered as in [`crate::cubie`].
+    pub position: usize,
+    /// How many times a corner is twisted clockwise, 1 for a flipped edge.
+    pub turns: u8,
+}
+
+/// The targets of the corners or the edges, as sticker numbers in Speffz
+/// order, and the pieces that are only turned in place.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trace {
+    pub targets: Vec<usize>,
+    pub misoriented: Vec<Misoriented>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Memo {
+    pub scheme: Scheme,
+    pub corners: Trace,
+    pub edges: Trace,
+}
+
+impl Memo {
+    /// Whether there's an odd number of targets, leaving two edges and two
+    /// corners swapped.
+    pub fn parity(&self) -> bool {
+        self.corners.targets.len() % 2 == 1
+    }
+
+    /// The targets as letters in pairs, like `AB CD E`.
+    fn pairs(letters: &[char; 24], targets: &[usize]) -> String {
+        let letters: Vec<String> = targets
+            .chunks(2)
+            .map(|pair| pair.iter().map(|&t| letters[t]).collect())
+            .collect();
+        if letters.is_empty() {
+            "none".to_string()
+        } else {
+            letters.join(" ")
+        }
+    }
+}
+
+/// Shows the edges then the corners, as they're usually memorised, like
+///
+/// ```text
+/// edges: AB CD E
+/// flipped: UF
+/// corners: FG H
+/// twisted: DFR clockwise
+/// parity
+/// ```
+impl Display for Memo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "edges: {}",
+            Self::pairs(&self.scheme.edges, &self.edges.targets)
+        )?;
+        if !self.edges.misoriented.is_empty() {
+            let flipped: Vec<&str> = self
+                .edges
+                .misoriented
+                .iter()
+                .map(|piece| EDGE_NAMES[piece.position])
+                .collect();
+            writeln!(f, "flipped: {}", flipped.join(" "))?;
+        }
+        write!(
+            f,
+            "corners: {}",
+            Self::pairs(&self.scheme.corners, &self.corners.targets)
+        )?;
+        if !self.corners.misoriented.is_empty() {
+            let twisted: Vec<String> = self
+                .corners
+                .misoriented
+                .iter()
+                .map(|piece| {
+                    let way = if piece.turns == 1 {
+                        "clockwise"
+                    } else {
+                        "counterclockwise"
+                    };
+                    format!("{} {}", CORNER_NAMES[piece.position], way)
+                })
+                .collect();
+            write!(f, "\ntwisted: {}", twisted.join(", "))?;
+        }
+        if self.parity() {
+            write!(f, "\nparity")?;
+        }
+        Ok(())
+    }
+}
+
+/// The facelet, as in [`Cube::facelets`], of the corner or edge sticker
+/// numbered `sticker` in Speffz order.
+pub(crate) fn facelet<const N: usize>(sticker: usize) -> usize {
+    let face = Cube::FACELET_ORDER
+        .iter()
+        .position(|side| *side == FACES[sticker / 4])
+        .unwrap();
+    let stickers = if N == 3 {
+        CORNER_STICKERS
+    } else {
+        EDGE_STICKERS
+    };
+    face * 9 + stickers[sticker % 4]
+}
+
+/// The piece stickers of one kind, corners with `N = 3` or edges with
+/// `N = 2`, numbered in Speffz order.
+pub(crate) struct Stickers<const N: usize> {
+    places: &'static [[usize; N]],
+    /// The position of each sticker's piece, and where in the piece it is.
+    pieces: [(usize, usize); 24],
+}
+
+impl Stickers<3> {
+    pub(crate) fn corners() -> Self {
+        Self::new(&CORNER_FACELETS)
+    }
+}
+
+impl Stickers<2> {
+    pub(crate) fn edges() -> Self {
+        Self::new(&EDGE_FACELETS)
+    }
+}
+
+impl<const N: usize> Stickers<N> {
+    fn new(places: &'static [[usize; N]]) -> Self {
+        let pieces = std::array::from_fn(|sticker| {
+            let facelet = facelet::<N>(sticker);
+            places
+                .iter()
+                .enumerate()
+                .find_map(|(position, stickers)| {
+                    let i = stickers.iter().position(|&f| f == facelet)?;
+                    Some((position, i))
+                })
+                .unwrap()
+        });
+        Self { places, pieces }
+    }
+
+    /// The sticker `turns` steps clockwise around the piece from `sticker`.
+    pub(crate) fn turned(&self, sticker: usize, turns: usize) -> usize {
+        let (position, i) = self.pieces[sticker];
+        let facelet = self.places[position][(i + turns) % N];
+        self.pieces
+            .iter()
+            .position(|&(p, j)| self.places[p][j] == facelet)
+            .unwrap()
+    }
+
+    pub(crate) fn piece(&self, sticker: usize) -> usize {
+        self.pieces[sticker].0
+    }
+
+    /// The stickers of the piece at `position`, from its first one.
+    pub(crate) fn of(&self, position: usize) -> [usize; N] {
+        let first = (0..24).find(|&s| self.pieces[s] == (position, 0)).unwrap();
+        std::array::from_fn(|turns| self.turned(first, turns))
+    }
+
+    /// The sticker each sticker of `cube` belongs on, going by its colour and
+    /// the next one clockwise around the piece.
+    fn homes(&self, cube: &Cube) -> [usize; 24] {
+        let colour = |sticker: usize| {
+            let facelet = facelet::<N>(sticker);
+            cube.get_face(Cube::FACELET_ORDER[facelet / 9].clone())
+                .stickers()[facelet % 9]
+        };
+        let centre = |sticker: usize| {
+            let facelet = facelet::<N>(sticker);
+            cube.get_face(Cube::FACELET_ORDER[facelet / 9].clone())
+                .stickers()[4]
+        };
+        std::array::from_fn(|sticker| {
+            (0..24)
+                .find(|&home| {
+                    centre(home) == colour(sticker)
+                        && centre(self.turned(home, 1)) == colour(self.turned(sticker, 1))
+                })
+                .unwrap()
+        })
+    }
+
+    /// Follows the stickers from the piece at `buffer`.
+    fn trace(&self, cube: &Cube, buffer: usize) -> Trace {
+        let homes = self.homes(cube);
+        let misoriented: Vec<Misoriented> = (0..self.places.len())
+            .filter(|&position| position != buffer)
+            .filter_map(|position| {
+                let sticker = self.of(position)[0];
+                let home = homes[sticker];
+                (self.piece(home) == position && home != sticker).then(|| Misoriented {
+                    position,
+                    turns: (0..N).find(|&t| self.turned(home, t) == sticker).unwrap() as u8,
+                })
+            })
+            .collect();
+
+        Trace {
+            targets: self.targets(cube, buffer, false),
+            misoriented,
+        }
+    }
+
+    /// The targets from the piece at `buffer`. With `twists` the cycles also
+    /// break into pieces turned in place, so that swapping the buffer with
+    /// every target in turn solves them all.
+    pub(crate) fn targets(&self, cube: &Cube, buffer: usize, twists: bool) -> Vec<usize> {
+        let mut homes = self.homes(cube);
+        let buffer = self.of(buffer)[0];
+        let in_place =
+            |homes: &[usize; 24], sticker: usize| self.piece(homes[sticker]) == self.piece(sticker);
+        let unsolved = |homes: &[usize; 24], sticker: usize| {
+            if twists {
+                homes[sticker] != sticker
+            } else {
+                !in_place(homes, sticker)
+            }
+        };
+
+        let mut targets = Vec::new();
+        loop {
+            let target = if in_place(&homes, buffer) {
+                // Break into the first piece that isn't solved.
+                match (0..24).find(|&s| self.piece(s) != self.piece(buffer) && unsolved(&homes, s))
+                {
+                    Some(sticker) => sticker,
+                    None => break,
+                }
+            } else {
+                homes[buffer]
+            };
+            targets.push(target);
+            for turns in 0..N {
+                homes.swap(self.turned(buffer, turns), self.turned(target, turns));
+            }
+        }
+        targets
+    }
+}
+
+/// Traces the corners and edges of `cube` from the buffers in `options`.
+///
+/// ```
+/// # use cube::{algoritm::Algorithm, bld::{self, BldOptions}, Cube};
+/// let mut cube = Cube::default();
+/// cube.execute_algorithm(Algorithm::parse("R U R' U' R' F R2 U' R' U' R U R' F'").unwrap());
+///
+/// let memo = bld::trace(&cube, &BldOptions::default()).unwrap();
+/// assert_eq!(memo.to_string(), "edges: D\ncorners: BC B\nparity");
+/// ```
+pub fn trace(cube: &Cube, options: &BldOptions) -> Result<Memo, StateError> {
+    CubieCube::from_cube(cube)?;
+    Ok(Memo {
+        scheme: options.scheme.clone(),
+        corners: Stickers::corners().trace(cube, options.corner_buffer),
+        edges: Stickers::edges().trace(cube, options.edge_buffer),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scrambled;
+
+    #[test]
+    fn letters_stickers_like_speffz() {
+        // A is the back left corner of the top, C the front right.
+        assert_eq!(facelet::<3>(0), 0);
+        assert_eq!(facelet::<3>(2), 8);
+        // E is the top left of the left face, U the front left of the
+        // bottom.
+        assert_eq!(facelet::<3>(4), 36);
+        assert_eq!(facelet::<3>(20), 27);
+        // B is the right edge of the top.
+        assert_eq!(facelet::<2>(1), 5);
+    }
+
+    #[test]
+    fn traces_cycles_from_the_buffer() {
+        assert_eq!(
+            trace(&Cube::default(), &BldOptions::default())
+                .unwrap()
+                .to_string(),
+            "edges: none\ncorners: none"
+        );
+
+        // U brings UB to UR, and UFL to ULB.
+        let memo = trace(&scrambled("U"), &BldOptions::default()).unwrap();
+        assert_eq!(memo.to_string(), "edges: AD C\ncorners: DC B\nparity");
+
+        // R2 swaps UR with DR, and FR with BR, which takes a cycle break.
+        let memo = trace(&scrambled("R2"), &BldOptions::default()).unwrap();
+        assert_eq!(memo.edges.targets.len(), 4);
+        assert!(!memo.parity());
+    }
+
+    #[test]
+    fn lists_pieces_turned_in_place() {
+        // The superflip's edges are all flipped in place.
+        let superflip = "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2";
+        let memo = trace(&scrambled(superflip), &BldOptions::default()).unwrap();
+        assert!(memo.edges.targets.is_empty());
+        assert_eq!(memo.edges.misoriented.len(), 11);
+
+        // The URF corner turned counterclockwise, then UBR clockwise.
+        let sexy = "R' D' R D R' D' R D";
+        let twists = format!("{} U {} {} U'", sexy, sexy, sexy);
+        let memo = trace(&scrambled(&twists), &BldOptions::default()).unwrap();
+        assert_eq!(
+            memo.to_string(),
+            "edges: none\ncorners: none\ntwisted: URF counterclockwise, UBR clockwise"
+        );
+
+        // Twists of the buffer are left to the others.
+        let options = BldOptions {
+            scheme: Scheme::parse(&"abcdefghijklmnopqrstuvwx".repeat(2)).unwrap(),
+            corner_buffer: 0,
+            edge_buffer: 1,
+        };
+        let memo = trace(&scrambled(&twists), &options).unwrap();
+        assert_eq!(memo.corners.misoriented.len(), 1);
+        let memo = trace(&scrambled("U"), &options).unwrap();
+        assert!(memo.to_string().starts_with("edges: ba d\n"));
+    }
+}
@@ -0,0 +1,1279 @@
+//! The `cube` command line interface.
+
+use cube::{
+    algoritm::{Algorithm, Equivalence},
+    ansi,
+    bld::{self, BldOptions, Scheme},
+    cross::{self, CrossOptions},
+    cstimer,
+    cubie::StateError,
+    database::{Database, Set},
+    f2l::{self, F2lOptions, Pairs, Slot},
+    iso::{self, IsoOptions},
+    last_layer::{self, RecognitionError},
+    masked::{self, MaskedCube},
+    playback::{self, Playback, PlaybackOptions},
+    pochmann::{self, Method},
+    reconstruction::Reconstruction,
+    repl::{self, Repl},
+    roux::{self, RouxError, RouxOptions},
+    schedule::Schedule,
+    scramble::{self, Rng},
+    search::{self, Mask, SearchOptions},
+    session::{self, Session, Time},
+    solver, svg, timer,
+    trainer::{self, Trainer, Training},
+    tui,
+    zz::{self, Goal},
+    Colour, Cube, FaceletError,
+};
+use std::{
+    fmt::{self, Display, Formatter},
+    fs,
+    io::{self, BufRead, IsTerminal, Write},
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+pub const USAGE: &str = "\
+usage: cube <command> [options]
+
+commands:
+  apply [--state <facelets>] [alg]   print the facelets after applying alg
+  invert [alg]                       print the inverse of alg
+  simplify [alg]                     merge and cancel moves in alg
+  scramble [--count <n>] [--seed <n>]
+                                     print random state scrambles
+  solve [--state <facelets>] [alg]   solve the state, or the scramble alg
+  case [--state <facelets>] [alg]    name the OLL or PLL case once the first
+                                     two layers are solved
+  cross [--colour <letters>|all] [--in-place] [--limit <n>]
+        [--state <facelets>] [alg]   print the optimal crosses of the
+                                     shortest of the colours, white by
+                                     default, rotated to the bottom
+  f2l [--pairs <n>|--slot <fr|br|bl|fl>] [--slack <n>] [cross options]
+                                     print the shortest crosses solving n
+                                     pairs too, or ways to insert the pair
+                                     of the slot, easiest first
+  roux [--step fb|sb|cmll|lse] [cross options]
+                                     print the shortest first blocks, of
+                                     the colours on the bottom, or second
+                                     blocks in R r U M, the CMLL case, or
+                                     the last six edges in M U
+  zz [--step eo|eoline|eocross] [cross options]
+                                     print the bad edges, or the shortest
+                                     EOLines or EOCrosses of the colours
+  find [--moves <layers>] [--mask <pieces>|--target <facelets>]
+       [--length <n>] [--limit <n>] [--state <facelets>] [alg]
+                                     print algorithms turning only the
+                                     layers, R U by default, that solve the
+                                     pieces of the mask, all by default, or
+                                     reach the target, whose stickers
+                                     written X can be any colour
+  bld [--letters <letters>] [--buffers <corner>,<edge>|--method op|m2]
+      [--state <facelets>] [alg]     print the letter pairs of the edges and
+                                     corners, traced from the buffers, ULB
+                                     and UR by default, lettered by Speffz
+                                     or the 24 or 48 letters given, and a
+                                     solution by Old Pochmann or M2/R2,
+                                     target by target
+  render [--format ascii|ansi|svg] [--view net|top|iso] [--back]
+         [--state <facelets>] [alg]  draw the state after applying alg
+  play [--delay <ms>] [alg]          step through alg move by move, or play
+                                     it with a delay that keys can pause
+  repl                               turn a cube interactively
+  tui                                turn a cube from the keyboard, timed
+  timer [--session <file>]           time solves with WCA inspection,
+                                     adding them to the session file, or
+                                     printing them when done
+  train <oll|pll|coll|zbll> [--case <name>] [--schedule <file>]
+                                     drill last layer cases with a timer,
+                                     checking how each was solved, and
+                                     picking cases due for review from the
+                                     schedule file
+  stats <file>                       averages and personal bests of a session
+  import [--dir <dir>] <json>        turn a csTimer export into session files
+  export <file>...                   print session files as a csTimer export
+  algs <oll|pll|f2l|coll> [case]     list the algorithms of a set, or one
+  group [--rotation] [--auf] [file]  group algorithms, one per line, by what
+                                     they do, maybe overlooking the final
+                                     rotation or U turn
+  recon --scramble <alg> [--time <t>] [file]
+                                     check a step by step reconstruction
+                                     and count its moves
+  help                               print this message
+
+An alg that isn't given as arguments is read from stdin, as is the state
+given as `--state -`. Facelets are 54 colour letters (W Y G B R O), face by
+face in the order U R F D L B.
+
+exit codes: 1 usage, 2 unreadable algorithm or facelets, 3 impossible or
+unrecognised state, 4 input or output failed, 5 the reconstruction or
+blindfolded solution doesn't solve";
+
+/// The averages `stats` reports.
+const AVERAGES: [usize; 3] = [5, 12, 100];
+
+/// Options taking a value, every other option is a switch.
+const VALUE_OPTIONS: [&str; 25] = [
+    "--state",
+    "--count",
+    "--seed",
+    "--format",
+    "--view",
+    "--delay",
+    "--session",
+    "--dir",
+    "--scramble",
+    "--time",
+    "--case",
+    "--schedule",
+    "--colour",
+    "--limit",
+    "--slack",
+    "--pairs",
+    "--slot",
+    "--step",
+    "--moves",
+    "--mask",
+    "--length",
+    "--target",
+    "--letters",
+    "--buffers",
+    "--method",
+];
+
+#[derive(Debug)]
+pub enum Error {
+    Usage(String),
+    Parse(String),
+    State(StateError),
+    Case(RecognitionError),
+    /// A Roux step whose earlier steps aren't done.
+    Roux(RouxError),
+    Io(io::Error),
+    /// A reconstruction that leaves the cube unsolved.
+    Unsolved,
+}
+
+impl Error {
+    pub fn code(&self) -> i32 {
+        match self {
+            Self::Usage(_) => 1,
+            Self::Parse(_) => 2,
+            Self::State(_) | Self::Case(_) | Self::Roux(_) => 3,
+            Self::Io(_) => 4,
+            Self::Unsolved => 5,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Usage(message) => write!(f, "{}\n\n{}", message, USAGE),
+            Self::Parse(message) => write!(f, "{}", message),
+            Self::State(e) => write!(f, "impossible state: {}", e),
+            Self::Case(e) => write!(f, "{}", e),
+            Self::Roux(e) => write!(f, "{}", e),
+            Self::Io(e) => write!(f, "{}", e),
+            Self::Unsolved => write!(f, "the moves don't solve the scramble"),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<StateError> for Error {
+    fn from(e: StateError) -> Self {
+        Self::State(e)
+    }
+}
+
+impl From<RecognitionError> for Error {
+    fn from(e: RecognitionError) -> Self {
+        Self::Case(e)
+    }
+}
+
+impl From<RouxError> for Error {
+    fn from(e: RouxError) -> Self {
+        match e {
+            RouxError::State(e) => Self::State(e),
+            RouxError::Case(e) => Self::Case(e),
+            e => Self::Roux(e),
+        }
+    }
+}
+
+impl From<FaceletError> for Error {
+    fn from(e: FaceletError) -> Self {
+        Self::Parse(format!("couldn't read the facelets: {}", e))
+    }
+}
+
+/// The arguments after the command name.
+struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, Option<String>)>,
+}
+
+impl Args {
+    fn parse(args: &[String]) -> Result<Self, Error> {
+        let mut parsed = Self {
+            positional: Vec::new(),
+            options: Vec::new(),
+        };
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            if VALUE_OPTIONS.contains(&arg.as_str()) {
+                let value = args
+                    .next()
+                    .ok_or_else(|| Error::Usage(format!("{} needs a value", arg)))?;
+                parsed.options.push((arg.clone(), Some(value.clone())));
+            } else if arg.starts_with("--") {
+                parsed.options.push((arg.clone(), None));
+            } else {
+                parsed.positional.push(arg.clone());
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    /// Fails on any option the command doesn't know.
+    fn allow(&self, known: &[&str]) -> Result<(), Error> {
+        match self
+            .options
+            .iter()
+            .find(|(name, _)| !known.contains(&name.as_str()))
+        {
+            Some((name, _)) => Err(Error::Usage(format!("unknown option {}", name))),
+            None => Ok(()),
+        }
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    fn switch(&self, name: &str) -> bool {
+        self.options.iter().any(|(n, _)| n == name)
+    }
+
+    fn number<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, Error> {
+        match self.value(name) {
+            Some(value) => value
+                .parse()
+                .map(Some)
+                .map_err(|_| Error::Usage(format!("{} needs a number, not `{}`", name, value))),
+            None => Ok(None),
+        }
+    }
+}
+
+pub struct Io<'a> {
+    pub input: &'a mut dyn BufRead,
+    pub output: &'a mut dyn Write,
+}
+
+impl<'a> Io<'a> {
+    fn read_input(&mut self) -> Result<String, Error> {
+        let mut source = String::new();
+        self.input.read_to_string(&mut source)?;
+        Ok(source)
+    }
+
+    /// The algorithm given as arguments, or else on the input.
+    fn algorithm(&mut self, args: &Args) -> Result<Algorithm, Error> {
+        let source = if args.positional.is_empty() {
+            self.read_input()?
+        } else {
+            args.positional.join(" ")
+        };
+
+        Algorithm::parse(&source)
+            .ok_or_else(|| Error::Parse(format!("couldn't read the algorithm `{}`", source.trim())))
+    }
+
+    /// The state given with `--state`, with the algorithm applied to it.
+    /// Without a state, the algorithm is applied to a solved cube.
+    fn state(&mut self, args: &Args) -> Result<Cube, Error> {
+        let mut cube = match args.value("--state") {
+            Some("-") => Cube::parse_facelets(&self.read_input()?)?,
+            Some(facelets) => Cube::parse_facelets(facelets)?,
+            None => Cube::default(),
+        };
+
+        if args.value("--state").is_none() || !args.positional.is_empty() {
+            cube.execute_algorithm(self.algorithm(args)?);
+        }
+
+        Ok(cube)
+    }
+}
+
+fn apply(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&["--state"])?;
+    let cube = io.state(args)?;
+    writeln!(io.output, "{}", cube.to_facelet_string())?;
+    Ok(())
+}
+
+fn invert(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&[])?;
+    let alg = io.algorithm(args)?;
+    writeln!(io.output, "{}", alg.inverse())?;
+    Ok(())
+}
+
+fn simplify(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&[])?;
+    let alg = io.algorithm(args)?;
+    writeln!(io.output, "{}", alg.simplify())?;
+    Ok(())
+}
+
+fn scramble(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&["--count", "--seed"])?;
+    let count = args.number("--count")?.unwrap_or(1);
+    let mut rng = match args.number("--seed")? {
+        Some(seed) => Rng::new(seed),
+        None => Rng::from_time(),
+    };
+
+    for _ in 0..count {
+        writeln!(io.output, "{}", scramble::scramble(&mut rng))?;
+    }
+    Ok(())
+}
+
+fn solve(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&["--state"])?;
+    let cube = io.state(args)?;
+    writeln!(io.output, "{}", solver::solve(&cube)?)?;
+    Ok(())
+}
+
+fn case(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&["--state"])?;
+    let cube = io.state(args)?;
+    match last_layer::recognise(&cube)? {
+        Some(recognition) => writeln!(io.output, "{}", recognition)?,
+        None => writeln!(io.output, "solved")?,
+    }
+    Ok(())
+}
+
+fn cross(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&["--state", "--colour", "--in-place", "--limit"])?;
+    let options = CrossOptions {
+        rotate: !args.switch("--in-place"),
+        limit: args
+            .number("--limit")?
+            .unwrap_or(CrossOptions::default().limit),
+    };
+    let cube = io.state(args)?;
+    for cross in cross::solve_neutral(&cube, &colours(args, &cube)?, &options)? {
+        writeln!(io.output, "{}", cross)?;
+    }
+    Ok(())
+}
+
+fn f2l(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&[
+        "--state",
+        "--colour",
+        "--in-place",
+        "--limit",
+        "--slack",
+        "--pairs",
+        "--slot",
+    ])?;
+    let defaults = F2lOptions::default();
+    let options = F2lOptions {
+        rotate: !args.switch("--in-place"),
+        limit: args.number("--limit")?.unwrap_or(defaults.limit),
+        slack: args.number("--slack")?.unwrap_or(defaults.slack),
+    };
+    let slot = match args.value("--slot") {
+        Some(name) => {
+            Some(Slot::parse(name).ok_or_else(|| Error::Usage(format!("no slot `{}`", name)))?)
+        }
+        None => None,
+    };
+    let pairs = args.number("--pairs")?.unwrap_or(1);
+    let pairs = Pairs::new(pairs)
+        .ok_or_else(|| Error::Usage(format!("--pairs needs 1 to 4, not {}", pairs)))?;
+    let cube = io.state(args)?;
+
+    let mut solutions = Vec::new();
+    for colour in colours(args, &cube)? {
+        solutions.extend(match slot {
+            Some(slot) => f2l::insert_pair(&cube, colour, slot, &options)?,
+            None => f2l::xcross(&cube, colour, pairs, &options)?,
+        });
+    }
+    f2l::rank(&mut solutions);
+    for solution in solutions.iter().take(options.limit) {
+        writeln!(io.output, "{}", solution)?;
+    }
+    Ok(())
+}
+
+fn roux(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&["--state", "--step", "--colour", "--in-place", "--limit"])?;
+    let options = RouxOptions {
+        rotate: !args.switch("--in-place"),
+        limit: args
+            .number("--limit")?
+            .unwrap_or(RouxOptions::default().limit),
+    };
+    let cube = io.state(args)?;
+    let steps = match args.value("--step").unwrap_or("fb") {
+        "fb" => roux::first_block(&cube, &colours(args, &cube)?, &options)?,
+        "sb" => roux::second_block(&cube, &options)?,
+        "cmll" => {
+            match roux::recognise_cmll(&cube)? {
+                Some(cmll) => writeln!(io.output, "{}", cmll)?,
+                None => writeln!(io.output, "solved")?,
+            }
+            return Ok(());
+        }
+        "lse" => roux::last_six_edges(&cube, &options)?,
+        step => return Err(Error::Usage(format!("no Roux step `{}`", step))),
+    };
+    for step in steps {
+        writeln!(io.output, "{}", step)?;
+    }
+    Ok(())
+}
+
+fn zz(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&["--state", "--step", "--colour", "--in-place", "--limit"])?;
+    let options = CrossOptions {
+        rotate: !args.switch("--in-place"),
+        limit: args
+            .number("--limit")?
+            .unwrap_or(CrossOptions::default().limit),
+    };
+    let cube = io.state(args)?;
+    let goal = match args.value("--step").unwrap_or("eoline") {
+        "eo" => {
+            writeln!(io.output, "{}", cube.edge_orientation()?)?;
+            return Ok(());
+        }
+        "eoline" => Goal::EoLine,
+        "eocross" => Goal::EoCross,
+        step => return Err(Error::Usage(format!("no ZZ step `{}`", step))),
+    };
+    for solution in zz::solve(&cube, &colours(args, &cube)?, goal, &options)? {
+        writeln!(io.output, "{}", solution)?;
+    }
+    Ok(())
+}
+
+fn find(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&[
+        "--state", "--moves", "--mask", "--target", "--length", "--limit",
+    ])?;
+    let layers = args.value("--moves").unwrap_or("R U");
+    let layers = Algorithm::parse(layers)
+        .ok_or_else(|| Error::Usage(format!("`{}` aren't moves", layers)))?;
+    let moves = search::turns(&layers);
+    let defaults = SearchOptions::default();
+    let options = SearchOptions {
+        max_length: args.number("--length")?.unwrap_or(defaults.max_length),
+        limit: args.number("--limit")?.unwrap_or(defaults.limit),
+    };
+    let found = match (args.value("--mask"), args.value("--target")) {
+        (Some(_), Some(_)) => {
+            return Err(Error::Usage(
+                "give a mask or a target, not both".to_string(),
+            ))
+        }
+        (None, Some(target)) => {
+            let target = MaskedCube::parse(target)?;
+            masked::find(&io.state(args)?, &target, &moves, &options)?
+        }
+        (mask, None) => {
+            let mask = mask.unwrap_or("all");
+            let mask = Mask::parse(mask)
+                .ok_or_else(|| Error::Usage(format!("`{}` aren't pieces", mask)))?;
+            search::find(&io.state(args)?, &moves, &mask, &|_| true, &options)?
+        }
+    };
+    for alg in found {
+        writeln!(io.output, "{}", alg)?;
+    }
+    Ok(())
+}
+
+fn bld(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&["--state", "--letters", "--buffers", "--method"])?;
+    let scheme = match args.value("--letters") {
+        Some(letters) => Scheme::parse(letters)
+            .ok_or_else(|| Error::Usage(format!("`{}` isn't a lettering scheme", letters)))?,
+        None => Scheme::speffz(),
+    };
+    let method = match args.value("--method") {
+        Some(name) => {
+            Some(Method::parse(name).ok_or_else(|| Error::Usage(format!("no method `{}`", name)))?)
+        }
+        None => None,
+    };
+    let mut options = match method {
+        Some(method) => method.options(scheme),
+        None => BldOptions {
+            scheme,
+            ..BldOptions::default()
+        },
+    };
+    if let Some(buffers) = args.value("--buffers") {
+        if method.is_some() {
+            return Err(Error::Usage("a method has its own buffers".to_string()));
+        }
+        let mask = Mask::parse(buffers)
+            .filter(|mask| {
+                mask.corners.iter().filter(|&&c| c).count() == 1
+                    && mask.edges.iter().filter(|&&e| e).count() == 1
+            })
+            .ok_or_else(|| Error::Usage(format!("`{}` isn't a corner and an edge", buffers)))?;
+        options.corner_buffer = mask.corners.iter().position(|&c| c).unwrap();
+        options.edge_buffer = mask.edges.iter().position(|&e| e).unwrap();
+    }
+    let cube = io.state(args)?;
+    writeln!(io.output, "{}", bld::trace(&cube, &options)?)?;
+    if let Some(method) = method {
+        let solution = pochmann::solve(&cube, method, &options.scheme)?;
+        writeln!(io.output, "\n{}", solution)?;
+        if !solution.solves(&cube) {
+            return Err(Error::Unsolved);
+        }
+    }
+    Ok(())
+}
+
+/// The colours in `--colour`, white if there are none.
+fn colours(args: &Args, cube: &Cube) -> Result<Vec<Colour>, Error> {
+    Ok(match args.value("--colour") {
+        None => vec![Colour::White],
+        Some("all") => Cube::FACELET_ORDER
+            .iter()
+            .map(|side| cube.get_face(side.clone()).stickers()[4])
+            .collect(),
+        Some(letters) => letters
+            .chars()
+            .map(|letter| Colour::parse(letter.to_ascii_uppercase()))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| Error::Usage(format!("`{}` aren't colours", letters)))?,
+    })
+}
+
+fn render(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&["--state", "--format", "--view", "--back"])?;
+    let format = args.value("--format").unwrap_or("ascii");
+    let view = args.value("--view").unwrap_or("net");
+    let cube = io.state(args)?;
+
+    let iso = |colour| {
+        iso::render(
+            &cube,
+            &IsoOptions {
+                colour,
+                back: args.switch("--back"),
+            },
+        )
+    };
+    let out = match (format, view) {
+        ("ascii", "net") => cube.to_string(),
+        ("ansi", "net") => ansi::net(&cube),
+        ("ascii", "iso") => iso(false),
+        ("ansi", "iso") => iso(true),
+        ("svg", "net") => svg::net(&cube, &svg::SvgOptions::default()),
+        ("svg", "top") => svg::top_face(&cube, &svg::SvgOptions::default()),
+        _ => {
+            return Err(Error::Usage(format!(
+                "can't render the {} view as {}",
+                view, format
+            )))
+        }
+    };
+
+    write!(io.output, "{}", out)?;
+    Ok(())
+}
+
+fn play(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&["--delay"])?;
+    let terminal = io::stdout().is_terminal();
+    let options = PlaybackOptions {
+        delay: args.number("--delay")?.map(Duration::from_millis),
+        colour: terminal,
+        clear: terminal,
+    };
+    let alg = if args.positional.is_empty() {
+        // The input is needed for the commands.
+        let mut line = String::new();
+        io.input.read_line(&mut line)?;
+        Algorithm::parse(&line)
+            .ok_or_else(|| Error::Parse(format!("couldn't read the algorithm `{}`", line.trim())))?
+    } else {
+        io.algorithm(args)?
+    };
+
+    let playback = Playback::new(Cube::default(), alg);
+    match options.delay {
+        Some(delay) if io::stdin().is_terminal() => {
+            playback::run_live(playback, delay, &options, &mut *io.input, &mut *io.output)?
+        }
+        _ => playback::run(playback, &options, &mut *io.input, &mut *io.output)?,
+    }
+    Ok(())
+}
+
+fn repl(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&[])?;
+    let mut repl = Repl::new(io::stdout().is_terminal());
+    repl::run(&mut repl, &mut *io.input, &mut *io.output)?;
+    Ok(())
+}
+
+fn tui(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&[])?;
+    if !io::stdin().is_terminal() {
+        return Err(Error::Usage("tui needs a terminal".to_string()));
+    }
+    tui::run(&mut *io.input, &mut *io.output)?;
+    Ok(())
+}
+
+fn timer(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&["--session"])?;
+    if !io::stdin().is_terminal() {
+        return Err(Error::Usage("timer needs a terminal".to_string()));
+    }
+
+    let path = args.value("--session");
+    let solves = timer::run(&mut *io.input, &mut *io.output, |solve| match path {
+        Some(path) => Session::append(path, solve),
+        None => Ok(()),
+    })?;
+    if path.is_none() {
+        write!(io.output, "{}", Session::new(solves))?;
+    }
+    Ok(())
+}
+
+fn train(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&["--case", "--schedule"])?;
+    let training = match args.positional.as_slice() {
+        [training] => Training::parse(training)
+            .ok_or_else(|| Error::Usage(format!("can't train `{}`", training)))?,
+        _ => {
+            return Err(Error::Usage(
+                "train needs oll, pll, coll or zbll".to_string(),
+            ))
+        }
+    };
+    let case = args.value("--case");
+
+    // A schedule that doesn't exist yet starts out empty.
+    let path = args.value("--schedule");
+    let schedule = match path.map(fs::read_to_string) {
+        Some(Ok(source)) => Some(
+            Schedule::parse(&source)
+                .map_err(|e| Error::Parse(format!("{}: {}", path.unwrap_or_default(), e)))?,
+        ),
+        Some(Err(e)) if e.kind() == io::ErrorKind::NotFound => Some(Schedule::default()),
+        Some(Err(e)) => return Err(e.into()),
+        None => None,
+    };
+    if !io::stdin().is_terminal() {
+        return Err(Error::Usage("train needs a terminal".to_string()));
+    }
+
+    let mut trainer = Trainer::new(training, case, Rng::from_time()).ok_or_else(|| {
+        Error::Usage(format!(
+            "{} has no case `{}`",
+            training,
+            case.unwrap_or_default()
+        ))
+    })?;
+    if let Some(schedule) = schedule {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        trainer = trainer.with_schedule(schedule, now);
+    }
+    // The reviews so far are saved even if the terminal gave out.
+    let result = trainer::run(&mut *io.input, &mut *io.output, &mut trainer);
+    if let (Some(path), Some(schedule)) = (path, trainer.schedule()) {
+        schedule.save(path)?;
+    }
+    result?;
+    write!(io.output, "{}", trainer.report())?;
+    Ok(())
+}
+
+fn stats(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&[])?;
+    let path = match args.positional.as_slice() {
+        [path] => path,
+        _ => return Err(Error::Usage("stats needs one session file".to_string())),
+    };
+    let source = fs::read_to_string(path)?;
+    let session = Session::parse(&source).map_err(|e| Error::Parse(format!("{}: {}", path, e)))?;
+
+    let show = |time: Option<Time>| time.map_or("-".to_string(), |time| time.to_string());
+    let dnfs = session
+        .results()
+        .iter()
+        .filter(|&&time| time == Time::Dnf)
+        .count();
+    writeln!(io.output, "solves: {} ({} DNF)", session.solves.len(), dnfs)?;
+    writeln!(io.output, "mean:   {}", show(session.mean()))?;
+    writeln!(io.output, "best:   {}", show(session.best()))?;
+    writeln!(io.output, "worst:  {}", show(session.worst()))?;
+    writeln!(io.output, "\n        current   best")?;
+    for &count in AVERAGES.iter() {
+        writeln!(
+            io.output,
+            "ao{:<5} {:<9} {}",
+            count,
+            show(session.current_average(count)),
+            show(session.best_average(count))
+        )?;
+    }
+
+    let ao5 = session.rolling_averages(5);
+    let ao12 = session.rolling_averages(12);
+    writeln!(io.output, "\n    #  time        ao5       ao12")?;
+    for (i, solve) in session.solves.iter().enumerate() {
+        writeln!(
+            io.output,
+            "{:>5}  {:<11} {:<9} {}",
+            i + 1,
+            solve.to_string(),
+            show(ao5[i]),
+            show(ao12[i])
+        )?;
+    }
+
+    writeln!(io.output, "\npersonal bests:")?;
+    let singles = session::personal_bests(&session.results());
+    let ao5: Vec<Time> = ao5
+        .into_iter()
+        .map(|time| time.unwrap_or(Time::Dnf))
+        .collect();
+    let ao12: Vec<Time> = ao12
+        .into_iter()
+        .map(|time| time.unwrap_or(Time::Dnf))
+        .collect();
+    for (name, bests) in [
+        ("single", singles),
+        ("ao5", session::personal_bests(&ao5)),
+        ("ao12", session::personal_bests(&ao12)),
+    ]
+    .iter()
+    {
+        let bests: Vec<String> = bests
+            .iter()
+            .map(|(i, time)| format!("{} (#{})", time, i + 1))
+            .collect();
+        if bests.is_empty() {
+            writeln!(io.output, "{:<7} -", name)?;
+        } else {
+            writeln!(io.output, "{:<7} {}", name, bests.join(", "))?;
+        }
+    }
+    Ok(())
+}
+
+/// A file name for a session, keeping only the characters that are safe
+/// everywhere.
+fn session_file(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' => c,
+            _ => '_',
+        })
+        .collect();
+    format!("{}.txt", name)
+}
+
+fn import(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&["--dir"])?;
+    let path = match args.positional.as_slice() {
+        [path] => path,
+        _ => return Err(Error::Usage("import needs one csTimer export".to_string())),
+    };
+    let dir = Path::new(args.value("--dir").unwrap_or("."));
+
+    let source = fs::read_to_string(path)?;
+    let import = cstimer::import(&source).map_err(|e| Error::Parse(format!("{}: {}", path, e)))?;
+
+    for (name, session) in &import.sessions {
+        let file = dir.join(session_file(name));
+        // Never overwrite a session, it may have solves csTimer doesn't.
+        fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&file)
+            .and_then(|mut out| out.write_all(session.to_string().as_bytes()))
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", file.display(), e)))?;
+        writeln!(
+            io.output,
+            "{}: {} solves",
+            file.display(),
+            session.solves.len()
+        )?;
+    }
+    for rejected in &import.rejected {
+        writeln!(io.output, "skipped {}", rejected)?;
+    }
+    Ok(())
+}
+
+fn export(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&[])?;
+    if args.positional.is_empty() {
+        return Err(Error::Usage("export needs session files".to_string()));
+    }
+
+    let mut sessions = Vec::new();
+    for path in &args.positional {
+        let source = fs::read_to_string(path)?;
+        let session =
+            Session::parse(&source).map_err(|e| Error::Parse(format!("{}: {}", path, e)))?;
+        let name = Path::new(path)
+            .file_stem()
+            .map_or(path.clone(), |stem| stem.to_string_lossy().into_owned());
+        sessions.push((name, session));
+    }
+
+    writeln!(io.output, "{}", cstimer::export(&sessions))?;
+    Ok(())
+}
+
+fn algs(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&[])?;
+    let (set, name) = match args.positional.as_slice() {
+        [set] => (set, None),
+        [set, name] => (set, Some(name)),
+        _ => return Err(Error::Usage("algs needs a set".to_string())),
+    };
+    let set = Set::parse(set).ok_or_else(|| Error::Usage(format!("unknown set `{}`", set)))?;
+
+    let database = Database::builtin();
+    let entries: Vec<_> = match name {
+        Some(name) => vec![database
+            .get(set, name)
+            .ok_or_else(|| Error::Usage(format!("{} has no case `{}`", set, name)))?],
+        None => database.set(set).collect(),
+    };
+    let width = entries
+        .iter()
+        .map(|entry| entry.name.len())
+        .max()
+        .unwrap_or(0);
+    for entry in entries {
+        writeln!(
+            io.output,
+            "{:<width$}  {}",
+            entry.name,
+            entry.algorithm,
+            width = width
+        )?;
+    }
+    Ok(())
+}
+
+fn group(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&["--rotation", "--auf"])?;
+    let modulo = Equivalence {
+        rotation: args.switch("--rotation"),
+        auf: args.switch("--auf"),
+    };
+    let (name, source) = match args.positional.as_slice() {
+        [] => ("stdin".to_string(), io.read_input()?),
+        [path] => (path.clone(), fs::read_to_string(path)?),
+        _ => return Err(Error::Usage("group reads one file".to_string())),
+    };
+
+    // Each group is kept with its first algorithm to compare against.
+    let mut groups: Vec<(Algorithm, Vec<&str>)> = Vec::new();
+    for (i, line) in source.lines().enumerate() {
+        let alg = Algorithm::parse(line).ok_or_else(|| {
+            Error::Parse(format!(
+                "{}:{}: couldn't read the algorithm `{}`",
+                name,
+                i + 1,
+                line.trim()
+            ))
+        })?;
+        if alg.moves.is_empty() {
+            continue;
+        }
+
+        match groups
+            .iter_mut()
+            .find(|(first, _)| first.equivalent_modulo(&alg, &modulo))
+        {
+            Some((_, lines)) => lines.push(line.trim()),
+            None => groups.push((alg, vec![line.trim()])),
+        }
+    }
+
+    for (i, (_, lines)) in groups.iter().enumerate() {
+        if i > 0 {
+            writeln!(io.output)?;
+        }
+        for line in lines {
+            writeln!(io.output, "{}", line)?;
+        }
+    }
+    Ok(())
+}
+
+fn recon(args: &Args, io: &mut Io) -> Result<(), Error> {
+    args.allow(&["--scramble", "--time"])?;
+    let scramble = args
+        .value("--scramble")
+        .ok_or_else(|| Error::Usage("recon needs --scramble".to_string()))?;
+    let scramble = Algorithm::parse(scramble)
+        .ok_or_else(|| Error::Parse(format!("couldn't read the scramble `{}`", scramble)))?;
+    let time = match args.value("--time") {
+        Some(time) => Some(
+            session::parse_time(time)
+                .ok_or_else(|| Error::Usage(format!("couldn't read the time `{}`", time)))?,
+        ),
+        None => None,
+    };
+
+    let (name, source) = match args.positional.as_slice() {
+        [] => ("stdin".to_string(), io.read_input()?),
+        [path] => (path.clone(), fs::read_to_string(path)?),
+        _ => return Err(Error::Usage("recon reads one file".to_string())),
+    };
+    let recon =
+        Reconstruction::parse(&source).map_err(|e| Error::Parse(format!("{}: {}", name, e)))?;
+
+    write!(io.output, "{}", recon.report(time))?;
+    if recon.solves(&scramble) {
+        Ok(())
+    } else {
+        Err(Error::Unsolved)
+    }
+}
+
+/// Runs the command in `args`, returning the exit code.
+pub fn run(args: &[String], io: &mut Io, error: &mut dyn Write) -> i32 {
+    let result = match args.split_first() {
+        Some((command, rest)) => Args::parse(rest).and_then(|rest| match command.as_str() {
+            "apply" => apply(&rest, io),
+            "invert" => invert(&rest, io),
+            "simplify" => simplify(&rest, io),
+            "scramble" => scramble(&rest, io),
+            "solve" => solve(&rest, io),
+            "case" => case(&rest, io),
+            "cross" => cross(&rest, io),
+            "f2l" => f2l(&rest, io),
+            "roux" => roux(&rest, io),
+            "zz" => zz(&rest, io),
+            "find" => find(&rest, io),
+            "bld" => bld(&rest, io),
+            "render" => render(&rest, io),
+            "play" => play(&rest, io),
+            "repl" => repl(&rest, io),
+            "tui" => tui(&rest, io),
+            "timer" => timer(&rest, io),
+            "train" => train(&rest, io),
+            "stats" => stats(&rest, io),
+            "import" => import(&rest, io),
+            "export" => export(&rest, io),
+            "algs" => algs(&rest, io),
+            "group" => group(&rest, io),
+            "recon" => recon(&rest, io),
+            "help" | "--help" | "-h" => writeln!(io.output, "{}", USAGE).map_err(Error::from),
+            other => Err(Error::Usage(format!("unknown command `{}`", other))),
+        }),
+        None => Err(Error::Usage("missing command".to_string())),
+    };
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            // Nothing sensible is left to do if stderr is gone too.
+            let _ = writeln!(error, "cube: {}", e);
+            e.code()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cube(args: &str, input: &str) -> (i32, String, String) {
+        let args: Vec<String> = args.split_whitespace().map(String::from).collect();
+        let mut input = input.as_bytes();
+        let (mut output, mut error) = (Vec::new(), Vec::new());
+        let code = run(
+            &args,
+            &mut Io {
+                input: &mut input,
+                output: &mut output,
+            },
+            &mut error,
+        );
+
+        (
+            code,
+            String::from_utf8(output).unwrap(),
+            String::from_utf8(error).unwrap(),
+        )
+    }
+
+    #[test]
+    fn algorithm_commands() {
+        assert_eq!(cube("invert R U R' U'", "").1, "U R U' R'\n");
+        assert_eq!(cube("simplify", "R R U U'\n").1, "R2\n");
+        assert_eq!(cube("invert R X", "").0, 2);
+        assert_eq!(cube("frobnicate", "").0, 1);
+        assert_eq!(cube("invert --what R", "").0, 1);
+    }
+
+    #[test]
+    fn apply_then_solve() {
+        let (code, facelets, _) = cube("apply R U R' U' F2", "");
+        assert_eq!(code, 0);
+
+        let (code, solution, _) = cube("solve --state -", &facelets);
+        assert_eq!(code, 0);
+
+        let mut state = Cube::parse_facelets(&facelets).unwrap();
+        state.execute_algorithm(Algorithm::parse(&solution).unwrap());
+        assert!(state.is_solved());
+    }
+
+    #[test]
+    fn invalid_states() {
+        let solved = Cube::default().to_facelet_string();
+        let flipped = format!("{}{}{}", &solved[..7], "G", &solved[8..19]) + "W" + &solved[20..];
+
+        assert_eq!(cube(&format!("solve --state {}", flipped), "").0, 3);
+        assert_eq!(cube("solve --state WWW", "").0, 2);
+    }
+
+    #[test]
+    fn recognise_cases() {
+        let t = "R U R' U' R' F R2 U' R' U' R U R' F'";
+        assert_eq!(
+            cube(&format!("case {}", t), "").1,
+            format!("PLL T: {}\n", t)
+        );
+        assert_eq!(
+            cube("case", "R U2 R' U' R U' R' U").1,
+            "OLL 27 after U': R U R' U R U2 R'\n"
+        );
+        assert_eq!(cube("case U", "").1, "solved\n");
+        assert_eq!(cube("case R", "").0, 3);
+    }
+
+    #[test]
+    fn render_formats() {
+        assert!(cube("render --format svg R", "").1.starts_with("<svg"));
+        assert!(cube("render --view iso R", "").1.contains('╱'));
+        assert_eq!(
+            cube("render --format ascii", "").1,
+            Cube::default().to_string()
+        );
+        assert_eq!(cube("render --format svg --view iso", "").0, 1);
+    }
+
+    #[test]
+    fn session_stats() {
+        let path = std::env::temp_dir().join(format!("cube-stats-{}.txt", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let session = "\
+10000 | OK | 2026-10-19T14:00:00Z | R | |
+10000 | +2 | 2026-10-19T14:01:00Z | U | |
+9000 | DNF | 2026-10-19T14:02:00Z | F | |
+11000 | OK | 2026-10-19T14:03:00Z | D | |
+13000 | OK | 2026-10-19T14:04:00Z | L | |
+";
+        std::fs::write(&path, session).unwrap();
+        let (code, report, _) = cube(&format!("stats {}", path), "");
+        std::fs::write(&path, session.replace("| D |", "| X |")).unwrap();
+        let (bad, _, error) = cube(&format!("stats {}", path), "");
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(code, 0);
+        assert!(report.contains("mean:   11.50"));
+        assert!(report.contains("ao5     12.00     12.00"));
+        assert!(report.contains("single  10.00 (#1)"));
+        assert_eq!(bad, 2);
+        assert!(error.contains("line 4: couldn't read the scramble `X`"));
+    }
+
+    #[test]
+    fn optimal_crosses() {
+        assert_eq!(cube("cross R D F", "").1, "W 2: x2 B' R'\n");
+        assert_eq!(cube("cross --in-place R D F", "").1, "W 2: F' R'\n");
+        assert_eq!(cube("cross --colour yb F", "").1, "B 0: x\n");
+        assert_eq!(cube("cross --colour wq", "").0, 1);
+    }
+
+    #[test]
+    fn roux_steps() {
+        assert_eq!(cube("roux --colour y L U' F D", "").1, "2: y2 D' B'\n");
+        assert_eq!(cube("roux --colour y --in-place L U' F", "").1, "1: F'\n");
+        assert_eq!(
+            cube("roux --step sb --limit 1 R U' r'", "").1,
+            "3: r U R'\n"
+        );
+        assert_eq!(cube("roux --step lse M2 U M2 U2", "").1, "4: U2 M2 U' M2\n");
+        assert_eq!(cube("roux --step cmll", "").1, "solved\n");
+        assert_eq!(cube("roux --step lse R", "").0, 3);
+        assert_eq!(cube("roux --step zz", "").0, 1);
+    }
+
+    #[test]
+    fn zz_steps() {
+        assert_eq!(cube("zz --step eo F", "").1, "4 bad: UF DF FR FL\n");
+        assert_eq!(cube("zz --step eo", "").1, "0 bad\n");
+        assert_eq!(cube("zz --colour y F R", "").1, "Y 1: y F'\n");
+        assert_eq!(
+            cube("zz --step eocross --colour y --in-place F R", "").1,
+            "Y 2: R' F'\n"
+        );
+        assert_eq!(cube("zz --step zz", "").0, 1);
+    }
+
+    #[test]
+    fn finds_algorithms() {
+        assert_eq!(
+            cube("find --moves RUF --limit 1 F R U R' U' F'", "").1,
+            "F U R U' R' F'\n"
+        );
+        assert_eq!(
+            cube("find --mask f2l --limit 1 R U R' U'", "").1,
+            "U R U' R'\n"
+        );
+        assert_eq!(cube("find --moves MU --length 3 M' U M", "").1, "M' U' M\n");
+        assert_eq!(cube("find --mask UX", "").0, 1);
+
+        let corner = "XXWXXXXXXXXGXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXRXXXXXXXX";
+        let target = format!("find --length 2 --target {}", corner);
+        assert_eq!(cube(&target, "").1, "U'\n");
+        assert_eq!(cube("find --target XXQ", "").0, 2);
+        assert_eq!(cube(&format!("{} --mask all", target), "").0, 1);
+    }
+
+    #[test]
+    fn bld_memo() {
+        assert_eq!(
+            cube("bld R U R' U' R' F R2 U' R' U' R U R' F'", "").1,
+            "edges: D\ncorners: BC B\nparity\n"
+        );
+        assert_eq!(
+            cube(
+                "bld --buffers urf,uf --letters abcdefghijklmnopqrstuvwx U",
+                ""
+            )
+            .1,
+            "edges: ba d\ncorners: ba d\nparity\n"
+        );
+        assert_eq!(cube("bld --buffers urf", "").0, 1);
+        assert_eq!(cube("bld --method op --buffers urf,uf", "").0, 1);
+        assert_eq!(cube("bld --letters aab", "").0, 1);
+    }
+
+    #[test]
+    fn bld_solutions() {
+        assert_eq!(
+            cube("bld --method op R U R' U' R' F R2 U' R' U' R U R' F'", "").1,
+            "edges: D\ncorners: BC B\nparity\n\nedge D: [T perm]\n"
+        );
+        let (code, out, _) = cube("bld --method m2 U", "");
+        assert_eq!(code, 0);
+        assert!(out.starts_with("edges: AD CB A\ncorners: BA D\nparity\n\ncorner B: "));
+        assert!(out.ends_with("parity: [M2] D' R L2 D' [Y perm] D L2 R' D\n"));
+        assert_eq!(cube("bld --method 3style", "").0, 1);
+    }
+
+    #[test]
+    fn f2l_solutions() {
+        assert_eq!(
+            cube("f2l --colour y --slot fr R U R'", "").1,
+            "Y 3 FR BR BL FL: R U' R'\n"
+        );
+        assert_eq!(
+            cube("f2l --colour y --pairs 2 --limit 1 R U R' F", "").1,
+            "Y 1 BR BL FL: F'\n"
+        );
+        assert_eq!(cube("f2l --slot ur", "").0, 1);
+        assert_eq!(cube("f2l --colour y --pairs 0 R U R' F", "").0, 1);
+        assert_eq!(cube("f2l --pairs 5", "").0, 1);
+    }
+
+    #[test]
+    fn algorithm_database() {
+        assert_eq!(
+            cube("algs pll T", "").1,
+            "T  R U R' U' R' F R2 U' R' U' R U R' F'\n"
+        );
+        assert_eq!(cube("algs f2l", "").1.lines().count(), 41);
+        assert!(cube("algs coll", "")
+            .1
+            .starts_with("H1    R U R' U R U' R' U R U2 R'\n"));
+        assert_eq!(cube("algs zbll", "").0, 1);
+        assert_eq!(cube("algs oll 58", "").0, 1);
+    }
+
+    #[test]
+    fn bad_schedules_are_reported() {
+        let path = std::env::temp_dir().join(format!("cube-schedule-{}.txt", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        fs::write(
+            &path,
+            "PLL T | 2.50 | 6 | 2 | 2026-10-19T14:03:12Z
+PLL Y | 2.50
+",
+        )
+        .unwrap();
+        let (code, _, error) = cube(&format!("train pll --schedule {}", path), "");
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(code, 2);
+        assert!(error.contains("line 2: expected a case and 4 fields"));
+    }
+
+    #[test]
+    fn group_algorithms() {
+        let algs = "R U R' U'\n// sexy\ny F U F' U' y'\nR U R' U R U2 R'\nR U R' U R U2 R' U\n";
+        assert_eq!(
+            cube("group", algs).1,
+            "R U R' U'\ny F U F' U' y'\n\nR U R' U R U2 R'\n\nR U R' U R U2 R' U\n"
+        );
+        assert_eq!(
+            cube("group --auf", algs).1,
+            "R U R' U'\ny F U F' U' y'\n\nR U R' U R U2 R'\nR U R' U R U2 R' U\n"
+        );
+        assert_eq!(
+            cube("group", "R\nR Q\n").2,
+            "cube: stdin:2: couldn't read the algorithm `R Q`\n"
+        );
+    }
+}
@@ -0,0 +1,428 @@
+//! Optimal crosses, the first step of CFOP, for planning them in
+//! inspection.
+//!
+//! The cross is solved on the bottom: the cube is turned so the cross colour
+//! is down, and the four edges around it are searched for with IDA*, pruned
+//! by a table of how far every placement of them is from solved. Since the
+//! table only follows those edges, it is exact and every optimal cross is
+//! found.
+
+use crate::{
+    algoritm::{Algorithm, Side},
+    cubie::{CubieCube, StateError},
+    solver::{self, MOVES, UNKNOWN},
+    Colour, Cube,
+};
+use std::{
+    fmt::{self, Display, Formatter},
+    sync::OnceLock,
+};
+
+/// The bottom edges DR, DF, DL and DB, the pieces of the cross.
+pub(crate) const CROSS_EDGES: [usize; 4] = [4, 5, 6, 7];
+
+/// An edge's place and flip, as `position * 2 + flip`.
+pub(crate) const EDGE_STATES: usize = 24;
+
+/// The optimal cross is never longer than this.
+pub const MAX_LENGTH: usize = 8;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CrossOptions {
+    /// Start every cross with the rotation bringing its colour to the
+    /// bottom, rather than solving it where it is.
+    pub rotate: bool,
+    /// The most solutions to give.
+    pub limit: usize,
+}
+
+impl Default for CrossOptions {
+    fn default() -> Self {
+        Self {
+            rotate: true,
+            limit: 10,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cross {
+    pub colour: Colour,
+    /// The rotation it starts with, if any.
+    pub rotation: Algorithm,
+    pub moves: Algorithm,
+}
+
+impl Cross {
+    /// The rotation followed by the moves.
+    pub fn algorithm(&self) -> Algorithm {
+        let mut moves = self.rotation.moves.clone();
+        moves.extend(self.moves.moves.iter().cloned());
+        Algorithm::new(moves)
+    }
+}
+
+/// Shows the colour, the move count and the algorithm, like
+/// `W 5: x2 R' D F2 U' L`.
+impl Display for Cross {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.colour, self.moves.move_count())?;
+        let algorithm = self.algorithm();
+        if !algorithm.moves.is_empty() {
+            write!(f, ": {}", algorithm)?;
+        }
+        Ok(())
+    }
+}
+
+/// The ways to place the cross edges: 12 * 11 * 10 * 9 places, times 16
+/// flips.
+pub(crate) const CROSSES: usize = 190_080;
+
+/// Where each edge state goes with each move, and the same for every
+/// placement of the cross edges, numbered from 0 to [`CROSSES`], with how
+/// many moves each is from solved.
+struct Tables {
+    edge_moves: [[u8; EDGE_STATES]; MOVES],
+    /// The number of each placement by its edge states as a base 24 number,
+    /// `u32::MAX` where two edges would be in one place.
+    numbers: Vec<u32>,
+    cross_moves: Vec<[u32; MOVES]>,
+    distance: Vec<u8>,
+}
+
+impl Tables {
+    fn new() -> Self {
+        let mut edge_moves = [[0; EDGE_STATES]; MOVES];
+        for (m, row) in edge_moves.iter_mut().enumerate() {
+            for (state, next) in row.iter_mut().enumerate() {
+                let (mut position, mut flip) = (state / 2, state % 2);
+                for _ in 0..=m % 3 {
+                    let turn = CubieCube::face_turn(m / 3);
+                    let to = turn
+                        .ep
+                        .iter()
+                        .position(|&p| p as usize == position)
+                        .unwrap();
+                    flip = (flip + turn.eo[to] as usize) % 2;
+                    position = to;
+                }
+                *next = (position * 2 + flip) as u8;
+            }
+        }
+
+        let raw = |states: &[u8; 4]| {
+            states
+                .iter()
+                .fold(0, |acc, state| acc * EDGE_STATES + *state as usize)
+        };
+        let mut placements = Vec::with_capacity(CROSSES);
+        let mut numbers = vec![u32::MAX; EDGE_STATES.pow(4)];
+        for (i, number) in numbers.iter_mut().enumerate() {
+            let mut states = [0; 4];
+            let mut rest = i;
+            for state in states.iter_mut().rev() {
+                *state = (rest % EDGE_STATES) as u8;
+                rest /= EDGE_STATES;
+            }
+            let places = states.map(|state| state / 2);
+            if (1..4).all(|j| !places[..j].contains(&places[j])) {
+                *number = placements.len() as u32;
+                placements.push(states);
+            }
+        }
+
+        let cross_moves: Vec<[u32; MOVES]> = placements
+            .iter()
+            .map(|states| {
+                let mut row = [0; MOVES];
+                for (m, next) in row.iter_mut().enumerate() {
+                    *next = numbers[raw(&states.map(|state| edge_moves[m][state as usize]))];
+                }
+                row
+            })
+            .collect();
+
+        let solved = numbers[raw(&CROSS_EDGES.map(|edge| (edge * 2) as u8))] as usize;
+        let distance = distances(&cross_moves, &[[0]; MOVES], solved);
+        Self {
+            edge_moves,
+            numbers,
+            cross_moves,
+            distance,
+        }
+    }
+
+    fn get() -> &'static Self {
+        static TABLES: OnceLock<Tables> = OnceLock::new();
+        TABLES.get_or_init(Tables::new)
+    }
+}
+
+/// Where each edge state goes with each move.
+pub(crate) fn edge_moves() -> &'static [[u8; EDGE_STATES]; MOVES] {
+    &Tables::get().edge_moves
+}
+
+/// The number of the placement of the cross edges in `states`.
+pub(crate) fn placement(states: &[u8; 4]) -> u32 {
+    let raw = states
+        .iter()
+        .fold(0, |acc, state| acc * EDGE_STATES + *state as usize);
+    Tables::get().numbers[raw]
+}
+
+/// Where each placement of the cross edges goes with each move.
+pub(crate) fn cross_moves() -> &'static [[u32; MOVES]] {
+    &Tables::get().cross_moves
+}
+
+/// How many moves the placement of the cross edges numbered `cross` is from
+/// solved.
+pub(crate) fn cross_distance(cross: u32) -> u8 {
+    Tables::get().distance[cross as usize]
+}
+
+/// Breadth first search from `solved`, over the placements of the cross
+/// edges together with one more piece that has `moves.len()` states and
+/// turns by `moves`, numbered `placement * moves.len() + state`. Gives how
+/// many moves each is from `solved`.
+pub(crate) fn distances<const N: usize>(
+    cross_moves: &[[u32; MOVES]],
+    moves: &[[u8; N]; MOVES],
+    solved: usize,
+) -> Vec<u8> {
+    let mut table = vec![UNKNOWN; cross_moves.len() * N];
+    table[solved] = 0;
+
+    let mut frontier = vec![solved];
+    let mut depth = 0;
+    while !frontier.is_empty() {
+        let mut next = Vec::new();
+        for i in frontier {
+            let (cross, piece) = (i / N, i % N);
+            for m in 0..MOVES {
+                let j = cross_moves[cross][m] as usize * N + moves[m][piece] as usize;
+                if table[j] == UNKNOWN {
+                    table[j] = depth + 1;
+                    next.push(j);
+                }
+            }
+        }
+        frontier = next;
+        depth += 1;
+    }
+
+    table
+}
+
+struct Search<'a> {
+    tables: &'a Tables,
+    moves: Vec<usize>,
+    found: Vec<Vec<usize>>,
+    limit: usize,
+}
+
+impl Search<'_> {
+    fn search(&mut self, cross: u32, left: usize) {
+        let distance = self.tables.distance[cross as usize] as usize;
+        if distance > left || self.found.len() >= self.limit {
+            return;
+        }
+        if left == 0 {
+            self.found.push(self.moves.clone());
+            return;
+        }
+
+        for m in 0..MOVES {
+            if !solver::allowed(m, self.moves.last().copied()) {
+                continue;
+            }
+            self.moves.push(m);
+            self.search(self.tables.cross_moves[cross as usize][m], left - 1);
+            self.moves.pop();
+        }
+    }
+}
+
+/// The place and flip of the edge `piece`.
+pub(crate) fn edge_state(cubie: &CubieCube, piece: usize) -> u8 {
+    let position = cubie.ep.iter().position(|&p| p as usize == piece).unwrap();
+    (position * 2 + cubie.eo[position] as usize) as u8
+}
+
+/// The rotation bringing `side` to the bottom.
+pub(crate) fn to_bottom(side: &Side) -> Algorithm {
+    let rotation = match side {
+        Side::Down => "",
+        Side::Up => "x2",
+        Side::Front => "x'",
+        Side::Back => "x",
+        Side::Right => "z",
+        Side::Left => "z'",
+    };
+    Algorithm::parse(rotation).expect("rotations parse")
+}
+
+/// Every optimal cross of `colour`, up to `options.limit` of them, and none
+/// if no centre has that colour.
+///
+/// ```
+/// # use cube::{algoritm::Algorithm, cross::{self, CrossOptions}, Colour, Cube};
+/// let mut cube = Cube::default();
+/// cube.execute_algorithm(Algorithm::parse("R D F").unwrap());
+///
+/// let crosses = cross::solve(&cube, Colour::White, &CrossOptions::default()).unwrap();
+/// assert_eq!(crosses[0].to_string(), "W 2: x2 B' R'");
+/// ```
+pub fn solve(
+    cube: &Cube,
+    colour: Colour,
+    options: &CrossOptions,
+) -> Result<Vec<Cross>, StateError> {
+    let side = match cube.centre_side(colour) {
+        Some(side) => side,
+        None => return Ok(Vec::new()),
+    };
+    let rotation = to_bottom(&side);
+    let mut turned = cube.clone();
+    turned.execute_algorithm(rotation.clone());
+    let cubie = CubieCube::from_cube(&turned)?;
+
+    let cross = placement(&CROSS_EDGES.map(|edge| edge_state(&cubie, edge)));
+    let mut search = Search {
+        tables: Tables::get(),
+        moves: Vec::new(),
+        found: Vec::new(),
+        limit: options.limit,
+    };
+    for depth in 0..=MAX_LENGTH {
+        search.search(cross, depth);
+        if !search.found.is_empty() {
+            break;
+        }
+    }
+
+    Ok(search
+        .found
+        .iter()
+        .map(|moves| {
+            let (rotation, moves) = placed(&rotation, solver::to_algorithm(moves), options.rotate);
+            Cross {
+                colour,
+                rotation,
+                moves,
+            }
+        })
+        .collect())
+}
+
+/// The rotation and the moves done after it, or with `rotate` off, just the
+/// moves turning the same faces without it.
+pub(crate) fn placed(
+    rotation: &Algorithm,
+    moves: Algorithm,
+    rotate: bool,
+) -> (Algorithm, Algorithm) {
+    if rotate {
+        return (rotation.clone(), moves);
+    }
+    let mut rotated = rotation.moves.clone();
+    rotated.extend(moves.moves);
+    (
+        Algorithm::new(Vec::new()),
+        Algorithm::new(rotated).without_rotations(),
+    )
+}
+
+/// The optimal crosses of whichever of `colours` has the shortest, up to
+/// `options.limit` of them, in the order of `colours`.
+pub fn solve_neutral(
+    cube: &Cube,
+    colours: &[Colour],
+    options: &CrossOptions,
+) -> Result<Vec<Cross>, StateError> {
+    let mut best: Vec<Cross> = Vec::new();
+    for colour in colours {
+        let crosses = solve(cube, *colour, options)?;
+        let length = |crosses: &[Cross]| crosses.first().map(|cross| cross.moves.move_count());
+        match (length(&crosses), length(&best)) {
+            (Some(new), Some(old)) if new < old => best = crosses,
+            (Some(new), Some(old)) if new == old => best.extend(crosses),
+            (Some(_), None) => best = crosses,
+            _ => {}
+        }
+    }
+    best.truncate(options.limit);
+    Ok(best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scrambled;
+
+    /// Whether the four edges around the `colour` centre are solved.
+    fn cross_solved(cube: &Cube, colour: Colour) -> bool {
+        let mut cube = cube.clone();
+        cube.execute_algorithm(to_bottom(&cube.centre_side(colour).unwrap()));
+        let cubie = CubieCube::from_cube(&cube).unwrap();
+        CROSS_EDGES
+            .iter()
+            .all(|&edge| cubie.ep[edge] as usize == edge && cubie.eo[edge] == 0)
+    }
+
+    #[test]
+    fn finds_every_optimal_cross() {
+        let scramble = "D2 F2 U' B2 R2 B2 R2 L B' D' F D2 F' L' U R' F' D' L2 B2";
+        let cube = scrambled(scramble);
+        let options = CrossOptions {
+            rotate: true,
+            limit: 100,
+        };
+
+        for colour in [Colour::White, Colour::Yellow, Colour::Green].iter() {
+            let crosses = solve(&cube, *colour, &options).unwrap();
+            assert!(!crosses.is_empty());
+            let length = crosses[0].moves.move_count();
+            for cross in &crosses {
+                assert_eq!(cross.moves.move_count(), length, "{}", cross);
+                let mut solved = cube.clone();
+                solved.execute_algorithm(cross.algorithm());
+                assert!(cross_solved(&solved, *colour), "{}", cross);
+            }
+            let algorithms: Vec<String> = crosses.iter().map(|c| c.to_string()).collect();
+            for (i, algorithm) in algorithms.iter().enumerate() {
+                assert!(!algorithms[..i].contains(algorithm));
+            }
+        }
+
+        // Undoing a short scramble is the only way to solve it that short.
+        let crosses = solve(&scrambled("R D F"), Colour::Yellow, &options).unwrap();
+        assert_eq!(crosses.len(), 1);
+        assert_eq!(crosses[0].to_string(), "Y 3: F' D' R'");
+    }
+
+    #[test]
+    fn solves_in_place_or_on_the_bottom() {
+        let cube = scrambled("F");
+        let options = CrossOptions {
+            rotate: false,
+            limit: 10,
+        };
+        let white = solve(&cube, Colour::White, &options).unwrap();
+        assert_eq!(white[0].to_string(), "W 1: F'");
+        let white = solve(&cube, Colour::White, &CrossOptions::default()).unwrap();
+        assert_eq!(white[0].to_string(), "W 1: x2 B'");
+
+        // The blue cross is the only one F leaves solved.
+        let neutral = solve_neutral(
+            &cube,
+            &[Colour::White, Colour::Green, Colour::Blue],
+            &options,
+        )
+        .unwrap();
+        let shown: Vec<String> = neutral.iter().map(|c| c.to_string()).collect();
+        assert_eq!(shown, vec!["B 0"]);
+    }
+}
@@ -0,0 +1,246 @@
+//! Sessions exported from and imported into csTimer.
+//!
+//! csTimer exports every session in one JSON object, with the solves of
+//! session `n` under `sessionn` and the names of the sessions in a JSON
+//! string under `properties.sessionData`. A solve is an array like
+//! `[[0, 12345], "R U ...", "comment", 1792418592]`: the penalty in
+//! milliseconds (-1 for a DNF) and the time, the scramble, the comment and
+//! when it was done in seconds. Reconstructions have no place there and are
+//! left out of exports.
+
+use crate::{
+    algoritm::Algorithm,
+    json::{Json, JsonError},
+    session::{Penalty, Session, Solve},
+};
+use std::{
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
+
+/// A solve that couldn't be imported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rejected {
+    pub session: String,
+    /// Counting from 1, as csTimer does.
+    pub solve: usize,
+    pub reason: String,
+}
+
+impl Display for Rejected {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "session `{}`, solve {}: {}",
+            self.session, self.solve, self.reason
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Import {
+    /// The sessions by name, in the order of the export.
+    pub sessions: Vec<(String, Session)>,
+    pub rejected: Vec<Rejected>,
+}
+
+/// Reads a csTimer export. Only broken JSON is an error, solves that can't
+/// be read are left out and listed in `rejected`.
+pub fn import(source: &str) -> Result<Import, JsonError> {
+    let json = Json::parse(source)?;
+    let names = session_names(&json);
+    let mut import = Import::default();
+
+    let members = match &json {
+        Json::Object(members) => members.as_slice(),
+        _ => &[],
+    };
+    for (key, solves) in members {
+        let id = match key.strip_prefix("session") {
+            Some(id) if !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()) => id,
+            _ => continue,
+        };
+        let name = names
+            .iter()
+            .find(|(other, _)| other == id)
+            .map_or_else(|| id.to_string(), |(_, name)| name.clone());
+
+        let mut session = Session::default();
+        for (i, row) in solves.as_array().unwrap_or(&[]).iter().enumerate() {
+            match solve(row) {
+                Ok(solve) => session.solves.push(solve),
+                Err(reason) => import.rejected.push(Rejected {
+                    session: name.clone(),
+                    solve: i + 1,
+                    reason,
+                }),
+            }
+        }
+        import.sessions.push((name, session));
+    }
+
+    Ok(import)
+}
+
+/// The names in `properties.sessionData` by session id. Names can be
+/// numbers there, and are missing from old exports.
+fn session_names(json: &Json) -> Vec<(String, String)> {
+    let data = json
+        .get("properties")
+        .and_then(|properties| properties.get("sessionData"))
+        .and_then(Json::as_str)
+        .and_then(|data| Json::parse(data).ok());
+
+    match data {
+        Some(Json::Object(members)) => members
+            .iter()
+            .filter_map(|(id, session)| {
+                let name = match session.get("name")? {
+                    Json::String(name) => name.clone(),
+                    Json::Number(_) => session.get("name")?.to_string(),
+                    _ => return None,
+                };
+                Some((id.clone(), name))
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn solve(row: &Json) -> Result<Solve, String> {
+    let row = row.as_array().ok_or("not a solve")?;
+    let result = row.first().and_then(Json::as_array).ok_or("missing time")?;
+    let (penalty, time) = match result {
+        [penalty, time, ..] => (penalty.as_i64(), time.as_i64()),
+        _ => return Err("missing time".to_string()),
+    };
+
+    let penalty = match penalty {
+        Some(0) => Penalty::None,
+        Some(2000) => Penalty::PlusTwo,
+        Some(-1) => Penalty::Dnf,
+        _ => return Err(format!("unknown penalty {}", result[0])),
+    };
+    let time = match time {
+        Some(time) if time >= 0 => Duration::from_millis(time as u64),
+        _ => return Err(format!("bad time {}", result[1])),
+    };
+
+    let scramble = row.get(1).and_then(Json::as_str).unwrap_or("");
+    let scramble = Algorithm::parse(scramble)
+        .ok_or_else(|| format!("couldn't read the scramble `{}`", scramble))?;
+    let comment = row.get(2).and_then(Json::as_str).unwrap_or("");
+    let timestamp = row.get(3).and_then(Json::as_i64).unwrap_or(0).max(0) as u64;
+
+    Ok(Solve {
+        scramble,
+        time,
+        penalty,
+        timestamp,
+        comment: comment.to_string(),
+        reconstruction: None,
+    })
+}
+
+/// Writes the sessions the way csTimer exports them, numbered from 1.
+pub fn export(sessions: &[(String, Session)]) -> String {
+    let mut members = Vec::new();
+    let mut data = Vec::new();
+
+    for (i, (name, session)) in sessions.iter().enumerate() {
+        let id = i + 1;
+        let solves = session
+            .solves
+            .iter()
+            .map(|solve| {
+                let penalty = match solve.penalty {
+                    Penalty::None => 0.0,
+                    Penalty::PlusTwo => 2000.0,
+                    Penalty::Dnf => -1.0,
+                };
+                Json::Array(vec![
+                    Json::Array(vec![
+                        Json::Number(penalty),
+                        Json::Number(solve.time.as_millis() as f64),
+                    ]),
+                    Json::String(solve.scramble.to_string()),
+                    Json::String(solve.comment.clone()),
+                    Json::Number(solve.timestamp as f64),
+                ])
+            })
+            .collect();
+        members.push((format!("session{}", id), Json::Array(solves)));
+
+        data.push((
+            id.to_string(),
+            Json::Object(vec![
+                ("name".to_string(), Json::String(name.clone())),
+                ("opt".to_string(), Json::Object(Vec::new())),
+                ("rank".to_string(), Json::Number(id as f64)),
+            ]),
+        ));
+    }
+
+    members.push((
+        "properties".to_string(),
+        Json::Object(vec![(
+            "sessionData".to_string(),
+            Json::String(Json::Object(data).to_string()),
+        )]),
+    ));
+    Json::Object(members).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"{
+        "session1": [
+            [[0, 12345], "R U R' U'", "", 1792418592],
+            [[2000, 10000], "F2 D", "nice", 1792418600],
+            [[-1, 9000], "B", "", 1792418700],
+            [[0, 11000], "3Rw U", "", 1792418800],
+            [[1000, 11000], "R", "", 1792418900],
+            "what"
+        ],
+        "session2": [],
+        "properties": {
+            "sessionData": "{\"1\":{\"name\":\"3x3\",\"opt\":{}},\"2\":{\"name\":4,\"opt\":{}}}"
+        }
+    }"#;
+
+    #[test]
+    fn imports_and_reports_rejected_solves() {
+        let import = import(EXPORT).unwrap();
+
+        let (name, session) = &import.sessions[0];
+        assert_eq!(name, "3x3");
+        assert_eq!(session.solves.len(), 3);
+        assert_eq!(session.solves[1].to_string(), "12.00+");
+        assert_eq!(session.solves[1].comment, "nice");
+        assert_eq!(session.solves[2].penalty, Penalty::Dnf);
+        assert_eq!(session.solves[0].timestamp, 1_792_418_592);
+        assert_eq!(import.sessions[1].0, "4");
+
+        let rejected: Vec<String> = import.rejected.iter().map(Rejected::to_string).collect();
+        assert_eq!(
+            rejected,
+            vec![
+                "session `3x3`, solve 4: couldn't read the scramble `3Rw U`",
+                "session `3x3`, solve 5: unknown penalty 1000",
+                "session `3x3`, solve 6: not a solve",
+            ]
+        );
+    }
+
+    #[test]
+    fn exports_what_it_imports() {
+        let first = import(EXPORT).unwrap();
+        let second = import(&export(&first.sessions)).unwrap();
+
+        assert_eq!(second.sessions, first.sessions);
+        assert!(second.rejected.is_empty());
+        assert!(import("[1,").is_err());
+    }
+}
@@ -0,0 +1,397 @@
+//! The cube as permuted and oriented pieces, rather than stickers.
+//!
+//! Pieces are numbered like in Kociemba's two-phase algorithm, corners
+//! URF, UFL, ULB, UBR, DFR, DLF, DBL, DRB and edges UR, UF, UL, UB, DR, DF,
+//! DL, DB, FR, FL, BL, BR. Sticker positions are indices into
+//! [`Cube::facelets`].
+
+use crate::{
+    algoritm::{Layers, Move},
+    Colour, Cube,
+};
+use std::fmt::{Display, Formatter};
+
+pub const CORNERS: usize = 8;
+pub const EDGES: usize = 12;
+
+// Facelet indices, `U1` is the first sticker of the up face.
+const U: usize = 0;
+const R: usize = 9;
+const F: usize = 18;
+const D: usize = 27;
+const L: usize = 36;
+const B: usize = 45;
+
+/// The stickers of every corner position, starting with the up or down one
+/// and going clockwise.
+pub const CORNER_FACELETS: [[usize; 3]; CORNERS] = [
+    [U + 8, R, F + 2],
+    [U + 6, F, L + 2],
+    [U, L, B + 2],
+    [U + 2, B, R + 2],
+    [D + 2, F + 8, R + 6],
+    [D, L + 8, F + 6],
+    [D + 6, B + 8, L + 6],
+    [D + 8, R + 8, B + 6],
+];
+
+/// The stickers of every edge position, starting with the up, down, front
+/// or back one.
+pub const EDGE_FACELETS: [[usize; 2]; EDGES] = [
+    [U + 5, R + 1],
+    [U + 7, F + 1],
+    [U + 3, L + 1],
+    [U + 1, B + 1],
+    [D + 5, R + 7],
+    [D + 1, F + 7],
+    [D + 3, L + 7],
+    [D + 7, B + 7],
+    [F + 5, R + 3],
+    [F + 3, L + 5],
+    [B + 5, L + 3],
+    [B + 3, R + 5],
+];
+
+/// The names of the corner positions.
+pub const CORNER_NAMES: [&str; CORNERS] = ["URF", "UFL", "ULB", "UBR", "DFR", "DLF", "DBL", "DRB"];
+
+/// The names of the edge positions.
+pub const EDGE_NAMES: [&str; EDGES] = [
+    "UR", "UF", "UL", "UB", "DR", "DF", "DL", "DB", "FR", "FL", "BL", "BR",
+];
+
+/// The faces, in [`Cube::FACELET_ORDER`], whose colours make up each corner.
+const CORNER_FACES: [[usize; 3]; CORNERS] = [
+    [0, 1, 2],
+    [0, 2, 4],
+    [0, 4, 5],
+    [0, 5, 1],
+    [3, 2, 1],
+    [3, 4, 2],
+    [3, 5, 4],
+    [3, 1, 5],
+];
+
+const EDGE_FACES: [[usize; 2]; EDGES] = [
+    [0, 1],
+    [0, 2],
+    [0, 4],
+    [0, 5],
+    [3, 1],
+    [3, 2],
+    [3, 4],
+    [3, 5],
+    [2, 1],
+    [2, 4],
+    [5, 4],
+    [5, 1],
+];
+
+/// Why a sticker state isn't a cube that can be solved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StateError {
+    /// The six centres must all have different colours.
+    Centres,
+    /// Every colour must appear on exactly nine stickers.
+    Count(Colour, usize),
+    /// The stickers at this corner position don't form a corner.
+    Corner(usize),
+    /// The stickers at this edge position don't form an edge.
+    Edge(usize),
+    /// Some corner appears more than once.
+    DuplicateCorner,
+    /// Some edge appears more than once.
+    DuplicateEdge,
+    /// A single corner is twisted.
+    Twist,
+    /// A single edge is flipped.
+    Flip,
+    /// Two pieces are swapped.
+    Parity,
+}
+
+impl Display for StateError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Self::Centres => write!(f, "the centres don't have six different colours"),
+            Self::Count(c, n) => write!(f, "{} appears on {} stickers instead of 9", c, n),
+            Self::Corner(i) => write!(f, "the corner at position {} doesn't exist", i),
+            Self::Edge(i) => write!(f, "the edge at position {} doesn't exist", i),
+            Self::DuplicateCorner => write!(f, "a corner appears twice"),
+            Self::DuplicateEdge => write!(f, "an edge appears twice"),
+            Self::Twist => write!(f, "a corner is twisted"),
+            Self::Flip => write!(f, "an edge is flipped"),
+            Self::Parity => write!(f, "two pieces are swapped"),
+        }
+    }
+}
+
+impl std::error::Error for StateError {}
+
+/// Piece `cp[i]` sits at corner position `i`, twisted `co[i]` times
+/// clockwise, and likewise for the edges with flips.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CubieCube {
+    pub cp: [u8; CORNERS],
+    pub co: [u8; CORNERS],
+    pub ep: [u8; EDGES],
+    pub eo: [u8; EDGES],
+}
+
+impl Default for CubieCube {
+    fn default() -> Self {
+        Self {
+            cp: [0, 1, 2, 3, 4, 5, 6, 7],
+            co: [0; CORNERS],
+            ep: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+            eo: [0; EDGES],
+        }
+    }
+}
+
+/// The six face turns, in [`Cube::FACELET_ORDER`].
+const FACE_TURNS: [CubieCube; 6] = [
+    CubieCube {
+        cp: [3, 0, 1, 2, 4, 5, 6, 7],
+        co: [0, 0, 0, 0, 0, 0, 0, 0],
+        ep: [3, 0, 1, 2, 4, 5, 6, 7, 8, 9, 10, 11],
+        eo: [0; EDGES],
+    },
+    CubieCube {
+        cp: [4, 1, 2, 0, 7, 5, 6, 3],
+        co: [2, 0, 0, 1, 1, 0, 0, 2],
+        ep: [8, 1, 2, 3, 11, 5, 6, 7, 4, 9, 10, 0],
+        eo: [0; EDGES],
+    },
+    CubieCube {
+        cp: [1, 5, 2, 3, 0, 4, 6, 7],
+        co: [1, 2, 0, 0, 2, 1, 0, 0],
+        ep: [0, 9, 2, 3, 4, 8, 6, 7, 1, 5, 10, 11],
+        eo: [0, 1, 0, 0, 0, 1, 0, 0, 1, 1, 0, 0],
+    },
+    CubieCube {
+        cp: [0, 1, 2, 3, 5, 6, 7, 4],
+        co: [0, 0, 0, 0, 0, 0, 0, 0],
+        ep: [0, 1, 2, 3, 5, 6, 7, 4, 8, 9, 10, 11],
+        eo: [0; EDGES],
+    },
+    CubieCube {
+        cp: [0, 2, 6, 3, 4, 1, 5, 7],
+        co: [0, 1, 2, 0, 0, 2, 1, 0],
+        ep: [0, 1, 10, 3, 4, 5, 9, 7, 8, 2, 6, 11],
+        eo: [0; EDGES],
+    },
+    CubieCube {
+        cp: [0, 1, 3, 7, 4, 5, 2, 6],
+        co: [0, 0, 1, 2, 0, 0, 2, 1],
+        ep: [0, 1, 2, 11, 4, 5, 6, 10, 8, 9, 3, 7],
+        eo: [0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 1],
+    },
+];
+
+impl CubieCube {
+    /// Reads the pieces from the stickers, taking each face to be the colour
+    /// of its centre.
+    pub fn from_cube(cube: &Cube) -> Result<Self, StateError> {
+        let facelets = cube.facelets();
+
+        let mut centres = [Colour::White; 6];
+        for (i, centre) in centres.iter_mut().enumerate() {
+            *centre = facelets[i * 9 + 4];
+        }
+        for (i, colour) in centres.iter().enumerate() {
+            if centres[..i].contains(colour) {
+                return Err(StateError::Centres);
+            }
+            let count = facelets.iter().filter(|c| *c == colour).count();
+            if count != 9 {
+                return Err(StateError::Count(*colour, count));
+            }
+        }
+        let face = |index: usize| centres.iter().position(|c| *c == facelets[index]).unwrap();
+
+        let mut cubie = Self::default();
+
+        for (i, stickers) in CORNER_FACELETS.iter().enumerate() {
+            let faces: Vec<_> = stickers.iter().map(|s| face(*s)).collect();
+            let ori = faces
+                .iter()
+                .position(|f| *f == 0 || *f == 3)
+                .ok_or(StateError::Corner(i))?;
+            let (a, b) = (faces[(ori + 1) % 3], faces[(ori + 2) % 3]);
+            let piece = CORNER_FACES
+                .iter()
+                .position(|c| c[1] == a && c[2] == b && (c[0] == faces[ori]))
+                .ok_or(StateError::Corner(i))?;
+            cubie.cp[i] = piece as u8;
+            cubie.co[i] = ori as u8;
+        }
+
+        for (i, stickers) in EDGE_FACELETS.iter().enumerate() {
+            let (a, b) = (face(stickers[0]), face(stickers[1]));
+            let (piece, flip) = EDGE_FACES
+                .iter()
+                .enumerate()
+                .find_map(|(j, e)| match (e[0], e[1]) {
+                    (x, y) if x == a && y == b => Some((j, 0)),
+                    (x, y) if x == b && y == a => Some((j, 1)),
+                    _ => None,
+                })
+                .ok_or(StateError::Edge(i))?;
+            cubie.ep[i] = piece as u8;
+            cubie.eo[i] = flip;
+        }
+
+        cubie.verify()?;
+        Ok(cubie)
+    }
+
+    /// The stickers of this state, with the standard colour scheme.
+    pub fn to_cube(&self) -> Cube {
+        let solved = Cube::default().facelets();
+        let centres: Vec<_> = (0..6).map(|i| solved[i * 9 + 4]).collect();
+        let mut facelets = solved;
+
+        for i in 0..CORNERS {
+            let (piece, ori) = (self.cp[i] as usize, self.co[i] as usize);
+            for n in 0..3 {
+                facelets[CORNER_FACELETS[i][(n + ori) % 3]] = centres[CORNER_FACES[piece][n]];
+            }
+        }
+        for i in 0..EDGES {
+            let (piece, flip) = (self.ep[i] as usize, self.eo[i] as usize);
+            for n in 0..2 {
+                facelets[EDGE_FACELETS[i][(n + flip) % 2]] = centres[EDGE_FACES[piece][n]];
+            }
+        }
+
+        Cube::from_facelets(&facelets)
+    }
+
+    /// Checks the state can be reached by turning a solved cube.
+    pub fn verify(&self) -> Result<(), StateError> {
+        let mut corners = [false; CORNERS];
+        for c in self.cp.iter() {
+            if corners[*c as usize] {
+                return Err(StateError::DuplicateCorner);
+            }
+            corners[*c as usize] = true;
+        }
+        let mut edges = [false; EDGES];
+        for e in self.ep.iter() {
+            if edges[*e as usize] {
+                return Err(StateError::DuplicateEdge);
+            }
+            edges[*e as usize] = true;
+        }
+        if self.co.iter().map(|o| *o as u32).sum::<u32>() % 3 != 0 {
+            return Err(StateError::Twist);
+        }
+        if self.eo.iter().map(|o| *o as u32).sum::<u32>() % 2 != 0 {
+            return Err(StateError::Flip);
+        }
+        if parity(&self.cp) != parity(&self.ep) {
+            return Err(StateError::Parity);
+        }
+
+        Ok(())
+    }
+
+    /// The state after doing `self` and then `other`.
+    pub fn multiply(&self, other: &Self) -> Self {
+        let mut out = Self::default();
+        for i in 0..CORNERS {
+            let from = other.cp[i] as usize;
+            out.cp[i] = self.cp[from];
+            out.co[i] = (self.co[from] + other.co[i]) % 3;
+        }
+        for i in 0..EDGES {
+            let from = other.ep[i] as usize;
+            out.ep[i] = self.ep[from];
+            out.eo[i] = (self.eo[from] + other.eo[i]) % 2;
+        }
+        out
+    }
+
+    /// The clockwise quarter turn of the face at `face` in [`Cube::FACELET_ORDER`].
+    pub fn face_turn(face: usize) -> &'static Self {
+        &FACE_TURNS[face]
+    }
+
+    /// Turns a face. Rotations leave the pieces where they are relative to
+    /// the centres, so they do nothing here; see
+    /// [`Algorithm::without_rotations`](crate::algoritm::Algorithm::without_rotations)
+    /// for the moves after them. Wide and slice moves turn their outer
+    /// layers, leaving out the rotation they come with.
+    pub fn execute_move(&mut self, mv: &Move) {
+        if mv.layers != Layers::Outer {
+            for mv in mv.to_outer().iter().filter(|mv| !mv.is_rotation()) {
+                self.execute_move(mv);
+            }
+            return;
+        }
+        let face = Cube::FACELET_ORDER
+            .iter()
+            .position(|side| *side == mv.side)
+            .unwrap_or(0);
+        for _ in 0..mv.ty.quarter_turns() {
+            *self = self.multiply(&FACE_TURNS[face]);
+        }
+    }
+}
+
+/// Whether the permutation is odd.
+pub fn parity(perm: &[u8]) -> bool {
+    let mut odd = false;
+    for i in 0..perm.len() {
+        for j in i + 1..perm.len() {
+            if perm[i] > perm[j] {
+                odd = !odd;
+            }
+        }
+    }
+    odd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algoritm::Algorithm;
+
+    #[test]
+    fn stickers_and_pieces_agree() {
+        let alg = Algorithm::parse("R U F D L B R2 U' F2 L' D2 B'").unwrap();
+        let mut cube = Cube::default();
+        cube.execute_algorithm(alg.clone());
+        let mut cubie = CubieCube::default();
+        for mv in alg.moves.iter() {
+            cubie.execute_move(mv);
+        }
+
+        assert_eq!(CubieCube::from_cube(&cube), Ok(cubie.clone()));
+        assert_eq!(cubie.to_cube(), cube);
+    }
+
+    #[test]
+    fn rejects_impossible_states() {
+        let solved = Cube::default().to_facelet_string();
+
+        let mut twisted: Vec<char> = solved.chars().collect();
+        // Cycle the stickers of the URF corner.
+        let [a, b, c] = CORNER_FACELETS[0];
+        let (x, y, z) = (twisted[a], twisted[b], twisted[c]);
+        twisted[a] = z;
+        twisted[b] = x;
+        twisted[c] = y;
+        let twisted: String = twisted.into_iter().collect();
+        let cube = Cube::parse_facelets(&twisted).unwrap();
+        assert_eq!(CubieCube::from_cube(&cube), Err(StateError::Twist));
+
+        let mut swapped = CubieCube::default();
+        swapped.ep.swap(0, 1);
+        assert_eq!(
+            CubieCube::from_cube(&swapped.to_cube()),
+            Err(StateError::Parity)
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! Named algorithms for the cases of OLL, PLL, F2L and COLL.
+//!
+//! The built in ones are in `algorithms.txt`, compiled into the crate: a
+//! version, then sections like `[pll]` with a `name = moves` line for each
+//! case. Anything after `#` is a comment.
+
+use crate::algoritm::Algorithm;
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    sync::OnceLock,
+};
+
+/// The source of [`Database::builtin`].
+pub const BUILTIN: &str = include_str!("algorithms.txt");
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Set {
+    Oll,
+    Pll,
+    F2l,
+    Coll,
+}
+
+impl Set {
+    pub const ALL: [Set; 4] = [Set::Oll, Set::Pll, Set::F2l, Set::Coll];
+
+    /// Reads the lowercase name used for sections, like `oll`.
+    pub fn parse(source: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|set| set.to_string().to_lowercase() == source)
+            .copied()
+    }
+}
+
+impl Display for Set {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Oll => write!(f, "OLL"),
+            Self::Pll => write!(f, "PLL"),
+            Self::F2l => write!(f, "F2L"),
+            Self::Coll => write!(f, "COLL"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub set: Set,
+    /// The number or letters of the case, unique within the set.
+    pub name: String,
+    pub algorithm: Algorithm,
+}
+
+/// Shows the case, like `OLL 27`.
+impl Display for Entry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.set, self.name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DatabaseError {
+    /// The first line that isn't a comment should be `version = <n>`.
+    Version(usize),
+    Section(usize, String),
+    /// A line that isn't `name = moves`, or comes before any section.
+    Entry(usize),
+    Algorithm(usize, String),
+    /// A name used twice in the same set.
+    Duplicate(usize, String),
+}
+
+impl DatabaseError {
+    /// The line the error is on, counting from 1.
+    pub fn line(&self) -> usize {
+        match self {
+            Self::Version(line)
+            | Self::Section(line, _)
+            | Self::Entry(line)
+            | Self::Algorithm(line, _)
+            | Self::Duplicate(line, _) => *line,
+        }
+    }
+}
+
+impl Display for DatabaseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line())?;
+        match self {
+            Self::Version(_) => write!(f, "expected `version = <number>`"),
+            Self::Section(_, name) => write!(f, "unknown set `{}`", name),
+            Self::Entry(_) => write!(f, "expected `name = moves` in a set"),
+            Self::Algorithm(_, source) => write!(f, "couldn't read the algorithm `{}`", source),
+            Self::Duplicate(_, name) => write!(f, "`{}` is already in the set", name),
+        }
+    }
+}
+
+impl Error for DatabaseError {}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Database {
+    pub version: u32,
+    /// In the order they were written.
+    pub entries: Vec<Entry>,
+}
+
+impl Database {
+    pub fn parse(source: &str) -> Result<Self, DatabaseError> {
+        let mut database = Self::default();
+        let mut version = None;
+        let mut set = None;
+
+        for (i, line) in source.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if version.is_none() {
+                version = line
+                    .strip_prefix("version")
+                    .and_then(|rest| rest.trim().strip_prefix('='))
+                    .and_then(|number| number.trim().parse().ok());
+                database.version = version.ok_or(DatabaseError::Version(line_number))?;
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let name = name.trim();
+                set = Some(
+                    Set::parse(name)
+                        .ok_or_else(|| DatabaseError::Section(line_number, name.to_string()))?,
+                );
+                continue;
+            }
+
+            let (set, (name, moves)) = match (set, line.split_once('=')) {
+                (Some(set), Some((name, moves))) if !name.trim().is_empty() => {
+                    (set, (name.trim(), moves.trim()))
+                }
+                _ => return Err(DatabaseError::Entry(line_number)),
+            };
+            if database.get(set, name).is_some() {
+                return Err(DatabaseError::Duplicate(line_number, name.to_string()));
+            }
+            let algorithm = Algorithm::parse(moves)
+                .ok_or_else(|| DatabaseError::Algorithm(line_number, moves.to_string()))?;
+
+            database.entries.push(Entry {
+                set,
+                name: name.to_string(),
+                algorithm,
+            });
+        }
+
+        if version.is_none() {
+            return Err(DatabaseError::Version(source.lines().count().max(1)));
+        }
+        Ok(database)
+    }
+
+    /// The database that comes with the crate.
+    pub fn builtin() -> &'static Self {
+        static BUILTIN_DATABASE: OnceLock<Database> = OnceLock::new();
+        BUILTIN_DATABASE
+            .get_or_init(|| Self::parse(BUILTIN).expect("the built in algorithms are valid"))
+    }
+
+    /// The cases of `set`, in the order they were written.
+    pub fn set(&self, set: Set) -> impl Iterator<Item = &Entry> + '_ {
+        self.entries.iter().filter(move |entry| entry.set == set)
+    }
+
+    pub fn get(&self, set: Set, name: &str) -> Option<&Entry> {
+        self.set(set).find(|entry| entry.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cubie::CubieCube, Cube};
+
+    #[test]
+    fn builtin_has_every_case() {
+        let database = Database::builtin();
+        assert_eq!(database.version, 1);
+
+        let count = |set| database.set(set).count();
+        assert_eq!(count(Set::Oll), 57);
+        assert_eq!(count(Set::Pll), 21);
+        assert_eq!(count(Set::F2l), 41);
+        assert_eq!(count(Set::Coll), 42);
+
+        let t = database.get(Set::Pll, "T").unwrap();
+        assert_eq!(t.to_string(), "PLL T");
+        assert_eq!(t.algorithm.move_count(), 14);
+    }
+
+    /// The last layer sets are checked by recognising them, see
+    /// `last_layer`. F2L cases are told apart by where the front right
+    /// corner and edge are, up to turns of the top layer.
+    #[test]
+    fn f2l_algorithms_insert_their_pair() {
+        let mut seen = Vec::new();
+        for entry in Database::builtin().set(Set::F2l) {
+            let mut cube = Cube::default();
+            cube.execute_algorithm(entry.algorithm.inverse());
+            let pieces = CubieCube::from_cube(&cube).unwrap();
+
+            let solved = |corners: &[usize], edges: &[usize]| {
+                corners
+                    .iter()
+                    .all(|&i| pieces.cp[i] == i as u8 && pieces.co[i] == 0)
+                    && edges
+                        .iter()
+                        .all(|&i| pieces.ep[i] == i as u8 && pieces.eo[i] == 0)
+            };
+            assert!(solved(&[5, 6, 7], &[4, 5, 6, 7, 9, 10, 11]), "{}", entry);
+            assert!(!solved(&[4], &[8]), "{}", entry);
+
+            let case = (0..4)
+                .map(|turns| {
+                    let mut turned = pieces.clone();
+                    for _ in 0..turns {
+                        turned = turned.multiply(CubieCube::face_turn(0));
+                    }
+                    let corner = turned.cp.iter().position(|&p| p == 4).unwrap();
+                    let edge = turned.ep.iter().position(|&p| p == 8).unwrap();
+                    (corner, turned.co[corner], edge, turned.eo[edge])
+                })
+                .min()
+                .unwrap();
+            assert!(!seen.contains(&case), "{} is there twice", entry);
+            seen.push(case);
+        }
+    }
+
+    #[test]
+    fn reports_bad_lines() {
+        let error = |source| Database::parse(source).unwrap_err().to_string();
+        assert_eq!(error("[oll]"), "line 1: expected `version = <number>`");
+        assert_eq!(
+            error("version = 1\n1 = R"),
+            "line 2: expected `name = moves` in a set"
+        );
+        assert_eq!(error("version = 1\n[zbll]"), "line 2: unknown set `zbll`");
+        assert_eq!(
+            error("version = 1\n[oll]\n1 = R # fine\n2 = R Q"),
+            "line 4: couldn't read the algorithm `R Q`"
+        );
+        assert_eq!(
+            error("version = 1\n[pll]\nT = R\nT = U"),
+            "line 4: `T` is already in the set"
+        );
+
+        let database = Database::parse("# algs\nversion = 2\n[f2l]\n1 = R U R'").unwrap();
+        assert_eq!(database.version, 2);
+        assert_eq!(
+            database.get(Set::F2l, "1").unwrap().algorithm.move_count(),
+            3
+        );
+    }
+}
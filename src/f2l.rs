@@ -0,0 +1,567 @@
+//! Optimal XCrosses, XXCrosses and pair insertions, for looking back at how
+//! the start of a solve could have gone.
+//!
+//! Like [`cross`](crate::cross), everything is solved with the cross on the
+//! bottom. Each slot is pruned by two exact tables, of the cross with the
+//! slot's corner and of the cross with its edge. There is only one pair of
+//! tables, for the front right slot: the others are looked at through it by
+//! following the search from the cube turned with `y`, `y2` and `y'`.
+
+use crate::{
+    algoritm::{Algorithm, Move, MoveType, Side},
+    cross::{self, CROSS_EDGES, EDGE_STATES},
+    cubie::{CubieCube, StateError},
+    solver::{self, MOVES},
+    Colour, Cube,
+};
+use std::{
+    cmp::Reverse,
+    fmt::{self, Display, Formatter},
+    sync::OnceLock,
+};
+
+/// A corner's place and twist, as `position * 3 + twist`.
+const CORNER_STATES: usize = 24;
+
+/// The front right slot's edge FR and corner DFR.
+const SLOT_EDGE: usize = 8;
+const SLOT_CORNER: usize = 4;
+
+/// The longest search there is, well past any optimal XXCross.
+const MAX_LENGTH: usize = 16;
+/// The most solutions kept to rank, so a large slack can't run away.
+const MAX_FOUND: usize = 1000;
+
+/// The slots of the first two layers, with the cross on the bottom.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Slot {
+    FrontRight,
+    BackRight,
+    BackLeft,
+    FrontLeft,
+}
+
+impl Slot {
+    /// In the order `y'` moves them to the front right.
+    pub const ALL: [Slot; 4] = [
+        Slot::FrontRight,
+        Slot::BackRight,
+        Slot::BackLeft,
+        Slot::FrontLeft,
+    ];
+
+    /// Reads the lowercase name, like `fr`.
+    pub fn parse(source: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|slot| slot.to_string().to_lowercase() == source)
+            .copied()
+    }
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|slot| slot == self).unwrap_or(0)
+    }
+}
+
+impl Display for Slot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FrontRight => write!(f, "FR"),
+            Self::BackRight => write!(f, "BR"),
+            Self::BackLeft => write!(f, "BL"),
+            Self::FrontLeft => write!(f, "FL"),
+        }
+    }
+}
+
+/// How many F2L pairs to solve with the cross, from 1 to 4.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pairs(usize);
+
+impl Pairs {
+    /// `None` unless `count` is from 1 to 4.
+    pub fn new(count: usize) -> Option<Self> {
+        if (1..=4).contains(&count) {
+            Some(Self(count))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct F2lOptions {
+    /// Start with the rotation bringing the cross to the bottom, rather than
+    /// solving it where it is.
+    pub rotate: bool,
+    /// The most solutions to give.
+    pub limit: usize,
+    /// How many moves longer than optimal solutions may be.
+    pub slack: usize,
+}
+
+impl Default for F2lOptions {
+    fn default() -> Self {
+        Self {
+            rotate: true,
+            limit: 10,
+            slack: 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    pub colour: Colour,
+    pub rotation: Algorithm,
+    pub moves: Algorithm,
+    /// The slots solved after it, with the cross on the bottom.
+    pub slots: Vec<Slot>,
+}
+
+impl Solution {
+    /// The rotation followed by the moves.
+    pub fn algorithm(&self) -> Algorithm {
+        let mut moves = self.rotation.moves.clone();
+        moves.extend(self.moves.moves.iter().cloned());
+        Algorithm::new(moves)
+    }
+}
+
+/// Shows the colour, the move count, the solved slots and the algorithm,
+/// like `W 7 FR: x2 R' D' F2 U L' U' L`.
+impl Display for Solution {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.colour, self.moves.move_count())?;
+        for slot in &self.slots {
+            write!(f, " {}", slot)?;
+        }
+        let algorithm = self.algorithm();
+        if !algorithm.moves.is_empty() {
+            write!(f, ": {}", algorithm)?;
+        }
+        Ok(())
+    }
+}
+
+/// How awkward the moves are to do, lower being better: `R` and `U` are
+/// the easiest, then `L` and `D`, then `F` and finally `B`. Half turns
+/// count a little more than quarter turns.
+pub fn ergonomics(algorithm: &Algorithm) -> u32 {
+    algorithm
+        .moves
+        .iter()
+        .filter(|mv| !mv.is_rotation())
+        .map(|mv| {
+            let face = match mv.side {
+                Side::Right | Side::Up => 2,
+                Side::Left | Side::Down => 3,
+                Side::Front => 4,
+                Side::Back => 6,
+            };
+            face + (mv.ty.quarter_turns() == 2) as u32
+        })
+        .sum()
+}
+
+/// The cross edges, the slot edge and the slot corner, with the cube held
+/// one way.
+#[derive(Clone, Copy, Debug)]
+struct State {
+    cross: u32,
+    edge: u8,
+    corner: u8,
+}
+
+struct Tables {
+    corner_moves: [[u8; CORNER_STATES]; MOVES],
+    /// How far the cross and the front right edge are from solved, by
+    /// `cross * EDGE_STATES + edge`.
+    with_edge: Vec<u8>,
+    /// Likewise with the front right corner.
+    with_corner: Vec<u8>,
+}
+
+impl Tables {
+    fn new() -> Self {
+        let mut corner_moves = [[0; CORNER_STATES]; MOVES];
+        for (m, row) in corner_moves.iter_mut().enumerate() {
+            for (state, next) in row.iter_mut().enumerate() {
+                let (mut position, mut twist) = (state / 3, state % 3);
+                for _ in 0..=m % 3 {
+                    let turn = CubieCube::face_turn(m / 3);
+                    let to = turn
+                        .cp
+                        .iter()
+                        .position(|&p| p as usize == position)
+                        .unwrap();
+                    twist = (twist + turn.co[to] as usize) % 3;
+                    position = to;
+                }
+                *next = (position * 3 + twist) as u8;
+            }
+        }
+
+        let solved = cross::placement(&CROSS_EDGES.map(|edge| (edge * 2) as u8)) as usize;
+        let with_edge = cross::distances(
+            cross::cross_moves(),
+            cross::edge_moves(),
+            solved * EDGE_STATES + SLOT_EDGE * 2,
+        );
+        let with_corner = cross::distances(
+            cross::cross_moves(),
+            &corner_moves,
+            solved * CORNER_STATES + SLOT_CORNER * 3,
+        );
+        Self {
+            corner_moves,
+            with_edge,
+            with_corner,
+        }
+    }
+
+    fn get() -> &'static Self {
+        static TABLES: OnceLock<Tables> = OnceLock::new();
+        TABLES.get_or_init(Tables::new)
+    }
+
+    fn turn(&self, state: &State, m: usize) -> State {
+        State {
+            cross: cross::cross_moves()[state.cross as usize][m],
+            edge: cross::edge_moves()[m][state.edge as usize],
+            corner: self.corner_moves[m][state.corner as usize],
+        }
+    }
+
+    /// How many moves solving the cross and the front right slot takes at
+    /// least.
+    fn distance(&self, state: &State) -> u8 {
+        let cross = state.cross as usize;
+        self.with_edge[cross * EDGE_STATES + state.edge as usize]
+            .max(self.with_corner[cross * CORNER_STATES + state.corner as usize])
+    }
+}
+
+/// What the search is after: the cross, every slot in `required` and
+/// `any` more of the rest.
+struct Goal {
+    required: Vec<usize>,
+    any: usize,
+}
+
+struct Search<'a> {
+    tables: &'a Tables,
+    /// Each move as it is when the cube is held the way of each slot.
+    frames: [[usize; MOVES]; 4],
+    goal: Goal,
+    moves: Vec<usize>,
+    found: Vec<Vec<usize>>,
+}
+
+impl Search<'_> {
+    fn distance(&self, states: &[State; 4]) -> u8 {
+        let slots = states.map(|state| self.tables.distance(&state));
+        let required = self
+            .goal
+            .required
+            .iter()
+            .map(|&slot| slots[slot])
+            .max()
+            .unwrap_or(0);
+        let mut rest: Vec<u8> = (0..4)
+            .filter(|slot| !self.goal.required.contains(slot))
+            .map(|slot| slots[slot])
+            .collect();
+        rest.sort_unstable();
+        let any = match self.goal.any {
+            0 => 0,
+            any => rest[any - 1],
+        };
+        required.max(any)
+    }
+
+    fn search(&mut self, states: [State; 4], left: usize) {
+        let distance = self.distance(&states) as usize;
+        if distance > left || self.found.len() >= MAX_FOUND {
+            return;
+        }
+        // Solutions that get there early are found at their own length.
+        if distance == 0 {
+            if left == 0 {
+                self.found.push(self.moves.clone());
+            }
+            return;
+        }
+
+        for m in 0..MOVES {
+            if !solver::allowed(m, self.moves.last().copied()) {
+                continue;
+            }
+            let mut turned = states;
+            for (k, state) in turned.iter_mut().enumerate() {
+                *state = self.tables.turn(state, self.frames[k][m]);
+            }
+            self.moves.push(m);
+            self.search(turned, left - 1);
+            self.moves.pop();
+        }
+    }
+}
+
+/// The cube with `colour` on the bottom, held the way of each slot, with
+/// the rotation getting it there.
+fn frames(cube: &Cube, colour: Colour) -> Result<Option<(Algorithm, [State; 4])>, StateError> {
+    let side = match cube.centre_side(colour) {
+        Some(side) => side,
+        None => return Ok(None),
+    };
+    let rotation = cross::to_bottom(&side);
+    let mut bottomed = cube.clone();
+    bottomed.execute_algorithm(rotation.clone());
+
+    let mut states = [State {
+        cross: 0,
+        edge: 0,
+        corner: 0,
+    }; 4];
+    for (k, state) in states.iter_mut().enumerate() {
+        let mut turned = bottomed.clone();
+        for _ in 0..k {
+            turned.execute_move(Move::rotation(Side::Up, MoveType::Normal));
+        }
+        let cubie = CubieCube::from_cube(&turned)?;
+        let corner = cubie
+            .cp
+            .iter()
+            .position(|&p| p as usize == SLOT_CORNER)
+            .unwrap();
+        *state = State {
+            cross: cross::placement(&CROSS_EDGES.map(|edge| cross::edge_state(&cubie, edge))),
+            edge: cross::edge_state(&cubie, SLOT_EDGE),
+            corner: (corner * 3 + cubie.co[corner] as usize) as u8,
+        };
+    }
+    Ok(Some((rotation, states)))
+}
+
+/// Each move as it is with the cube turned `y` once, twice and three times.
+fn frame_moves() -> [[usize; MOVES]; 4] {
+    let mut frames = [[0; MOVES]; 4];
+    for (k, frame) in frames.iter_mut().enumerate() {
+        for (m, moved) in frame.iter_mut().enumerate() {
+            let mut side = Cube::FACELET_ORDER[m / 3].clone();
+            for _ in 0..k {
+                side = side.rotated(&Side::Up);
+            }
+            let face = Cube::FACELET_ORDER.iter().position(|s| *s == side).unwrap();
+            *moved = face * 3 + m % 3;
+        }
+    }
+    frames
+}
+
+fn solve(
+    cube: &Cube,
+    colour: Colour,
+    goal: Goal,
+    options: &F2lOptions,
+) -> Result<Vec<Solution>, StateError> {
+    let (rotation, states) = match frames(cube, colour)? {
+        Some(frames) => frames,
+        None => return Ok(Vec::new()),
+    };
+    let tables = Tables::get();
+    let mut search = Search {
+        tables,
+        frames: frame_moves(),
+        goal,
+        moves: Vec::new(),
+        found: Vec::new(),
+    };
+
+    let mut optimal = None;
+    for depth in 0..=MAX_LENGTH {
+        if optimal.is_some_and(|optimal| depth > optimal + options.slack) {
+            break;
+        }
+        search.search(states, depth);
+        if optimal.is_none() && !search.found.is_empty() {
+            optimal = Some(depth);
+        }
+    }
+
+    let mut solutions: Vec<Solution> = search
+        .found
+        .iter()
+        .map(|moves| {
+            let mut end = states;
+            for &m in moves {
+                for (k, state) in end.iter_mut().enumerate() {
+                    *state = tables.turn(state, search.frames[k][m]);
+                }
+            }
+            let slots = Slot::ALL
+                .iter()
+                .filter(|slot| tables.distance(&end[slot.index()]) == 0)
+                .copied()
+                .collect();
+
+            let (rotation, moves) =
+                cross::placed(&rotation, solver::to_algorithm(moves), options.rotate);
+            Solution {
+                colour,
+                rotation,
+                moves,
+                slots,
+            }
+        })
+        .collect();
+    rank(&mut solutions);
+    solutions.truncate(options.limit);
+    Ok(solutions)
+}
+
+/// Sorts by move count, then by [`ergonomics`], then by how many slots they
+/// solve.
+pub fn rank(solutions: &mut [Solution]) {
+    solutions.sort_by_key(|solution| {
+        (
+            solution.moves.move_count(),
+            ergonomics(&solution.moves),
+            Reverse(solution.slots.len()),
+        )
+    });
+}
+
+/// The shortest ways to solve the cross of `colour` with `pairs` of the F2L
+/// pairs, 1 for an XCross and 2 for an XXCross, best first.
+///
+/// ```
+/// # use cube::{algoritm::Algorithm, f2l::{self, F2lOptions, Pairs}, Colour, Cube};
+/// let mut cube = Cube::default();
+/// let scramble = "D2 F2 U' B2 R2 B2 R2 L B' D' F D2 F' L' U R' F' D' L2 B2";
+/// cube.execute_algorithm(Algorithm::parse(scramble).unwrap());
+///
+/// let pairs = Pairs::new(1).unwrap();
+/// let xcross = f2l::xcross(&cube, Colour::Yellow, pairs, &F2lOptions::default()).unwrap();
+/// assert_eq!(xcross[0].to_string(), "Y 8 FR: F2 L D B' D R F D'");
+/// ```
+pub fn xcross(
+    cube: &Cube,
+    colour: Colour,
+    pairs: Pairs,
+    options: &F2lOptions,
+) -> Result<Vec<Solution>, StateError> {
+    let goal = Goal {
+        required: Vec::new(),
+        any: pairs.0,
+    };
+    solve(cube, colour, goal, options)
+}
+
+/// The shortest ways to solve `slot` on the cross of `colour`, keeping the
+/// cross and the slots that are already solved, best first.
+pub fn insert_pair(
+    cube: &Cube,
+    colour: Colour,
+    slot: Slot,
+    options: &F2lOptions,
+) -> Result<Vec<Solution>, StateError> {
+    let tables = Tables::get();
+    let mut required = vec![slot.index()];
+    if let Some((_, states)) = frames(cube, colour)? {
+        required.extend((0..4).filter(|&k| k != slot.index() && tables.distance(&states[k]) == 0));
+    }
+    let goal = Goal { required, any: 0 };
+    solve(cube, colour, goal, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scrambled;
+
+    /// The slots solved along with the cross of `colour`, if it's solved.
+    fn solved_slots(cube: &Cube, colour: Colour) -> Vec<Slot> {
+        let (_, states) = frames(cube, colour).unwrap().unwrap();
+        let tables = Tables::get();
+        Slot::ALL
+            .iter()
+            .filter(|slot| tables.distance(&states[slot.index()]) == 0)
+            .copied()
+            .collect()
+    }
+
+    #[test]
+    fn finds_xcrosses_and_xxcrosses() {
+        let cube = scrambled("R U R' F D2 L' B");
+        let options = F2lOptions {
+            rotate: false,
+            limit: 100,
+            slack: 1,
+        };
+
+        for pairs in 1..=2 {
+            let solutions = xcross(&cube, Colour::Yellow, Pairs(pairs), &options).unwrap();
+            assert!(!solutions.is_empty());
+            let optimal = solutions[0].moves.move_count();
+            for solution in &solutions {
+                assert!(solution.moves.move_count() <= optimal + 1, "{}", solution);
+                let mut solved = cube.clone();
+                solved.execute_algorithm(solution.algorithm());
+                let slots = solved_slots(&solved, Colour::Yellow);
+                assert!(slots.len() >= pairs, "{}", solution);
+                assert_eq!(slots, solution.slots);
+            }
+            for pair in solutions.windows(2) {
+                let key = |s: &Solution| (s.moves.move_count(), ergonomics(&s.moves));
+                assert!(key(&pair[0]) <= key(&pair[1]));
+            }
+        }
+
+        // Undoing the scramble solves everything, but the F alone is enough.
+        let solutions = xcross(&scrambled("R U R' F"), Colour::Yellow, Pairs(2), &options).unwrap();
+        assert_eq!(solutions[0].to_string(), "Y 1 BR BL FL: F'");
+        assert_eq!(Pairs::new(0), None);
+        assert_eq!(Pairs::new(5), None);
+    }
+
+    #[test]
+    fn inserts_a_pair_keeping_the_rest() {
+        let cube = scrambled("R U R'");
+        let solutions = insert_pair(
+            &cube,
+            Colour::Yellow,
+            Slot::FrontRight,
+            &F2lOptions::default(),
+        )
+        .unwrap();
+        let shown: Vec<String> = solutions.iter().map(|s| s.to_string()).collect();
+        assert_eq!(shown, vec!["Y 3 FR BR BL FL: R U' R'"]);
+
+        let cube = scrambled("R' U' R");
+        let solutions = insert_pair(
+            &cube,
+            Colour::Yellow,
+            Slot::BackRight,
+            &F2lOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(solutions[0].to_string(), "Y 3 FR BR BL FL: R' U R");
+
+        // With white on the bottom.
+        let cube = scrambled("x2 R U R' x2");
+        let solutions = insert_pair(
+            &cube,
+            Colour::White,
+            Slot::FrontRight,
+            &F2lOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(solutions[0].to_string(), "W 3 FR BR BL FL: x2 R U' R'");
+
+        assert_eq!(ergonomics(&Algorithm::parse("R U R'").unwrap()), 6);
+        assert_eq!(ergonomics(&Algorithm::parse("x F2 B").unwrap()), 11);
+        assert_eq!(Slot::parse("bl"), Some(Slot::BackLeft));
+    }
+}
@@ -0,0 +1,221 @@
+//! Isometric drawing of the cube with Unicode box characters.
+//!
+//! The front face is drawn flat, the up face recedes above it and the right
+//! face recedes to its side:
+//!
+//! ```text
+//!       ╱───╱───╱───╱
+//!      ╱ W ╱ W ╱ W ╱│
+//!     ╱───╱───╱───╱R╱
+//!    ╱ W ╱ W ╱ W ╱│╱│
+//! ```
+
+use crate::{algoritm::Side, ansi, Colour, Cube};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IsoOptions {
+    /// Paint the stickers with ANSI background colours.
+    pub colour: bool,
+    /// Also draw the down, left and back faces, seen from behind and below.
+    pub back: bool,
+}
+
+/// Width of a front or up sticker, including one border.
+const CELL: usize = 4;
+/// Rows taken by the up face, and columns taken by the right face.
+const DEPTH: usize = 6;
+const WIDTH: usize = 3 * CELL + 1 + DEPTH;
+const HEIGHT: usize = DEPTH + 3 * 2 + 1;
+
+#[derive(Copy, Clone)]
+struct Cell {
+    ch: char,
+    colour: Option<Colour>,
+}
+
+struct Canvas {
+    cells: Vec<Vec<Cell>>,
+}
+
+impl Canvas {
+    fn new() -> Self {
+        Self {
+            cells: vec![
+                vec![
+                    Cell {
+                        ch: ' ',
+                        colour: None
+                    };
+                    WIDTH
+                ];
+                HEIGHT
+            ],
+        }
+    }
+
+    fn put(&mut self, row: usize, col: usize, ch: char) {
+        self.cells[row][col].ch = ch;
+    }
+
+    /// Writes the sticker letter at `col`, colouring `width` cells centred on it.
+    fn sticker(&mut self, row: usize, col: usize, width: usize, colour: Colour) {
+        for c in col - width / 2..=col + width / 2 {
+            self.cells[row][c].colour = Some(colour);
+        }
+        self.cells[row][col].ch = colour.to_string().chars().next().unwrap_or(' ');
+    }
+
+    fn lines(&self, colour: bool) -> Vec<String> {
+        self.cells
+            .iter()
+            .map(|row| {
+                let mut line = String::new();
+                for cell in row {
+                    match cell.colour {
+                        Some(c) if colour => line.push_str(&ansi::paint(c, &cell.ch.to_string())),
+                        _ => line.push(cell.ch),
+                    }
+                }
+                line.trim_end().to_string()
+            })
+            .collect()
+    }
+}
+
+fn draw(cube: &Cube) -> Canvas {
+    let mut canvas = Canvas::new();
+    let up = cube.get_face(Side::Up).stickers();
+    let front = cube.get_face(Side::Front).stickers();
+    let right = cube.get_face(Side::Right).stickers();
+
+    // The front face, its top left corner sits below the receding up face.
+    let top = DEPTH;
+    for row in 0..=6 {
+        for col in 0..=3 * CELL {
+            let on_row = row % 2 == 0;
+            let on_col = col % CELL == 0;
+            let ch = match (on_row, on_col) {
+                (true, true) => match (row, col) {
+                    (0, 0) => '┌',
+                    (0, c) if c == 3 * CELL => '┤',
+                    (0, _) => '┬',
+                    (6, 0) => '└',
+                    (6, c) if c == 3 * CELL => '┘',
+                    (6, _) => '┴',
+                    (_, 0) => '├',
+                    (_, c) if c == 3 * CELL => '┤',
+                    _ => '┼',
+                },
+                (true, false) => '─',
+                (false, true) => '│',
+                (false, false) => continue,
+            };
+            canvas.put(top + row, col, ch);
+        }
+    }
+    for (i, colour) in front.iter().enumerate() {
+        let (row, col) = (i / 3, i % 3);
+        canvas.sticker(top + 2 * row + 1, col * CELL + 2, 3, *colour);
+    }
+
+    // The up face, level 0 is the edge shared with the front face.
+    for level in 0..=3 {
+        let row = top - 2 * level;
+        if level > 0 {
+            for col in 0..=3 * CELL {
+                let ch = if col % CELL == 0 { '╱' } else { '─' };
+                canvas.put(row, 2 * level + col, ch);
+            }
+        }
+        if level < 3 {
+            for j in 0..=3 {
+                canvas.put(row - 1, 2 * level + 1 + j * CELL, '╱');
+            }
+        }
+    }
+    for (i, colour) in up.iter().enumerate() {
+        let (row, col) = (i / 3, i % 3);
+        let level = 2 - row;
+        canvas.sticker(top - 2 * level - 1, 2 * level + 3 + col * CELL, 3, *colour);
+    }
+
+    // The right face, depth 0 is the edge shared with the front face.
+    let left = 3 * CELL;
+    for depth in 0..=3 {
+        let col = left + 2 * depth;
+        if depth > 0 {
+            for row in 0..=6 {
+                let ch = if row % 2 == 0 { '╱' } else { '│' };
+                canvas.put(top - 2 * depth + row, col, ch);
+            }
+        }
+        if depth < 3 {
+            for i in 0..=3 {
+                canvas.put(top - 2 * depth - 1 + 2 * i, col + 1, '╱');
+            }
+        }
+    }
+    for (i, colour) in right.iter().enumerate() {
+        let (row, depth) = (i / 3, i % 3);
+        canvas.sticker(top - 2 * depth + 2 * row, left + 2 * depth + 1, 1, *colour);
+    }
+
+    canvas
+}
+
+/// Draws the up, front and right faces, and with [`IsoOptions::back`] a
+/// second cube next to it showing the down, left and back faces.
+pub fn render(cube: &Cube, options: &IsoOptions) -> String {
+    let mut lines = draw(cube).lines(options.colour);
+
+    if options.back {
+        // x2 y' brings down to the top, left to the front and back to the right.
+        let mut behind = cube.clone();
+        behind.rotate(Side::Right);
+        behind.rotate(Side::Right);
+        behind.rotate(Side::Down);
+
+        let plain = draw(cube).lines(false);
+        for ((line, plain), other) in lines
+            .iter_mut()
+            .zip(plain)
+            .zip(draw(&behind).lines(options.colour))
+        {
+            let pad = WIDTH + 4 - plain.chars().count();
+            line.push_str(&" ".repeat(pad));
+            line.push_str(&other);
+        }
+    }
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shows_three_faces() {
+        let out = render(&Cube::default(), &IsoOptions::default());
+
+        assert_eq!(out.matches('W').count(), 9);
+        assert_eq!(out.matches('G').count(), 9);
+        assert_eq!(out.matches('R').count(), 9);
+        assert_eq!(out.lines().count(), HEIGHT);
+    }
+
+    #[test]
+    fn back_view_shows_the_other_faces() {
+        let options = IsoOptions {
+            back: true,
+            ..IsoOptions::default()
+        };
+        let out = render(&Cube::default(), &options);
+
+        for letter in ['W', 'G', 'R', 'Y', 'O', 'B'].iter() {
+            assert_eq!(out.matches(*letter).count(), 9);
+        }
+    }
+}
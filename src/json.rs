@@ -0,0 +1,351 @@
+//! Just enough JSON to exchange data with other tools.
+
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter, Write},
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// Members in the order they were written.
+    Object(Vec<(String, Json)>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JsonError {
+    /// Where in the source, in bytes.
+    pub offset: usize,
+    pub expected: &'static str,
+}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "expected {} at byte {}", self.expected, self.offset)
+    }
+}
+
+impl Error for JsonError {}
+
+impl Json {
+    pub fn parse(source: &str) -> Result<Self, JsonError> {
+        let mut parser = Parser { source, offset: 0 };
+        let value = parser.value()?;
+        parser.whitespace();
+        if parser.offset != source.len() {
+            return Err(parser.error("the end"));
+        }
+        Ok(value)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    /// The number if it's a whole one that fits.
+    pub fn as_i64(&self) -> Option<i64> {
+        let number = self.as_f64()?;
+        if number.fract() == 0.0 && number.abs() < 9e15 {
+            Some(number as i64)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Self::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// The first member called `key`, if this is an object.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Self::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+/// Writes compact JSON.
+impl Display for Json {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => write!(f, "null"),
+            Self::Bool(value) => write!(f, "{}", value),
+            // JSON has no infinities or NaN.
+            Self::Number(number) if !number.is_finite() => write!(f, "null"),
+            Self::Number(number) => write!(f, "{}", number),
+            Self::String(string) => write_string(f, string),
+            Self::Array(items) => {
+                f.write_char('[')?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                f.write_char(']')
+            }
+            Self::Object(members) => {
+                f.write_char('{')?;
+                for (i, (name, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_string(f, name)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut Formatter<'_>, string: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in string.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    offset: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, expected: &'static str) -> JsonError {
+        JsonError {
+            offset: self.offset,
+            expected,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.source.as_bytes().get(self.offset).copied()
+    }
+
+    fn whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = self.peek() {
+            self.offset += 1;
+        }
+    }
+
+    /// Skips `expected` if it's next.
+    fn eat(&mut self, expected: &str) -> bool {
+        if self.source[self.offset..].starts_with(expected) {
+            self.offset += expected.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, JsonError> {
+        self.whitespace();
+        match self.peek() {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => self.string().map(Json::String),
+            Some(b'-') | Some(b'0'..=b'9') => self.number(),
+            _ if self.eat("null") => Ok(Json::Null),
+            _ if self.eat("true") => Ok(Json::Bool(true)),
+            _ if self.eat("false") => Ok(Json::Bool(false)),
+            _ => Err(self.error("a value")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, JsonError> {
+        self.offset += 1;
+        let mut members = Vec::new();
+        self.whitespace();
+        if self.eat("}") {
+            return Ok(Json::Object(members));
+        }
+
+        loop {
+            self.whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error("a member name"));
+            }
+            let name = self.string()?;
+            self.whitespace();
+            if !self.eat(":") {
+                return Err(self.error("`:`"));
+            }
+            members.push((name, self.value()?));
+
+            self.whitespace();
+            if self.eat("}") {
+                return Ok(Json::Object(members));
+            }
+            if !self.eat(",") {
+                return Err(self.error("`,` or `}`"));
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, JsonError> {
+        self.offset += 1;
+        let mut items = Vec::new();
+        self.whitespace();
+        if self.eat("]") {
+            return Ok(Json::Array(items));
+        }
+
+        loop {
+            items.push(self.value()?);
+            self.whitespace();
+            if self.eat("]") {
+                return Ok(Json::Array(items));
+            }
+            if !self.eat(",") {
+                return Err(self.error("`,` or `]`"));
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, JsonError> {
+        let start = self.offset;
+        while let Some(b'-') | Some(b'+') | Some(b'.') | Some(b'e') | Some(b'E')
+        | Some(b'0'..=b'9') = self.peek()
+        {
+            self.offset += 1;
+        }
+
+        match self.source[start..self.offset].parse() {
+            Ok(number) => Ok(Json::Number(number)),
+            Err(_) => {
+                self.offset = start;
+                Err(self.error("a number"))
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.offset += 1;
+        let mut string = String::new();
+
+        loop {
+            let rest = &self.source[self.offset..];
+            let c = match rest.chars().next() {
+                Some(c) => c,
+                None => return Err(self.error("`\"`")),
+            };
+            self.offset += c.len_utf8();
+
+            match c {
+                '"' => return Ok(string),
+                '\\' => string.push(self.escape()?),
+                c if (c as u32) < 0x20 => {
+                    self.offset -= 1;
+                    return Err(self.error("an escaped control character"));
+                }
+                c => string.push(c),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, JsonError> {
+        let c = match self.peek() {
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\u{8}',
+            Some(b'f') => '\u{c}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                self.offset += 1;
+                let high = self.hex()?;
+                if !(0xd800..0xdc00).contains(&high) {
+                    return char::from_u32(high).ok_or_else(|| self.error("a character"));
+                }
+                // A surrogate pair, for characters outside the BMP.
+                if !self.eat("\\u") {
+                    return Err(self.error("a low surrogate"));
+                }
+                let low = self.hex()?;
+                if !(0xdc00..0xe000).contains(&low) {
+                    return Err(self.error("a low surrogate"));
+                }
+                let c = 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00);
+                return char::from_u32(c).ok_or_else(|| self.error("a character"));
+            }
+            _ => return Err(self.error("an escape")),
+        };
+        self.offset += 1;
+        Ok(c)
+    }
+
+    fn hex(&mut self) -> Result<u32, JsonError> {
+        let digits = self
+            .source
+            .get(self.offset..self.offset + 4)
+            .ok_or_else(|| self.error("four hex digits"))?;
+        let value = u32::from_str_radix(digits, 16).map_err(|_| self.error("four hex digits"))?;
+        self.offset += 4;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_writes() {
+        let source = r#" {"a": [1, -2.5e1, true, null], "b": "x\"é😀\n", "c": {}} "#;
+        let json = Json::parse(source).unwrap();
+
+        assert_eq!(
+            json.get("a").unwrap().as_array().unwrap()[1].as_f64(),
+            Some(-25.0)
+        );
+        assert_eq!(json.get("b").unwrap().as_str(), Some("x\"é😀\n"));
+        assert_eq!(
+            json.to_string(),
+            r#"{"a":[1,-25,true,null],"b":"x\"é😀\n","c":{}}"#
+        );
+        assert_eq!(Json::parse(&json.to_string()).unwrap(), json);
+    }
+
+    #[test]
+    fn reports_where_it_went_wrong() {
+        let error = |source| Json::parse(source).unwrap_err().to_string();
+        assert_eq!(error("[1, 2"), "expected `,` or `]` at byte 5");
+        assert_eq!(error("{\"a\" 1}"), "expected `:` at byte 5");
+        assert_eq!(error("[1] x"), "expected the end at byte 4");
+        assert_eq!(error("\"abc"), "expected `\"` at byte 4");
+        assert_eq!(error("[-]"), "expected a number at byte 1");
+    }
+}
@@ -0,0 +1,330 @@
+//! Recognising the last layer once the first two are solved, as one of the
+//! OLL cases that orient it, the PLL cases that permute it or the COLL
+//! cases that solve its corners.
+//!
+//! Cases are told apart by the stickers of the top layer: for OLL which of
+//! them show the top colour, for PLL and COLL how far side stickers are
+//! from the side with their colour. Each case's pattern is that of the
+//! state its algorithm in the [`Database`] solves, so that's all there is
+//! to adding one.
+
+use crate::{
+    algoritm::{Algorithm, Move, MoveType, Side},
+    database::{Database, Entry, Set},
+    Colour, Cube,
+};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Recognition {
+    pub case: &'static Entry,
+    /// The turn of the top layer to do before the algorithm, if any.
+    pub auf: Algorithm,
+}
+
+impl Recognition {
+    /// The AUF followed by the case's algorithm.
+    pub fn solution(&self) -> Algorithm {
+        let mut moves = self.auf.moves.clone();
+        moves.extend(self.case.algorithm.moves.iter().cloned());
+        Algorithm::new(moves)
+    }
+}
+
+impl Display for Recognition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.auf.moves.is_empty() {
+            write!(f, "{}: {}", self.case, self.case.algorithm)
+        } else {
+            write!(
+                f,
+                "{} after {}: {}",
+                self.case, self.auf, self.case.algorithm
+            )
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecognitionError {
+    /// The first two layers aren't solved, with the last one on top.
+    F2lUnsolved,
+    /// No case of the set looks like this, so it can't be solved with one.
+    Unknown(Set),
+}
+
+impl Display for RecognitionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::F2lUnsolved => write!(f, "the first two layers aren't solved"),
+            Self::Unknown(set) => write!(f, "the last layer isn't one of the {} cases", set),
+        }
+    }
+}
+
+impl Error for RecognitionError {}
+
+/// The sides around the top layer, in the order `U` moves stickers.
+const AROUND: [Side; 4] = [Side::Front, Side::Left, Side::Back, Side::Right];
+
+/// Whether the first two layers are solved, with the last one on top.
+pub fn f2l_solved(cube: &Cube) -> bool {
+    let down = cube.get_face(Side::Down).stickers();
+    down.iter().all(|colour| *colour == down[4])
+        && AROUND.iter().all(|side| {
+            let stickers = cube.get_face(side.clone()).stickers();
+            stickers[3..].iter().all(|colour| *colour == stickers[4])
+        })
+}
+
+/// Whether what `set` solves is solved: the top layer oriented for OLL, its
+/// corners for COLL and everything up to a last AUF for PLL. The first two
+/// layers have to be solved too.
+pub fn is_done(cube: &Cube, set: Set) -> bool {
+    let solved = Cube::default();
+    f2l_solved(cube)
+        && match set {
+            Set::Pll => solved_up_to_auf(cube).is_solved(),
+            set => pattern(set, cube) == pattern(set, &solved),
+        }
+}
+
+/// The OLL case if the top layer isn't oriented yet, and the PLL case if it
+/// is, `None` when it's solved.
+pub fn recognise(cube: &Cube) -> Result<Option<Recognition>, RecognitionError> {
+    if !f2l_solved(cube) {
+        return Err(RecognitionError::F2lUnsolved);
+    }
+
+    let up = cube.get_face(Side::Up).stickers();
+    if up.iter().any(|colour| *colour != up[4]) {
+        return recognise_as(cube, Set::Oll).map(Some);
+    }
+    if solved_up_to_auf(cube).is_solved() {
+        return Ok(None);
+    }
+    recognise_as(cube, Set::Pll).map(Some)
+}
+
+/// The case of `set` the cube is in, with the AUF to do first. Only the
+/// last layer sets can be recognised.
+pub fn recognise_as(cube: &Cube, set: Set) -> Result<Recognition, RecognitionError> {
+    if !f2l_solved(cube) {
+        return Err(RecognitionError::F2lUnsolved);
+    }
+
+    let cases: Vec<_> = Database::builtin().set(set).collect();
+    let patterns: Vec<_> = cases
+        .iter()
+        .map(|case| pattern(set, &case_state(case)))
+        .collect();
+
+    for (auf, turned) in aufs(cube) {
+        let mine = match pattern(set, &turned) {
+            Some(mine) => mine,
+            None => continue,
+        };
+        if let Some(i) = patterns.iter().position(|p| p.as_ref() == Some(&mine)) {
+            return Ok(Recognition {
+                case: cases[i],
+                auf,
+            });
+        }
+    }
+    Err(RecognitionError::Unknown(set))
+}
+
+/// The cube turned back to solved if only the top layer is off, or as it is.
+fn solved_up_to_auf(cube: &Cube) -> Cube {
+    aufs(cube)
+        .map(|(_, cube)| cube)
+        .find(Cube::is_solved)
+        .unwrap_or_else(|| cube.clone())
+}
+
+/// The state the case's algorithm solves.
+fn case_state(case: &Entry) -> Cube {
+    let mut cube = Cube::default();
+    cube.execute_algorithm(case.algorithm.inverse());
+    cube.reorient();
+    cube
+}
+
+/// The cube after each of the four turns of the top layer, with the turn.
+fn aufs(cube: &Cube) -> impl Iterator<Item = (Algorithm, Cube)> + '_ {
+    (0..4).map(move |turns| {
+        let auf = match MoveType::from_quarter_turns(turns) {
+            Some(ty) => Algorithm::new(vec![Move::new(Side::Up, ty)]),
+            None => Algorithm::new(Vec::new()),
+        };
+        let mut turned = cube.clone();
+        turned.execute_algorithm(auf.clone());
+        (auf, turned)
+    })
+}
+
+/// The top layer stickers, the top face first and then the sides in the
+/// order of [`Cube::layer_stickers`].
+fn top_layer(cube: &Cube) -> Vec<Colour> {
+    Cube::layer_stickers(Side::Up)
+        .into_iter()
+        .map(|(side, sticker)| cube.get_face(side).stickers()[sticker])
+        .collect()
+}
+
+/// The side stickers of the top corners, in the order of [`top_layer`].
+const CORNER_STICKERS: [usize; 8] = [9, 11, 12, 14, 15, 17, 18, 20];
+
+/// What tells the cases of `set` apart, `None` if the cube can't be in one
+/// of them.
+fn pattern(set: Set, cube: &Cube) -> Option<Vec<u8>> {
+    let top = top_layer(cube);
+    let up = top[4];
+    let sides = AROUND.clone().map(|side| cube.get_face(side).stickers()[4]);
+    match set {
+        Set::Oll => Some(top.iter().map(|colour| (*colour == up) as u8).collect()),
+        Set::Pll => relative(
+            up,
+            &sides,
+            &top,
+            &[9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20],
+        ),
+        Set::Coll => {
+            // The corners can be told apart by their side stickers alone.
+            if [1, 3, 5, 7].iter().any(|&i| top[i] != up) {
+                return None;
+            }
+            relative(up, &sides, &top, &CORNER_STICKERS)
+        }
+        Set::F2l => None,
+    }
+}
+
+/// The COLL case of the top corners whatever the edges are, with the AUF to
+/// do first, or `None` if they're solved but for an AUF. The colours of the
+/// top and of the sides in the order `U` moves them are given, since the
+/// centres needn't be where they belong.
+///
+/// COLL algorithms solve the corners the same with any edges, so this is
+/// CMLL recognition too.
+pub fn recognise_corners(
+    cube: &Cube,
+    up: Colour,
+    sides: [Colour; 4],
+) -> Result<Option<Recognition>, RecognitionError> {
+    let corners = |cube: &Cube| relative(up, &sides, &top_layer(cube), &CORNER_STICKERS);
+    let solved = Cube::default();
+    let solved = relative(
+        Colour::White,
+        &AROUND
+            .clone()
+            .map(|side| solved.get_face(side).stickers()[4]),
+        &top_layer(&solved),
+        &CORNER_STICKERS,
+    );
+    if aufs(cube).any(|(_, turned)| corners(&turned) == solved) {
+        return Ok(None);
+    }
+
+    let cases: Vec<_> = Database::builtin().set(Set::Coll).collect();
+    let patterns: Vec<_> = cases
+        .iter()
+        .map(|case| pattern(Set::Coll, &case_state(case)))
+        .collect();
+    for (auf, turned) in aufs(cube) {
+        let mine = match corners(&turned) {
+            Some(mine) => mine,
+            None => continue,
+        };
+        if let Some(i) = patterns.iter().position(|p| p.as_ref() == Some(&mine)) {
+            return Ok(Some(Recognition {
+                case: cases[i],
+                auf,
+            }));
+        }
+    }
+    Err(RecognitionError::Unknown(Set::Coll))
+}
+
+/// The top layer stickers at `indices` as 4 for the `up` colour, and
+/// otherwise as how many turns of the top layer they are from the side with
+/// their colour, of `sides` in the order `U` moves them. These are shifted
+/// to be as small as they get, so the last AUF doesn't matter.
+fn relative(up: Colour, sides: &[Colour; 4], top: &[Colour], indices: &[usize]) -> Option<Vec<u8>> {
+    // The first 9 stickers are on the top face.
+    let offsets = indices
+        .iter()
+        .map(|&i| {
+            if top[i] == up {
+                return Some(None);
+            }
+            let home = sides.iter().position(|side| *side == top[i])?;
+            Some(Some(home + 4 - (i - 9) / 3))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    (0..4)
+        .map(|shift| {
+            offsets
+                .iter()
+                .map(|offset| offset.map_or(4, |offset| ((offset + shift) % 4) as u8))
+                .collect::<Vec<_>>()
+        })
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(cube: &Cube, alg: &str) -> Cube {
+        let mut cube = cube.clone();
+        cube.execute_algorithm(Algorithm::parse(alg).unwrap());
+        cube
+    }
+
+    #[test]
+    fn recognises_every_case_from_every_side() {
+        let solved = Cube::default();
+        for set in [Set::Oll, Set::Pll, Set::Coll].iter() {
+            for case in Database::builtin().set(*set) {
+                for (before, after) in [("", ""), ("U", "U'"), ("U2", "U"), ("U'", "U2")].iter() {
+                    let alg = case.algorithm.inverse().to_string();
+                    let mut cube = apply(&solved, &format!("{} {} {}", before, alg, after));
+                    cube.reorient();
+
+                    let recognition =
+                        recognise_as(&cube, *set).unwrap_or_else(|e| panic!("{}: {}", case, e));
+                    assert_eq!(recognition.case, case, "{} {} {}", before, alg, after);
+
+                    cube.execute_algorithm(recognition.solution());
+                    cube.reorient();
+                    assert!(is_done(&cube, *set), "{}", case);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn needs_the_first_two_layers() {
+        let solved = Cube::default();
+        assert_eq!(recognise(&solved), Ok(None));
+        assert_eq!(recognise(&apply(&solved, "U2")), Ok(None));
+        assert_eq!(
+            recognise(&apply(&solved, "R")),
+            Err(RecognitionError::F2lUnsolved)
+        );
+
+        let sune = recognise(&apply(&solved, "R U2 R' U' R U' R' U"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(sune.to_string(), "OLL 27 after U': R U R' U R U2 R'");
+        assert_eq!(
+            recognise_as(&apply(&solved, "F R U R' U' F'"), Set::Coll).map(|r| r.case),
+            Err(RecognitionError::Unknown(Set::Coll))
+        );
+    }
+}
@@ -0,0 +1,1119 @@
+pub mod algoritm;
+pub mod ansi;
+pub mod bld;
+pub mod cross;
+pub mod cstimer;
+pub mod cubie;
+pub mod database;
+pub mod f2l;
+pub mod iso;
+pub mod json;
+pub mod last_layer;
+pub mod masked;
+pub mod playback;
+pub mod pochmann;
+pub mod reconstruction;
+pub mod repl;
+pub mod roux;
+pub mod schedule;
+pub mod scramble;
+pub mod search;
+pub mod session;
+pub mod solver;
+pub mod svg;
+pub mod term;
+pub mod timer;
+pub mod trainer;
+pub mod tui;
+pub mod zz;
+
+use algoritm::*;
+use std::fmt::{Display, Formatter};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Colour {
+    Green,
+    Blue,
+    Yellow,
+    White,
+    Red,
+    Orange,
+}
+
+impl Display for Colour {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Self::Green => write!(f, "G")?,
+            Self::Blue => write!(f, "B")?,
+            Self::Yellow => write!(f, "Y")?,
+            Self::White => write!(f, "W")?,
+            Self::Red => write!(f, "R")?,
+            Self::Orange => write!(f, "O")?,
+        }
+
+        Ok(())
+    }
+}
+
+impl Colour {
+    /// Parses the letter used by `Display for Colour`.
+    pub fn parse(source: char) -> Option<Self> {
+        match source {
+            'G' => Some(Self::Green),
+            'B' => Some(Self::Blue),
+            'Y' => Some(Self::Yellow),
+            'W' => Some(Self::White),
+            'R' => Some(Self::Red),
+            'O' => Some(Self::Orange),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Face {
+    ul: Colour,
+    um: Colour,
+    ur: Colour,
+    ml: Colour,
+    mm: Colour,
+    mr: Colour,
+    bl: Colour,
+    bm: Colour,
+    br: Colour,
+}
+
+impl Display for Face {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        writeln!(f, "{}|{}|{}", self.ul, self.um, self.ur)?;
+        writeln!(f, "-+-+-")?;
+        writeln!(f, "{}|{}|{}", self.ml, self.mm, self.mr)?;
+        writeln!(f, "-+-+-")?;
+        writeln!(f, "{}|{}|{}", self.bl, self.bm, self.br)?;
+
+        Ok(())
+    }
+}
+
+/// Ordering of contents of lines are in clockwise order
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Line(Colour, Colour, Colour);
+
+impl Face {
+    pub fn fill(colour: Colour) -> Self {
+        Face {
+            ul: colour,
+            um: colour,
+            ur: colour,
+            ml: colour,
+            mm: colour,
+            mr: colour,
+            bl: colour,
+            bm: colour,
+            br: colour,
+        }
+    }
+    pub fn from_stickers([ul, um, ur, ml, mm, mr, bl, bm, br]: [Colour; 9]) -> Self {
+        Face {
+            ul,
+            um,
+            ur,
+            ml,
+            mm,
+            mr,
+            bl,
+            bm,
+            br,
+        }
+    }
+    /// The stickers of the face in reading order, row by row.
+    pub fn stickers(&self) -> [Colour; 9] {
+        [
+            self.ul, self.um, self.ur, self.ml, self.mm, self.mr, self.bl, self.bm, self.br,
+        ]
+    }
+    pub fn rot_clockwise(&mut self) {
+        let new_face = Face {
+            ul: self.bl,
+            um: self.ml,
+            ur: self.ul,
+            ml: self.bm,
+            mm: self.mm,
+            mr: self.um,
+            bl: self.br,
+            bm: self.mr,
+            br: self.ur,
+        };
+        *self = new_face;
+    }
+    pub fn rot_counterclockwise(&mut self) {
+        let new_face = Face {
+            ul: self.ur,
+            um: self.mr,
+            ur: self.br,
+            ml: self.um,
+            mm: self.mm,
+            mr: self.bm,
+            bl: self.ul,
+            bm: self.ml,
+            br: self.bl,
+        };
+        *self = new_face;
+    }
+    // ordering of contents of lines are in clockwise order
+    pub fn get_left_line(&self) -> Line {
+        Line(self.bl, self.ml, self.ul)
+    }
+    pub fn get_upper_line(&self) -> Line {
+        Line(self.ul, self.um, self.ur)
+    }
+    pub fn get_right_line(&self) -> Line {
+        Line(self.ur, self.mr, self.br)
+    }
+    pub fn get_bottom_line(&self) -> Line {
+        Line(self.br, self.bm, self.bl)
+    }
+    pub fn set_left_line(&mut self, line: Line) {
+        self.bl = line.0;
+        self.ml = line.1;
+        self.ul = line.2;
+    }
+    pub fn set_upper_line(&mut self, line: Line) {
+        self.ul = line.0;
+        self.um = line.1;
+        self.ur = line.2;
+    }
+    pub fn set_right_line(&mut self, line: Line) {
+        self.ur = line.0;
+        self.mr = line.1;
+        self.br = line.2;
+    }
+    pub fn set_bottom_line(&mut self, line: Line) {
+        self.br = line.0;
+        self.bm = line.1;
+        self.bl = line.2;
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Cube {
+    up: Face,
+    front: Face,
+    left: Face,
+    back: Face,
+    right: Face,
+    down: Face,
+}
+
+impl Display for Cube {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        writeln!(f, "      {}|{}|{}", self.up.ul, self.up.um, self.up.ur)?;
+        writeln!(f, "      -+-+-")?;
+        writeln!(f, "      {}|{}|{}", self.up.ml, self.up.mm, self.up.mr)?;
+        writeln!(f, "      -+-+-")?;
+        writeln!(f, "      {}|{}|{}", self.up.bl, self.up.bm, self.up.br)?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{}|{}|{} {}|{}|{} {}|{}|{} {}|{}|{}",
+            self.left.ul,
+            self.left.um,
+            self.left.ur,
+            self.front.ul,
+            self.front.um,
+            self.front.ur,
+            self.right.ul,
+            self.right.um,
+            self.right.ur,
+            self.back.ul,
+            self.back.um,
+            self.back.ur
+        )?;
+        writeln!(f, "-+-+- -+-+- -+-+- -+-+-")?;
+        writeln!(
+            f,
+            "{}|{}|{} {}|{}|{} {}|{}|{} {}|{}|{}",
+            self.left.ml,
+            self.left.mm,
+            self.left.mr,
+            self.front.ml,
+            self.front.mm,
+            self.front.mr,
+            self.right.ml,
+            self.right.mm,
+            self.right.mr,
+            self.back.ml,
+            self.back.mm,
+            self.back.mr
+        )?;
+        writeln!(f, "-+-+- -+-+- -+-+- -+-+-")?;
+        writeln!(
+            f,
+            "{}|{}|{} {}|{}|{} {}|{}|{} {}|{}|{}",
+            self.left.bl,
+            self.left.bm,
+            self.left.br,
+            self.front.bl,
+            self.front.bm,
+            self.front.br,
+            self.right.bl,
+            self.right.bm,
+            self.right.br,
+            self.back.bl,
+            self.back.bm,
+            self.back.br
+        )?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "      {}|{}|{}",
+            self.down.ul, self.down.um, self.down.ur
+        )?;
+        writeln!(f, "      -+-+-")?;
+        writeln!(
+            f,
+            "      {}|{}|{}",
+            self.down.ml, self.down.mm, self.down.mr
+        )?;
+        writeln!(f, "      -+-+-")?;
+        writeln!(
+            f,
+            "      {}|{}|{}",
+            self.down.bl, self.down.bm, self.down.br
+        )?;
+
+        Ok(())
+    }
+}
+
+impl Default for Cube {
+    fn default() -> Self {
+        Cube {
+            up: Face::fill(Colour::White),
+            front: Face::fill(Colour::Green),
+            left: Face::fill(Colour::Orange),
+            back: Face::fill(Colour::Blue),
+            right: Face::fill(Colour::Red),
+            down: Face::fill(Colour::Yellow),
+        }
+    }
+}
+
+/// Why a facelet string couldn't be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FaceletError {
+    /// There should be exactly 54 stickers.
+    Length(usize),
+    /// A character that isn't one of the colour letters.
+    Colour(char),
+}
+
+impl Display for FaceletError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Self::Length(n) => write!(f, "expected 54 stickers, found {}", n),
+            Self::Colour(c) => write!(f, "`{}` isn't a colour, use one of W Y G B R O", c),
+        }
+    }
+}
+
+impl std::error::Error for FaceletError {}
+
+impl Cube {
+    /// The order of the faces in a facelet string.
+    pub const FACELET_ORDER: [Side; 6] = [
+        Side::Up,
+        Side::Right,
+        Side::Front,
+        Side::Down,
+        Side::Left,
+        Side::Back,
+    ];
+
+    /// All 54 stickers, face by face in [`Cube::FACELET_ORDER`], each face
+    /// in the reading order of the net.
+    pub fn facelets(&self) -> [Colour; 54] {
+        let mut facelets = [Colour::White; 54];
+        for (i, side) in Self::FACELET_ORDER.iter().enumerate() {
+            facelets[i * 9..i * 9 + 9].copy_from_slice(&self.get_face(side.clone()).stickers());
+        }
+        facelets
+    }
+
+    pub fn from_facelets(facelets: &[Colour; 54]) -> Self {
+        let mut cube = Cube::default();
+        for (i, side) in Self::FACELET_ORDER.iter().enumerate() {
+            let mut stickers = [Colour::White; 9];
+            stickers.copy_from_slice(&facelets[i * 9..i * 9 + 9]);
+            *cube.get_face_mut(side.clone()) = Face::from_stickers(stickers);
+        }
+        cube
+    }
+
+    /// The stickers as 54 colour letters, see [`Cube::facelets`].
+    ///
+    /// ```
+    /// # use cube::Cube;
+    /// let solved = Cube::default().to_facelet_string();
+    /// assert_eq!(&solved[..12], "WWWWWWWWWRRR");
+    /// ```
+    pub fn to_facelet_string(&self) -> String {
+        self.facelets().iter().map(|c| c.to_string()).collect()
+    }
+
+    /// Reads the format written by [`Cube::to_facelet_string`], ignoring
+    /// whitespace. The stickers aren't checked to form a solvable cube.
+    pub fn parse_facelets(source: &str) -> Result<Self, FaceletError> {
+        let colours = source
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| Colour::parse(c).ok_or(FaceletError::Colour(c)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if colours.len() != 54 {
+            return Err(FaceletError::Length(colours.len()));
+        }
+
+        let mut facelets = [Colour::White; 54];
+        facelets.copy_from_slice(&colours);
+        Ok(Self::from_facelets(&facelets))
+    }
+
+    pub fn get_face(&self, side: Side) -> &Face {
+        match side {
+            Side::Up => &self.up,
+            Side::Down => &self.down,
+            Side::Front => &self.front,
+            Side::Back => &self.back,
+            Side::Right => &self.right,
+            Side::Left => &self.left,
+        }
+    }
+
+    pub fn get_face_mut(&mut self, side: Side) -> &mut Face {
+        match side {
+            Side::Up => &mut self.up,
+            Side::Down => &mut self.down,
+            Side::Front => &mut self.front,
+            Side::Back => &mut self.back,
+            Side::Right => &mut self.right,
+            Side::Left => &mut self.left,
+        }
+    }
+
+    pub fn get_up_lines(&self) -> [Line; 4] {
+        [
+            self.front.get_upper_line(),
+            self.left.get_upper_line(),
+            self.back.get_upper_line(),
+            self.right.get_upper_line(),
+        ]
+    }
+
+    pub fn get_down_lines(&self) -> [Line; 4] {
+        [
+            self.front.get_bottom_line(),
+            self.right.get_bottom_line(),
+            self.back.get_bottom_line(),
+            self.left.get_bottom_line(),
+        ]
+    }
+
+    pub fn get_front_lines(&self) -> [Line; 4] {
+        [
+            self.up.get_bottom_line(),
+            self.right.get_left_line(),
+            self.down.get_upper_line(),
+            self.left.get_right_line(),
+        ]
+    }
+
+    pub fn get_back_lines(&self) -> [Line; 4] {
+        [
+            self.up.get_upper_line(),
+            self.left.get_left_line(),
+            self.down.get_bottom_line(),
+            self.right.get_right_line(),
+        ]
+    }
+
+    pub fn get_right_lines(&self) -> [Line; 4] {
+        [
+            self.front.get_right_line(),
+            self.up.get_right_line(),
+            self.back.get_left_line(),
+            self.down.get_right_line(),
+        ]
+    }
+
+    pub fn get_left_lines(&self) -> [Line; 4] {
+        [
+            self.up.get_left_line(),
+            self.front.get_left_line(),
+            self.down.get_left_line(),
+            self.back.get_right_line(),
+        ]
+    }
+
+    pub fn set_up_lines(&mut self, [a, b, c, d]: [Line; 4]) {
+        self.front.set_upper_line(a);
+        self.left.set_upper_line(b);
+        self.back.set_upper_line(c);
+        self.right.set_upper_line(d);
+    }
+
+    pub fn set_down_lines(&mut self, [a, b, c, d]: [Line; 4]) {
+        self.front.set_bottom_line(a);
+        self.right.set_bottom_line(b);
+        self.back.set_bottom_line(c);
+        self.left.set_bottom_line(d);
+    }
+
+    pub fn set_front_lines(&mut self, [a, b, c, d]: [Line; 4]) {
+        self.up.set_bottom_line(a);
+        self.right.set_left_line(b);
+        self.down.set_upper_line(c);
+        self.left.set_right_line(d);
+    }
+
+    pub fn set_back_lines(&mut self, [a, b, c, d]: [Line; 4]) {
+        self.up.set_upper_line(a);
+        self.left.set_left_line(b);
+        self.down.set_bottom_line(c);
+        self.right.set_right_line(d);
+    }
+
+    pub fn set_right_lines(&mut self, [a, b, c, d]: [Line; 4]) {
+        self.front.set_right_line(a);
+        self.up.set_right_line(b);
+        self.back.set_left_line(c);
+        self.down.set_right_line(d);
+    }
+
+    pub fn set_left_lines(&mut self, [a, b, c, d]: [Line; 4]) {
+        self.up.set_left_line(a);
+        self.front.set_left_line(b);
+        self.down.set_left_line(c);
+        self.back.set_right_line(d);
+    }
+
+    pub fn get_lines(&self, side: Side) -> [Line; 4] {
+        match side {
+            Side::Up => self.get_up_lines(),
+            Side::Down => self.get_down_lines(),
+            Side::Front => self.get_front_lines(),
+            Side::Back => self.get_back_lines(),
+            Side::Right => self.get_right_lines(),
+            Side::Left => self.get_left_lines(),
+        }
+    }
+
+    pub fn set_lines(&mut self, side: Side, lines: [Line; 4]) {
+        match side {
+            Side::Up => self.set_up_lines(lines),
+            Side::Down => self.set_down_lines(lines),
+            Side::Front => self.set_front_lines(lines),
+            Side::Back => self.set_back_lines(lines),
+            Side::Right => self.set_right_lines(lines),
+            Side::Left => self.set_left_lines(lines),
+        }
+    }
+
+    pub fn turn_side(&mut self, side: Side) {
+        self.get_face_mut(side.clone()).rot_clockwise();
+
+        let mut lines = self.get_lines(side.clone());
+        lines.rotate_right(1);
+        self.set_lines(side, lines);
+    }
+
+    pub fn turn_side_prime(&mut self, side: Side) {
+        self.get_face_mut(side.clone()).rot_counterclockwise();
+
+        let mut lines = self.get_lines(side.clone());
+        lines.rotate_left(1);
+        self.set_lines(side, lines);
+    }
+
+    pub fn turn_side_twice(&mut self, side: Side) {
+        let face = self.get_face_mut(side.clone());
+        face.rot_clockwise();
+        face.rot_clockwise();
+
+        let mut lines = self.get_lines(side.clone());
+        lines.rotate_right(2);
+        self.set_lines(side, lines);
+    }
+
+    /// Rotates the whole cube the same way as turning `side` clockwise, so
+    /// `Right` is x, `Up` is y and `Front` is z, while the opposite sides
+    /// rotate the other way.
+    pub fn rotate(&mut self, side: Side) {
+        match side {
+            Side::Right => {
+                let Cube {
+                    up,
+                    front,
+                    left,
+                    back,
+                    right,
+                    down,
+                } = self.clone();
+                self.up = front;
+                self.front = down;
+                self.down = back;
+                self.back = up;
+                self.back.rot_clockwise();
+                self.back.rot_clockwise();
+                self.down.rot_clockwise();
+                self.down.rot_clockwise();
+                self.right = right;
+                self.right.rot_clockwise();
+                self.left = left;
+                self.left.rot_counterclockwise();
+            }
+            Side::Up => {
+                let Cube {
+                    up,
+                    front,
+                    left,
+                    back,
+                    right,
+                    down,
+                } = self.clone();
+                self.front = right;
+                self.left = front;
+                self.back = left;
+                self.right = back;
+                self.up = up;
+                self.up.rot_clockwise();
+                self.down = down;
+                self.down.rot_counterclockwise();
+            }
+            Side::Front => {
+                let Cube {
+                    up,
+                    front,
+                    left,
+                    back,
+                    right,
+                    down,
+                } = self.clone();
+                self.right = up;
+                self.down = right;
+                self.left = down;
+                self.up = left;
+                for face in [
+                    &mut self.right,
+                    &mut self.down,
+                    &mut self.left,
+                    &mut self.up,
+                ]
+                .iter_mut()
+                {
+                    face.rot_clockwise();
+                }
+                self.front = front;
+                self.front.rot_clockwise();
+                self.back = back;
+                self.back.rot_counterclockwise();
+            }
+            Side::Left => (0..3).for_each(|_| self.rotate(Side::Right)),
+            Side::Down => (0..3).for_each(|_| self.rotate(Side::Up)),
+            Side::Back => (0..3).for_each(|_| self.rotate(Side::Front)),
+        }
+    }
+
+    // these functions shouldn't be needed, review before removing
+    //      Hjalte 25-02-21
+    /*
+    pub fn turn_right(&mut self) {
+        self.right.rot_clockwise();
+        let front_right_line: Line = self.front.get_right_line();
+        let up_right_line: Line = self.up.get_right_line();
+        let back_left_line: Line = self.back.get_left_line();
+        let down_left_line: Line = self.down.get_left_line();
+        self.up.set_right_line(front_right_line);
+        self.back.set_left_line(up_right_line);
+        self.down.set_left_line(back_left_line);
+        self.front.set_right_line(down_left_line);
+    }
+    pub fn turn_right_prime(&mut self) {
+        self.right.rot_clockwise();
+        let front_right_line: Line = self.front.get_right_line();
+        let up_right_line: Line = self.up.get_right_line();
+        let back_left_line: Line = self.back.get_left_line();
+        let down_left_line: Line = self.down.get_left_line();
+        self.down.set_left_line(front_right_line);
+        self.front.set_right_line(up_right_line);
+        self.up.set_right_line(back_left_line);
+        self.back.set_right_line(down_left_line);
+    }
+    pub fn turn_right_twice(&mut self) {
+        self.right.rot_clockwise();
+        let front_right_line: Line = self.front.get_right_line();
+        let up_right_line: Line = self.up.get_right_line();
+        let back_left_line: Line = self.back.get_left_line();
+        let down_left_line: Line = self.down.get_left_line();
+        self.back.set_left_line(front_right_line);
+        self.down.set_left_line(up_right_line);
+        self.front.set_right_line(back_left_line);
+        self.up.set_right_line(down_left_line);
+    }
+    pub fn turn_front(&mut self) {
+        self.front.rot_clockwise();
+        let left_right_line: Line = self.left.get_right_line();
+        let up_bottom_line: Line = self.up.get_bottom_line();
+        let right_left_line: Line = self.right.get_left_line();
+        let down_bottom_line: Line = self.down.get_bottom_line();
+        self.up.set_bottom_line(left_right_line);
+        self.right.set_left_line(up_bottom_line);
+        self.down.set_bottom_line(right_left_line);
+        self.left.set_right_line(down_bottom_line);
+    }
+    pub fn turn_front_prime(&mut self) {
+        self.front.rot_clockwise();
+        let left_right_line: Line = self.left.get_right_line();
+        let up_bottom_line: Line = self.up.get_bottom_line();
+        let right_left_line: Line = self.right.get_left_line();
+        let down_bottom_line: Line = self.down.get_bottom_line();
+        self.down.set_bottom_line(left_right_line);
+        self.left.set_left_line(up_bottom_line);
+        self.up.set_bottom_line(right_left_line);
+        self.right.set_right_line(down_bottom_line);
+    }
+    pub fn turn_left(&mut self) {
+        self.left.rot_clockwise();
+        let back_right_line: Line = self.back.get_right_line();
+        let up_left_line: Line = self.up.get_left_line();
+        let front_left_line: Line = self.front.get_left_line();
+        let down_right_line: Line = self.down.get_right_line();
+        self.up.set_left_line(back_right_line);
+        self.front.set_left_line(up_left_line);
+        self.down.set_right_line(front_left_line);
+        self.back.set_right_line(down_right_line);
+    }
+    pub fn turn_left_prime(&mut self) {
+        self.left.rot_clockwise();
+        let back_right_line: Line = self.back.get_right_line();
+        let up_left_line: Line = self.up.get_left_line();
+        let front_left_line: Line = self.front.get_left_line();
+        let down_right_line: Line = self.down.get_right_line();
+        self.down.set_left_line(back_right_line);
+        self.back.set_left_line(up_left_line);
+        self.up.set_right_line(front_left_line);
+        self.front.set_right_line(down_right_line);
+    }
+    pub fn turn_back(&mut self) {
+        self.back.rot_clockwise();
+        let up_upper_line: Line = self.up.get_upper_line();
+        let left_left_line: Line = self.left.get_left_line();
+        let down_upper_line: Line = self.down.get_upper_line();
+        let right_right_line: Line = self.right.get_right_line();
+        self.left.set_left_line(up_upper_line);
+        self.down.set_upper_line(left_left_line);
+        self.right.set_right_line(down_upper_line);
+        self.up.set_upper_line(right_right_line);
+    }
+    pub fn turn_back_prime(&mut self) {
+        self.back.rot_clockwise();
+        let up_upper_line: Line = self.up.get_upper_line();
+        let left_left_line: Line = self.left.get_left_line();
+        let down_upper_line: Line = self.down.get_upper_line();
+        let right_right_line: Line = self.right.get_right_line();
+        self.right.set_left_line(up_upper_line);
+        self.up.set_upper_line(left_left_line);
+        self.left.set_right_line(down_upper_line);
+        self.down.set_upper_line(right_right_line);
+    }
+    pub fn turn_down(&mut self) {
+        self.down.rot_clockwise();
+        let left_bottom_line: Line = self.left.get_bottom_line();
+        let back_bottom_line: Line = self.back.get_bottom_line();
+        let right_bottom_line: Line = self.right.get_bottom_line();
+        let front_bottom_line: Line = self.front.get_bottom_line();
+        self.back.set_bottom_line(right_bottom_line);
+        self.right.set_bottom_line(front_bottom_line);
+        self.front.set_bottom_line(left_bottom_line);
+        self.left.set_bottom_line(back_bottom_line);
+    }
+    pub fn turn_down_prime(&mut self) {
+        self.down.rot_clockwise();
+        let left_bottom_line: Line = self.left.get_bottom_line();
+        let back_bottom_line: Line = self.back.get_bottom_line();
+        let right_bottom_line: Line = self.right.get_bottom_line();
+        let front_bottom_line: Line = self.front.get_bottom_line();
+        self.front.set_bottom_line(right_bottom_line);
+        self.left.set_bottom_line(front_bottom_line);
+        self.back.set_bottom_line(left_bottom_line);
+        self.right.set_bottom_line(back_bottom_line);
+    }
+    pub fn turn_up(&mut self) {
+        self.up.rot_clockwise();
+        let left_upper_line: Line = self.left.get_upper_line();
+        let back_upper_line: Line = self.back.get_upper_line();
+        let right_upper_line: Line = self.right.get_upper_line();
+        let front_upper_line: Line = self.front.get_upper_line();
+        self.back.set_upper_line(left_upper_line);
+        self.right.set_upper_line(back_upper_line);
+        self.front.set_upper_line(right_upper_line);
+        self.left.set_upper_line(front_upper_line);
+    }
+    pub fn turn_up_prime(&mut self) {
+        self.up.rot_clockwise();
+        let left_upper_line: Line = self.left.get_upper_line();
+        let back_upper_line: Line = self.back.get_upper_line();
+        let right_upper_line: Line = self.right.get_upper_line();
+        let front_upper_line: Line = self.front.get_upper_line();
+        self.front.set_upper_line(left_upper_line);
+        self.left.set_upper_line(back_upper_line);
+        self.back.set_upper_line(right_upper_line);
+        self.right.set_upper_line(front_upper_line);
+    }
+    */
+
+    pub fn execute_move(&mut self, mv: Move) {
+        if matches!(mv.layers, Layers::Wide | Layers::Slice) {
+            for mv in mv.to_outer() {
+                self.execute_move(mv);
+            }
+            return;
+        }
+        if mv.is_rotation() {
+            for _ in 0..mv.ty.quarter_turns() {
+                self.rotate(mv.side.clone());
+            }
+            return;
+        }
+
+        match &mv.ty {
+            MoveType::Normal => self.turn_side(mv.side.clone()),
+            MoveType::Prime => self.turn_side_prime(mv.side.clone()),
+            MoveType::Double => self.turn_side_twice(mv.side.clone()),
+        }
+    }
+
+    pub fn execute_algorithm(&mut self, alg: Algorithm) {
+        for mv in alg.moves {
+            self.execute_move(mv);
+        }
+    }
+
+    /// The stickers moved by turning `side`, as the face they're on and
+    /// their index in [`Face::stickers`].
+    pub fn layer_stickers(side: Side) -> Vec<(Side, usize)> {
+        const UPPER: [usize; 3] = [0, 1, 2];
+        const RIGHT: [usize; 3] = [2, 5, 8];
+        const BOTTOM: [usize; 3] = [6, 7, 8];
+        const LEFT: [usize; 3] = [0, 3, 6];
+
+        let lines = match side {
+            Side::Up => [
+                (Side::Front, UPPER),
+                (Side::Left, UPPER),
+                (Side::Back, UPPER),
+                (Side::Right, UPPER),
+            ],
+            Side::Down => [
+                (Side::Front, BOTTOM),
+                (Side::Right, BOTTOM),
+                (Side::Back, BOTTOM),
+                (Side::Left, BOTTOM),
+            ],
+            Side::Front => [
+                (Side::Up, BOTTOM),
+                (Side::Right, LEFT),
+                (Side::Down, UPPER),
+                (Side::Left, RIGHT),
+            ],
+            Side::Back => [
+                (Side::Up, UPPER),
+                (Side::Left, LEFT),
+                (Side::Down, BOTTOM),
+                (Side::Right, RIGHT),
+            ],
+            Side::Right => [
+                (Side::Front, RIGHT),
+                (Side::Up, RIGHT),
+                (Side::Back, LEFT),
+                (Side::Down, RIGHT),
+            ],
+            Side::Left => [
+                (Side::Up, LEFT),
+                (Side::Front, LEFT),
+                (Side::Down, LEFT),
+                (Side::Back, RIGHT),
+            ],
+        };
+
+        let mut stickers: Vec<_> = (0..9).map(|i| (side.clone(), i)).collect();
+        for (face, line) in lines.iter() {
+            stickers.extend(line.iter().map(|i| (face.clone(), *i)));
+        }
+
+        stickers
+    }
+
+    /// Lays out the net like `Display for Cube`, drawing every sticker with
+    /// `cell`, which should return strings of the same width.
+    pub fn net_with<F: Fn(Side, usize, Colour) -> String>(&self, cell: F) -> String {
+        let row = |side: Side, row: usize| -> String {
+            let stickers = self.get_face(side.clone()).stickers();
+            (0..3)
+                .map(|col| cell(side.clone(), row * 3 + col, stickers[row * 3 + col]))
+                .collect()
+        };
+        // Skip ANSI escape sequences, which take up no room on screen.
+        let mut escape = false;
+        let width = row(Side::Up, 0)
+            .chars()
+            .filter(|c| match (escape, c) {
+                (false, '\x1b') => {
+                    escape = true;
+                    false
+                }
+                (true, 'm') => {
+                    escape = false;
+                    false
+                }
+                (escape, _) => !escape,
+            })
+            .count();
+        let indent = " ".repeat(width + 1);
+        let mut out = String::new();
+
+        for i in 0..3 {
+            out.push_str(&format!("{}{}\n", indent, row(Side::Up, i)));
+        }
+        out.push('\n');
+        for i in 0..3 {
+            let sides = [Side::Left, Side::Front, Side::Right, Side::Back];
+            let line: Vec<_> = sides.iter().map(|side| row(side.clone(), i)).collect();
+            out.push_str(&line.join(" "));
+            out.push('\n');
+        }
+        out.push('\n');
+        for i in 0..3 {
+            out.push_str(&format!("{}{}\n", indent, row(Side::Down, i)));
+        }
+
+        out
+    }
+
+    /// The side with the `colour` centre.
+    pub fn centre_side(&self, colour: Colour) -> Option<Side> {
+        Self::FACELET_ORDER
+            .iter()
+            .find(|side| self.get_face((*side).clone()).mm == colour)
+            .cloned()
+    }
+
+    /// Which edges are bad, flipped relative to the front and back centres.
+    pub fn edge_orientation(&self) -> Result<zz::EdgeOrientation, cubie::StateError> {
+        zz::EdgeOrientation::of(self)
+    }
+
+    /// Rotates the whole cube so the centres are where they are on a solved
+    /// cube, leaving it as it is if no rotation does that.
+    pub fn reorient(&mut self) {
+        let solved = Cube::default();
+        let centres = |cube: &Cube| {
+            Self::FACELET_ORDER
+                .clone()
+                .map(|side| cube.get_face(side).mm)
+        };
+
+        for rotation in Algorithm::orientations() {
+            let mut rotated = self.clone();
+            rotated.execute_algorithm(rotation);
+            if centres(&rotated) == centres(&solved) {
+                *self = rotated;
+                return;
+            }
+        }
+    }
+
+    pub fn is_solved(&self) -> bool {
+        [
+            &self.up,
+            &self.front,
+            &self.left,
+            &self.back,
+            &self.right,
+            &self.down,
+        ]
+        .iter()
+        .all(|face| face.stickers().iter().all(|colour| *colour == face.mm))
+    }
+}
+
+/// The solved cube after `scramble`, for tests.
+#[cfg(test)]
+pub(crate) fn scrambled(scramble: &str) -> Cube {
+    let mut cube = Cube::default();
+    cube.execute_algorithm(Algorithm::parse(scramble).unwrap());
+    cube
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(alg: &str) -> Cube {
+        let mut cube = Cube::default();
+        cube.execute_algorithm(Algorithm::parse(alg).unwrap());
+        cube
+    }
+
+    #[test]
+    fn turns_return_to_solved() {
+        for side in ["U", "D", "R", "L", "F", "B"].iter() {
+            assert!(
+                apply(&format!("{0} {0}'", side)).is_solved(),
+                "{0} {0}'",
+                side
+            );
+            assert!(
+                apply(&format!("{0}2 {0}2", side)).is_solved(),
+                "{0}2 {0}2",
+                side
+            );
+        }
+    }
+
+    const SIDES: [Side; 6] = [
+        Side::Up,
+        Side::Down,
+        Side::Front,
+        Side::Back,
+        Side::Left,
+        Side::Right,
+    ];
+
+    #[test]
+    fn checkerboard() {
+        let cube = apply("R2 L2 U2 D2 F2 B2");
+
+        for side in SIDES.iter() {
+            let own = Cube::default().get_face(side.clone()).mm;
+            for (i, colour) in cube.get_face(side.clone()).stickers().iter().enumerate() {
+                assert_eq!(*colour == own, i % 2 == 0, "{:?} {}", side, i);
+            }
+        }
+    }
+
+    #[test]
+    fn layer_stickers_are_the_ones_that_move() {
+        let before = apply("R2 L2 U2 D2 F2 B2 R U");
+
+        for side in SIDES.iter() {
+            let layer = Cube::layer_stickers(side.clone());
+            let mut after = before.clone();
+            after.execute_move(Move::new(side.clone(), MoveType::Normal));
+
+            assert_eq!(layer.len(), 21);
+            for face in SIDES.iter() {
+                for i in 0..9 {
+                    if !layer.contains(&(face.clone(), i)) {
+                        assert_eq!(
+                            after.get_face(face.clone()).stickers()[i],
+                            before.get_face(face.clone()).stickers()[i]
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn turns_move_the_right_stickers() {
+        let turned = |alg: &str| {
+            let mut cube = Cube::default();
+            cube.execute_algorithm(Algorithm::parse(alg).unwrap());
+            cube
+        };
+        let column = |cube: &Cube, side: Side, stickers: [usize; 3]| {
+            let face = cube.get_face(side).stickers();
+            stickers.map(|i| face[i])
+        };
+
+        // D takes the front's bottom row to the right.
+        let cube = turned("D");
+        assert_eq!(column(&cube, Side::Right, [6, 7, 8]), [Colour::Green; 3]);
+        // B takes the top row down the left, L the left column to the front.
+        let cube = turned("B");
+        assert_eq!(column(&cube, Side::Left, [0, 3, 6]), [Colour::White; 3]);
+        let cube = turned("L");
+        assert_eq!(column(&cube, Side::Front, [0, 3, 6]), [Colour::White; 3]);
+        // F brings the left's colour onto the top, not the front.
+        let cube = turned("F");
+        assert_eq!(column(&cube, Side::Up, [6, 7, 8]), [Colour::Orange; 3]);
+        assert_eq!(cube.get_face(Side::Front), &Face::fill(Colour::Green));
+
+        for side in ["U", "D", "R", "L", "F", "B"] {
+            assert_eq!(turned(&[side; 4].join(" ")), Cube::default(), "{}4", side);
+            let sexy = format!("R {0} R' {0}' ", side);
+            assert_eq!(turned(&sexy.repeat(6)), Cube::default(), "({})6", sexy);
+        }
+    }
+
+    #[test]
+    fn rotations_can_be_moved_to_the_end() {
+        for source in ["y R U x2 F", "z R x' U y2 F", "x y z R U F L D B"].iter() {
+            let alg = Algorithm::parse(source).unwrap();
+            let rotations = alg.moves.iter().filter(|mv| mv.is_rotation()).cloned();
+
+            let mut moved = Cube::default();
+            moved.execute_algorithm(alg.without_rotations());
+            moved.execute_algorithm(Algorithm::new(rotations.collect()));
+            assert_eq!(moved, apply(source));
+        }
+        assert!(apply("x y2 z'").is_solved());
+        assert!(!apply("x y2 z'")
+            .to_facelet_string()
+            .starts_with("WWWWWWWWW"));
+    }
+
+    #[test]
+    fn rotation() {
+        let initial_face = Face {
+            ul: Colour::Blue,
+            um: Colour::Red,
+            ur: Colour::Yellow,
+            ml: Colour::Orange,
+            mm: Colour::White,
+            mr: Colour::Blue,
+            bl: Colour::Yellow,
+            bm: Colour::White,
+            br: Colour::Red,
+        };
+        let mut face1 = initial_face.clone();
+        let mut face2 = initial_face.clone();
+        let clock_turned_face = Face {
+            ul: Colour::Yellow,
+            um: Colour::Orange,
+            ur: Colour::Blue,
+            ml: Colour::White,
+            mm: Colour::White,
+            mr: Colour::Red,
+            bl: Colour::Red,
+            bm: Colour::Blue,
+            br: Colour::Yellow,
+        };
+        let cc_turned_face = Face {
+            ul: Colour::Yellow,
+            um: Colour::Blue,
+            ur: Colour::Red,
+            ml: Colour::Red,
+            mm: Colour::White,
+            mr: Colour::White,
+            bl: Colour::Blue,
+            bm: Colour::Orange,
+            br: Colour::Yellow,
+        };
+        face1.rot_clockwise();
+        face2.rot_counterclockwise();
+        assert_eq!(face1, clock_turned_face);
+        assert_eq!(face2, cc_turned_face);
+    }
+}
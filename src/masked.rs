@@ -0,0 +1,210 @@
+//! Cubes with some stickers left out, for goals that only care about part of
+//! the cube, like the stickers shown in grey by Cube Explorer.
+//!
+//! In facelet strings the left out stickers are written `X`.
+
+use crate::{
+    algoritm::{Algorithm, Move},
+    cubie::{StateError, CORNERS, CORNER_FACELETS, EDGES, EDGE_FACELETS},
+    search::{self, Mask, SearchOptions},
+    Colour, Cube, FaceletError,
+};
+use std::fmt::{self, Display, Formatter};
+
+/// A sticker's colour, or any colour at all.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MaskedColour {
+    Any,
+    Known(Colour),
+}
+
+impl MaskedColour {
+    /// Parses the colour letters, and `X` for any colour.
+    pub fn parse(source: char) -> Option<Self> {
+        match source {
+            'X' => Some(Self::Any),
+            c => Colour::parse(c).map(Self::Known),
+        }
+    }
+
+    /// Whether `colour` is this colour, or this is any colour.
+    pub fn matches(&self, colour: Colour) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Known(known) => *known == colour,
+        }
+    }
+}
+
+impl Display for MaskedColour {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Any => write!(f, "X"),
+            Self::Known(colour) => write!(f, "{}", colour),
+        }
+    }
+}
+
+/// The 54 stickers like [`Cube::facelets`], some of which can be any colour.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MaskedCube {
+    pub facelets: [MaskedColour; 54],
+}
+
+impl MaskedCube {
+    /// The stickers of `cube` on the pieces in `mask`, leaving out the rest.
+    pub fn masked(cube: &Cube, mask: &Mask) -> Self {
+        let mut masked = Self::from(cube);
+        let mut leave_out = |facelet: usize| masked.facelets[facelet] = MaskedColour::Any;
+        for i in (0..CORNERS).filter(|&i| !mask.corners[i]) {
+            CORNER_FACELETS[i].iter().for_each(|&f| leave_out(f));
+        }
+        for i in (0..EDGES).filter(|&i| !mask.edges[i]) {
+            EDGE_FACELETS[i].iter().for_each(|&f| leave_out(f));
+        }
+        if !mask.centres {
+            (0..6).for_each(|face| leave_out(face * 9 + 4));
+        }
+        masked
+    }
+
+    /// Reads the format of [`Cube::parse_facelets`], with `X` for any
+    /// colour.
+    ///
+    /// ```
+    /// # use cube::{masked::MaskedCube, Cube};
+    /// let top = "XXXXXXXXX RRRXXXXXX GGGXXXXXX XXXXXXXXX OOOXXXXXX BBBXXXXXX";
+    /// let masked = MaskedCube::parse(top).unwrap();
+    /// assert!(masked == Cube::default());
+    /// ```
+    pub fn parse(source: &str) -> Result<Self, FaceletError> {
+        let stickers = source
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| MaskedColour::parse(c).ok_or(FaceletError::Colour(c)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if stickers.len() != 54 {
+            return Err(FaceletError::Length(stickers.len()));
+        }
+
+        let mut facelets = [MaskedColour::Any; 54];
+        facelets.copy_from_slice(&stickers);
+        Ok(Self { facelets })
+    }
+
+    /// Whether every sticker that isn't left out has the colour it has on
+    /// `cube`.
+    pub fn matches(&self, cube: &Cube) -> bool {
+        self.facelets
+            .iter()
+            .zip(cube.facelets())
+            .all(|(masked, colour)| masked.matches(colour))
+    }
+
+    /// The pieces that have all their stickers, where they'd be on `cube`
+    /// once solved.
+    fn solved_pieces(&self, cube: &Cube) -> Mask {
+        let facelets = cube.facelets();
+        let solved = |facelet: usize| {
+            self.facelets[facelet] == MaskedColour::Known(facelets[facelet / 9 * 9 + 4])
+        };
+        Mask {
+            corners: CORNER_FACELETS.map(|stickers| stickers.iter().all(|&f| solved(f))),
+            edges: EDGE_FACELETS.map(|stickers| stickers.iter().all(|&f| solved(f))),
+            centres: (0..6).all(|face| solved(face * 9 + 4)),
+        }
+    }
+}
+
+impl From<&Cube> for MaskedCube {
+    fn from(cube: &Cube) -> Self {
+        Self {
+            facelets: cube.facelets().map(MaskedColour::Known),
+        }
+    }
+}
+
+impl PartialEq<Cube> for MaskedCube {
+    fn eq(&self, cube: &Cube) -> bool {
+        self.matches(cube)
+    }
+}
+
+/// Writes the 54 stickers like [`Cube::to_facelet_string`].
+impl Display for MaskedCube {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for sticker in &self.facelets {
+            write!(f, "{}", sticker)?;
+        }
+        Ok(())
+    }
+}
+
+/// Every algorithm of `moves`, up to `options.max_length` long, taking
+/// `cube` to a state matching `target`, shortest first, up to
+/// `options.limit` of them. Pieces the target has solved prune the search,
+/// so it's quickest when most of what it asks for is solved pieces.
+///
+/// ```
+/// # use cube::{algoritm::Algorithm, masked::{self, MaskedCube}, search::{self, SearchOptions}, Cube};
+/// // Any <R, U> way of turning the UFR corner where the UBR one is.
+/// let target = "XXWXXXXXX XXGXXXXXX XXXXXXXXX XXXXXXXXX XXXXXXXXX RXXXXXXXX";
+/// let target = MaskedCube::parse(target).unwrap();
+/// let moves = search::turns(&Algorithm::parse("R U").unwrap());
+/// let options = SearchOptions { max_length: 2, limit: 10 };
+///
+/// let found = masked::find(&Cube::default(), &target, &moves, &options).unwrap();
+/// let found: Vec<String> = found.iter().map(|alg| alg.to_string()).collect();
+/// assert_eq!(found, vec!["U'"]);
+/// ```
+pub fn find(
+    cube: &Cube,
+    target: &MaskedCube,
+    moves: &[Move],
+    options: &SearchOptions,
+) -> Result<Vec<Algorithm>, StateError> {
+    let goal = |cube: &Cube| target.matches(cube);
+    search::find(cube, moves, &target.solved_pieces(cube), &goal, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scrambled;
+
+    #[test]
+    fn leaves_out_stickers() {
+        let f2l = MaskedCube::masked(&Cube::default(), &Mask::f2l());
+        assert!(f2l == scrambled("R U R' U R U2 R'"));
+        assert!(f2l != scrambled("R U R'"));
+        assert_eq!(&f2l.to_string()[..18], "XXXXWXXXXXXXRRRRRR");
+
+        let parsed = MaskedCube::parse(&f2l.to_string()).unwrap();
+        assert_eq!(parsed, f2l);
+        assert_eq!(MaskedCube::parse("XQ"), Err(FaceletError::Colour('Q')));
+        assert_eq!(MaskedCube::parse("XX"), Err(FaceletError::Length(2)));
+    }
+
+    #[test]
+    fn finds_algorithms_reaching_the_target() {
+        let moves = search::turns(&Algorithm::parse("R U").unwrap());
+        let options = SearchOptions {
+            max_length: 6,
+            limit: 10,
+        };
+
+        // Solving everything but the top corners.
+        let mut mask = Mask::all();
+        mask.corners[..4].fill(false);
+        let target = MaskedCube::masked(&Cube::default(), &mask);
+        let cube = scrambled("R U' L' U R' U' L U2");
+        let found = find(&cube, &target, &moves, &options).unwrap();
+        assert_eq!(found[0].to_string(), "U'");
+
+        // Targets needn't be solved pieces.
+        let target = MaskedCube::from(&scrambled("R U R'"));
+        let found = find(&Cube::default(), &target, &moves, &options).unwrap();
+        assert_eq!(found[0].to_string(), "R U R'");
+    }
+}
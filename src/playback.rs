@@ -0,0 +1,355 @@
+//! Move by move playback of an [`Algorithm`] in the terminal.
+
+use crate::{
+    algoritm::{Algorithm, Layers, Move, Side},
+    ansi,
+    term::{self, RawMode},
+    Colour, Cube,
+};
+use std::{
+    io::{self, BufRead, Read, Write},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Steps through an algorithm, keeping the cube in the state after the
+/// moves played so far. Stepping back applies the inverse of the last move.
+#[derive(Clone, Debug)]
+pub struct Playback {
+    cube: Cube,
+    moves: Vec<Move>,
+    position: usize,
+}
+
+impl Playback {
+    pub fn new(cube: Cube, alg: Algorithm) -> Self {
+        Self {
+            cube,
+            moves: alg.moves,
+            position: 0,
+        }
+    }
+
+    pub fn cube(&self) -> &Cube {
+        &self.cube
+    }
+
+    /// How many moves have been played.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// The move that brought the cube into its current state.
+    pub fn last_move(&self) -> Option<&Move> {
+        self.position.checked_sub(1).map(|i| &self.moves[i])
+    }
+
+    /// Plays the next move, returns `false` at the end of the algorithm.
+    pub fn forward(&mut self) -> bool {
+        match self.moves.get(self.position) {
+            Some(mv) => {
+                self.cube.execute_move(mv.clone());
+                self.position += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Undoes the last move, returns `false` at the start of the algorithm.
+    pub fn back(&mut self) -> bool {
+        match self.last_move() {
+            Some(mv) => {
+                self.cube.execute_move(mv.inverse());
+                self.position -= 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The algorithm with the last played move in brackets, the cube with
+    /// the layer it turned in brackets, and the position.
+    pub fn frame(&self, colour: bool) -> String {
+        let highlight = match self.last_move() {
+            // Only outer turns move a single face's layer to pick out.
+            Some(mv) if mv.layers == Layers::Outer => Cube::layer_stickers(mv.side.clone()),
+            _ => Vec::new(),
+        };
+        let cell = |side: Side, i: usize, c: Colour| {
+            let text = if highlight.contains(&(side, i)) {
+                format!("[{}]", c)
+            } else {
+                format!(" {} ", c)
+            };
+            if colour {
+                ansi::paint(c, &text)
+            } else {
+                text
+            }
+        };
+
+        let moves: Vec<_> = self
+            .moves
+            .iter()
+            .enumerate()
+            .map(|(i, mv)| {
+                if i + 1 == self.position {
+                    format!("[{}]", mv)
+                } else {
+                    mv.to_string()
+                }
+            })
+            .collect();
+
+        format!(
+            "{}\n\n{}\nmove {}/{}\n",
+            moves.join(" "),
+            self.cube.net_with(cell),
+            self.position,
+            self.len()
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlaybackOptions {
+    /// Play on its own, waiting this long between moves. Without a delay,
+    /// every step waits for a command on the input, and with one `run` plays
+    /// straight through while `run_live` still takes keys.
+    pub delay: Option<Duration>,
+    pub colour: bool,
+    /// Clear the terminal before drawing each frame.
+    pub clear: bool,
+}
+
+const HELP: &str = "[enter]/n: next, b: back, s: start, e: end, q: quit";
+const LIVE_HELP: &str = "space: pause, n: next, b: back, s: start, e: end, q: quit";
+
+/// Carries out a command from `HELP`. `None` if there's no such command,
+/// and `Some(false)` to quit.
+fn command(playback: &mut Playback, command: &str) -> Option<bool> {
+    match command {
+        "" | "n" => {
+            playback.forward();
+        }
+        "b" => {
+            playback.back();
+        }
+        "s" => while playback.back() {},
+        "e" => while playback.forward() {},
+        "q" => return Some(false),
+        _ => return None,
+    }
+    Some(true)
+}
+
+/// Plays `playback`, reading commands from `input` when there's no delay.
+pub fn run<R: BufRead, W: Write>(
+    mut playback: Playback,
+    options: &PlaybackOptions,
+    mut input: R,
+    mut output: W,
+) -> io::Result<()> {
+    loop {
+        if options.clear {
+            write!(output, "\x1b[2J\x1b[H")?;
+        }
+        write!(output, "{}", playback.frame(options.colour))?;
+
+        if let Some(delay) = options.delay {
+            output.flush()?;
+            if playback.position() == playback.len() {
+                return Ok(());
+            }
+            thread::sleep(delay);
+            playback.forward();
+            continue;
+        }
+
+        write!(output, "{}\n> ", HELP)?;
+        output.flush()?;
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        match command(&mut playback, line.trim()) {
+            Some(true) => {}
+            Some(false) => return Ok(()),
+            None => writeln!(output, "unknown command `{}`", line.trim())?,
+        }
+    }
+}
+
+/// Playback going on its own, driven by `key` and `tick` so it can be tested
+/// without a terminal or a clock.
+struct AutoPlay {
+    playback: Playback,
+    delay: Duration,
+    paused: bool,
+    /// When the next move is due.
+    next: Instant,
+}
+
+impl AutoPlay {
+    /// Handles a key pressed at `now`, returns `false` to quit. Stepping
+    /// pauses, so the step can be looked at.
+    fn key(&mut self, key: u8, now: Instant) -> bool {
+        let step = match key {
+            term::ESCAPE | term::CTRL_C | b'q' => return false,
+            b' ' => {
+                self.paused = !self.paused;
+                self.next = now + self.delay;
+                return true;
+            }
+            b'\r' | b'\n' | b'n' => "n",
+            b'b' => "b",
+            b's' => "s",
+            b'e' => "e",
+            _ => return true,
+        };
+        self.paused = true;
+        command(&mut self.playback, step);
+        true
+    }
+
+    /// Plays the moves due by `now`, pausing at the end so it can still be
+    /// stepped back through.
+    fn tick(&mut self, now: Instant) {
+        while !self.paused && self.next <= now {
+            self.next += self.delay;
+            if !self.playback.forward() {
+                self.paused = true;
+            }
+        }
+    }
+}
+
+/// Plays `playback` on its own with `delay` between moves, taking keys to
+/// pause and step it meanwhile. `input` has to be the terminal's stdin,
+/// since that's what gets switched to raw mode.
+pub fn run_live<R: Read, W: Write>(
+    playback: Playback,
+    delay: Duration,
+    options: &PlaybackOptions,
+    mut input: R,
+    mut output: W,
+) -> io::Result<()> {
+    let raw = RawMode::enable(1)?;
+    let mut play = AutoPlay {
+        playback,
+        delay,
+        paused: false,
+        next: Instant::now() + delay,
+    };
+
+    loop {
+        if options.clear {
+            write!(output, "{}", term::CLEAR)?;
+        }
+        let state = if play.paused { "paused" } else { "playing" };
+        write!(
+            output,
+            "{}{}\n{}\n",
+            play.playback.frame(options.colour),
+            state,
+            LIVE_HELP
+        )?;
+        output.flush()?;
+
+        match raw.read_key(&mut input)? {
+            Some(key) => {
+                if !play.key(key, Instant::now()) {
+                    return Ok(());
+                }
+            }
+            None => play.tick(Instant::now()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_forward_and_back() {
+        let alg = Algorithm::parse("R U R' U'").unwrap();
+        let mut playback = Playback::new(Cube::default(), alg);
+
+        while playback.forward() {}
+        assert_eq!(playback.position(), 4);
+        assert!(!playback.cube().is_solved());
+
+        while playback.back() {}
+        assert_eq!(playback.position(), 0);
+        assert!(playback.cube().is_solved());
+    }
+
+    #[test]
+    fn frame_highlights_the_turned_layer() {
+        let alg = Algorithm::parse("R U").unwrap();
+        let mut playback = Playback::new(Cube::default(), alg);
+        playback.forward();
+
+        let frame = playback.frame(false);
+        assert!(frame.starts_with("[R] U\n"));
+        assert_eq!(frame.matches('[').count(), 1 + 21);
+    }
+
+    #[test]
+    fn keys_pause_and_step_auto_play() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let alg = Algorithm::parse("R U R'").unwrap();
+        let mut play = AutoPlay {
+            playback: Playback::new(Cube::default(), alg),
+            delay: Duration::from_millis(100),
+            paused: false,
+            next: at(100),
+        };
+
+        play.tick(at(250));
+        assert_eq!(play.playback.position(), 2);
+        assert!(play.key(b'b', at(260)));
+        play.tick(at(1000));
+        assert_eq!(play.playback.position(), 1);
+        assert!(play.paused);
+
+        play.key(b' ', at(1000));
+        play.tick(at(1350));
+        assert_eq!(play.playback.position(), 3);
+        assert!(play.paused);
+        play.key(b's', at(1400));
+        assert_eq!(play.playback.position(), 0);
+        assert!(!play.key(b'q', at(1500)));
+    }
+
+    #[test]
+    fn commands_from_input() {
+        let alg = Algorithm::parse("R U").unwrap();
+        let playback = Playback::new(Cube::default(), alg);
+        let mut output = Vec::new();
+
+        run(
+            playback,
+            &PlaybackOptions::default(),
+            "n\nn\nb\nq\n".as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("move 2/2"));
+        assert!(output.ends_with(&format!("move 1/2\n{}\n> ", HELP)));
+    }
+}
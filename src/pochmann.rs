@@ -0,0 +1,573 @@
+//! Blindfolded solutions from a memo, swapping the buffer with one target
+//! at a time the ways Stefan Pochmann came up with.
+//!
+//! Old Pochmann sets each target up to a helper spot with moves that leave
+//! the buffer alone, swaps the two with a T perm for edges or a Y perm for
+//! corners, and undoes the setup. M2/R2 swaps with just a half turn of the
+//! layer holding both the buffer and the helper, doing the corners then the
+//! edges. The half turn swaps two other pieces too, so every second target
+//! on one of them is found where the last half turn left it, targets on
+//! them need algorithms of their own, and with parity a last algorithm puts
+//! them back.
+//!
+//! Either way the swaps for the first kind of piece move some of the other
+//! kind, so that kind is traced again once the first is solved. With
+//! parity, the letters can differ from the memo of the scrambled cube.
+
+use crate::{
+    algoritm::{Algorithm, Move, MoveType},
+    bld::{self, BldOptions, Scheme, Stickers},
+    cubie::{CubieCube, StateError},
+    Colour, Cube,
+};
+use std::{
+    fmt::{self, Display, Formatter},
+    sync::OnceLock,
+};
+
+/// Where each facelet's sticker comes from, as in [`Cube::facelets`].
+type Perm = [u8; 54];
+
+/// The longest setups searched for.
+const MAX_SETUP: usize = 5;
+
+/// The colours standing for the digits of facelet numbers in base 6, so
+/// that three cubes show where a move takes every sticker.
+const DIGITS: [Colour; 6] = [
+    Colour::White,
+    Colour::Yellow,
+    Colour::Green,
+    Colour::Blue,
+    Colour::Red,
+    Colour::Orange,
+];
+
+fn permutation(alg: &Algorithm) -> Perm {
+    let mut perm = [0; 54];
+    let mut place = 1;
+    for _ in 0..3 {
+        let facelets = std::array::from_fn(|i| DIGITS[i / place % 6]);
+        let mut cube = Cube::from_facelets(&facelets);
+        cube.execute_algorithm(alg.clone());
+        for (i, colour) in cube.facelets().iter().enumerate() {
+            let digit = DIGITS.iter().position(|c| c == colour).unwrap();
+            perm[i] += (digit * place) as u8;
+        }
+        place *= 6;
+    }
+    perm
+}
+
+/// `a` followed by `b`.
+fn then(a: &Perm, b: &Perm) -> Perm {
+    std::array::from_fn(|i| a[b[i] as usize])
+}
+
+fn identity() -> Perm {
+    std::array::from_fn(|i| i as u8)
+}
+
+/// The face turns, quarter turns first.
+fn face_turns() -> Vec<(Move, Perm)> {
+    Cube::FACELET_ORDER
+        .iter()
+        .flat_map(|side| {
+            [MoveType::Normal, MoveType::Prime, MoveType::Double].map(|ty| {
+                let mv = Move::new(side.clone(), ty);
+                let perm = permutation(&Algorithm::new(vec![mv.clone()]));
+                (mv, perm)
+            })
+        })
+        .collect()
+}
+
+/// The shortest `setup` of at most [`MAX_SETUP`] face turns for each goal,
+/// such that `setup`, then `inner`, then undoing `setup` does the goal.
+fn conjugates(inner: &Perm, goals: &[Perm]) -> Vec<Option<Algorithm>> {
+    struct Search<'a> {
+        inner: &'a Perm,
+        goals: &'a [Perm],
+        moves: Vec<(Move, Perm)>,
+        found: Vec<Option<Algorithm>>,
+        path: Vec<Move>,
+    }
+
+    impl Search<'_> {
+        fn visit(&mut self, setup: &Perm, depth: usize) {
+            if depth == 0 {
+                let inner = self.inner;
+                for (goal, found) in self.goals.iter().zip(&mut self.found) {
+                    // The setup then inner is the goal then the setup.
+                    if found.is_none()
+                        && (0..54).all(|i| setup[inner[i] as usize] == goal[setup[i] as usize])
+                    {
+                        *found = Some(Algorithm::new(self.path.clone()));
+                    }
+                }
+                return;
+            }
+            for m in 0..self.moves.len() {
+                let (mv, perm) = &self.moves[m];
+                if self.path.last().is_some_and(|last| last.side == mv.side) {
+                    continue;
+                }
+                let next = then(setup, perm);
+                self.path.push(mv.clone());
+                self.visit(&next, depth - 1);
+                self.path.pop();
+            }
+        }
+    }
+
+    let mut search = Search {
+        inner,
+        goals,
+        moves: face_turns(),
+        found: vec![None; goals.len()],
+        path: Vec::new(),
+    };
+    for depth in 0..=MAX_SETUP {
+        if search.found.iter().all(Option::is_some) {
+            break;
+        }
+        search.visit(&identity(), depth);
+    }
+    search.found
+}
+
+/// A named algorithm, or setup moves.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Part {
+    pub name: Option<&'static str>,
+    pub moves: Algorithm,
+}
+
+impl Part {
+    fn moves(moves: Algorithm) -> Self {
+        Self { name: None, moves }
+    }
+}
+
+/// Writes the name in brackets, or else the moves.
+impl Display for Part {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.name {
+            Some(name) => write!(f, "[{}]", name),
+            None => write!(f, "{}", self.moves),
+        }
+    }
+}
+
+/// The moves solving one target, or the parity.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub label: String,
+    pub parts: Vec<Part>,
+}
+
+impl Step {
+    pub fn algorithm(&self) -> Algorithm {
+        Algorithm::new(
+            self.parts
+                .iter()
+                .flat_map(|part| part.moves.moves.clone())
+                .collect(),
+        )
+    }
+}
+
+/// Writes the label then the parts, like `edge C: R2 D' [T perm] D R2`.
+impl Display for Step {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self
+            .parts
+            .iter()
+            .filter(|part| part.name.is_some() || !part.moves.moves.is_empty())
+            .map(|part| part.to_string())
+            .collect();
+        write!(f, "{}: {}", self.label, parts.join(" "))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    pub steps: Vec<Step>,
+}
+
+impl Solution {
+    /// Every step's moves, one after the other.
+    pub fn algorithm(&self) -> Algorithm {
+        Algorithm::new(
+            self.steps
+                .iter()
+                .flat_map(|step| step.algorithm().moves)
+                .collect(),
+        )
+    }
+
+    /// Whether applying the solution to `cube` solves it.
+    pub fn solves(&self, cube: &Cube) -> bool {
+        let mut cube = cube.clone();
+        cube.execute_algorithm(self.algorithm());
+        cube.is_solved()
+    }
+}
+
+/// Writes a step a line.
+impl Display for Solution {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let steps: Vec<String> = self.steps.iter().map(|step| step.to_string()).collect();
+        write!(f, "{}", steps.join("\n"))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Method {
+    OldPochmann,
+    M2R2,
+}
+
+impl Method {
+    /// Reads `op` or `m2`.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "op" => Some(Self::OldPochmann),
+            "m2" => Some(Self::M2R2),
+            _ => None,
+        }
+    }
+
+    /// The memo options with the method's buffers: ULB and UR for Old
+    /// Pochmann, UFR and DF for M2/R2.
+    pub fn options(&self, scheme: Scheme) -> BldOptions {
+        let (corner_buffer, edge_buffer) = match self {
+            Self::OldPochmann => (2, 0),
+            Self::M2R2 => (0, 5),
+        };
+        BldOptions {
+            scheme,
+            corner_buffer,
+            edge_buffer,
+        }
+    }
+}
+
+/// Swapping the buffer with each sticker of corners or edges.
+struct Swapper {
+    corners: bool,
+    buffer: usize,
+    /// The moves for each target sticker, in Speffz order, and none for
+    /// the buffer's.
+    targets: Vec<Option<Vec<Part>>>,
+    /// Where the rest of the swap, besides the buffer and the helper, takes
+    /// each sticker.
+    partners: [usize; 24],
+}
+
+impl Swapper {
+    /// Sets targets up to `swap`, which swaps the buffer at `buffer` with a
+    /// helper and maybe other pieces of the kind. Targets on the other
+    /// pieces are cycled with the buffer and helper by `cycle` set up,
+    /// then swapped, unless they're in `special`.
+    fn new<const N: usize>(
+        stickers: Stickers<N>,
+        buffer: usize,
+        swap: (&'static str, &str),
+        cycle: Option<(&'static str, &str)>,
+        special: &[(usize, &str)],
+    ) -> Self {
+        let parse = |moves: &str| Algorithm::parse(moves).unwrap();
+        let sticker = |facelet: usize| (0..24).find(|&s| bld::facelet::<N>(s) == facelet);
+        let swap_part = Part {
+            name: Some(swap.0),
+            moves: parse(swap.1),
+        };
+        let swap = permutation(&swap_part.moves);
+
+        // The swap less its swap of the buffer and the helper.
+        let first = stickers.of(buffer)[0];
+        let helper = (0..54)
+            .find(|&i| swap[i] as usize == bld::facelet::<N>(first))
+            .and_then(sticker)
+            .unwrap();
+        let mut extra = swap;
+        for turns in 0..N {
+            for s in [
+                stickers.turned(first, turns),
+                stickers.turned(helper, turns),
+            ] {
+                let facelet = bld::facelet::<N>(s);
+                extra[facelet] = facelet as u8;
+            }
+        }
+        let partners =
+            std::array::from_fn(|s| sticker(extra[bld::facelet::<N>(s)] as usize).unwrap_or(s));
+
+        // Swapping the buffer with the target, then the rest of the swap.
+        let goal = |target: usize| {
+            let mut goal = identity();
+            for turns in 0..N {
+                let a = bld::facelet::<N>(stickers.turned(first, turns));
+                let b = bld::facelet::<N>(stickers.turned(target, turns));
+                goal.swap(a, b);
+            }
+            then(&goal, &extra)
+        };
+        let plain: Vec<usize> = (0..24)
+            .filter(|&s| stickers.piece(s) != buffer && partners[s] == s)
+            .collect();
+        let setups = conjugates(&swap, &plain.iter().map(|&s| goal(s)).collect::<Vec<_>>());
+        let cycles: Vec<usize> = (0..24)
+            .filter(|&s| partners[s] != s && !special.iter().any(|(t, _)| *t == s))
+            .collect();
+        let cycle_setups = match cycle {
+            Some((_, moves)) => {
+                // The cycle is the goal undone by the swap.
+                let goals: Vec<Perm> = cycles.iter().map(|&s| then(&goal(s), &swap)).collect();
+                conjugates(&permutation(&parse(moves)), &goals)
+            }
+            None => vec![None; cycles.len()],
+        };
+
+        let mut targets = vec![None; 24];
+        for (&s, setup) in plain.iter().zip(setups) {
+            let setup = setup.expect("no setup for a target");
+            targets[s] = Some(vec![
+                Part::moves(setup.clone()),
+                swap_part.clone(),
+                Part::moves(setup.inverse()),
+            ]);
+        }
+        for (&s, setup) in cycles.iter().zip(cycle_setups) {
+            let (name, moves) = cycle.unwrap();
+            let setup = setup.expect("no setup for a cycle");
+            targets[s] = Some(vec![
+                Part::moves(setup.clone()),
+                Part {
+                    name: Some(name),
+                    moves: parse(moves),
+                },
+                Part::moves(setup.inverse()),
+                swap_part.clone(),
+            ]);
+        }
+        for &(s, moves) in special {
+            targets[s] = Some(vec![Part::moves(parse(moves))]);
+        }
+
+        Self {
+            corners: N == 3,
+            buffer,
+            targets,
+            partners,
+        }
+    }
+
+    /// The steps solving the pieces of `cube`.
+    fn steps(&self, cube: &Cube, scheme: &Scheme) -> Vec<Step> {
+        let (kind, letters, targets) = if self.corners {
+            (
+                "corner",
+                &scheme.corners,
+                Stickers::corners().targets(cube, self.buffer, true),
+            )
+        } else {
+            (
+                "edge",
+                &scheme.edges,
+                Stickers::edges().targets(cube, self.buffer, true),
+            )
+        };
+        targets
+            .iter()
+            .enumerate()
+            .map(|(i, &target)| {
+                // After an odd number of swaps the other pieces are swapped.
+                let swapped = if i % 2 == 1 {
+                    self.partners[target]
+                } else {
+                    target
+                };
+                let label = if swapped == target {
+                    format!("{} {}", kind, letters[target])
+                } else {
+                    format!("{} {} as {}", kind, letters[target], letters[swapped])
+                };
+                Step {
+                    label,
+                    parts: self.targets[swapped].clone().unwrap(),
+                }
+            })
+            .collect()
+    }
+}
+
+const Y_PERM: &str = "R U' R' U' R U R' F' R U R' U' R' F R";
+
+/// Sets the Y perm up to swap UB with DF, and UBR with DFR.
+const PARITY_SETUP: &str = "D' R L2 D'";
+
+fn old_pochmann_edges() -> &'static Swapper {
+    static SWAPPER: OnceLock<Swapper> = OnceLock::new();
+    SWAPPER.get_or_init(|| {
+        Swapper::new(
+            Stickers::edges(),
+            0,
+            ("T perm", "R U R' U' R' F R2 U' R' U' R U R' F'"),
+            None,
+            &[],
+        )
+    })
+}
+
+fn old_pochmann_corners() -> &'static Swapper {
+    static SWAPPER: OnceLock<Swapper> = OnceLock::new();
+    SWAPPER.get_or_init(|| Swapper::new(Stickers::corners(), 2, ("Y perm", Y_PERM), None, &[]))
+}
+
+fn m2_edges() -> &'static Swapper {
+    static SWAPPER: OnceLock<Swapper> = OnceLock::new();
+    SWAPPER.get_or_init(|| {
+        Swapper::new(
+            Stickers::edges(),
+            5,
+            ("M2", "M2"),
+            None,
+            // C, I, S and W: UF, FU, BD and DB.
+            &[
+                (2, "U2 M' U2 M'"),
+                (8, "D M' U R2 U' M U R2 U' D' M2"),
+                (18, "M2 D U R2 U' M' U R2 U' M D'"),
+                (22, "M U2 M U2"),
+            ],
+        )
+    })
+}
+
+fn r2_corners() -> &'static Swapper {
+    static SWAPPER: OnceLock<Swapper> = OnceLock::new();
+    SWAPPER.get_or_init(|| {
+        Swapper::new(
+            Stickers::corners(),
+            0,
+            ("R2", "R2"),
+            Some(("A perm", "R' F R' B2 R F' R' B2 R2")),
+            &[],
+        )
+    })
+}
+
+/// Solves `cube` by `method`, lettering the targets by `scheme`. Each step
+/// is labelled with its target, so that applying the steps one by one
+/// follows the memo.
+///
+/// ```
+/// # use cube::{algoritm::Algorithm, bld::Scheme, pochmann::{self, Method}, Cube};
+/// let mut cube = Cube::default();
+/// cube.execute_algorithm(Algorithm::parse("R U R' U' R' F R2 U' R' U' R U R' F'").unwrap());
+///
+/// let solution = pochmann::solve(&cube, Method::OldPochmann, &Scheme::speffz()).unwrap();
+/// assert_eq!(solution.to_string(), "edge D: [T perm]");
+/// assert!(solution.solves(&cube));
+/// ```
+pub fn solve(cube: &Cube, method: Method, scheme: &Scheme) -> Result<Solution, StateError> {
+    CubieCube::from_cube(cube)?;
+    let stages = match method {
+        Method::OldPochmann => [old_pochmann_edges(), old_pochmann_corners()],
+        Method::M2R2 => [r2_corners(), m2_edges()],
+    };
+
+    let mut cube = cube.clone();
+    let mut steps = Vec::new();
+    let mut parity = false;
+    for swapper in stages {
+        let stage = swapper.steps(&cube, scheme);
+        for step in &stage {
+            cube.execute_algorithm(step.algorithm());
+        }
+        parity = stage.len() % 2 == 1;
+        steps.extend(stage);
+    }
+
+    // The T perms take Old Pochmann's parity into the corners, which are
+    // traced after them, but M2/R2 is left with both half turns' other
+    // pieces swapped.
+    if parity {
+        let parse = |moves: &str| Algorithm::parse(moves).unwrap();
+        let setup = parse(PARITY_SETUP);
+        steps.push(Step {
+            label: "parity".to_string(),
+            parts: vec![
+                Part {
+                    name: Some("M2"),
+                    moves: parse("M2"),
+                },
+                Part::moves(setup.clone()),
+                Part {
+                    name: Some("Y perm"),
+                    moves: parse(Y_PERM),
+                },
+                Part::moves(setup.inverse()),
+            ],
+        });
+    }
+    Ok(Solution { steps })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{scramble::Rng, scrambled, solver};
+
+    #[test]
+    fn annotates_each_target() {
+        let cube = scrambled("U");
+        let solution = solve(&cube, Method::OldPochmann, &Scheme::speffz()).unwrap();
+        assert_eq!(
+            solution.to_string(),
+            "edge A: R2 U' R2 [T perm] R2 U R2\n\
+             edge D: [T perm]\n\
+             edge C: R2 U R2 [T perm] R2 U' R2\n\
+             corner D: F R' [Y perm] R F'\n\
+             corner C: F [Y perm] F'"
+        );
+        assert!(solution.solves(&cube));
+    }
+
+    #[test]
+    fn half_turns_move_the_other_targets() {
+        // Every second target on UF or DB, or UBR or DFR, is where the last
+        // half turn put it.
+        let cube = scrambled("U2");
+        let solution = solve(&cube, Method::M2R2, &Scheme::speffz()).unwrap();
+        let steps = solution.to_string();
+        assert!(steps.contains("corner B as V: U2 R2 [A perm] R2 U2 [R2]\n"));
+        assert!(steps.contains("edge C as W: M U2 M U2\n"));
+        assert!(solution.solves(&cube));
+
+        let cube = scrambled("U");
+        let solution = solve(&cube, Method::M2R2, &Scheme::speffz()).unwrap();
+        let last = solution.steps.last().unwrap().to_string();
+        assert_eq!(last, "parity: [M2] D' R L2 D' [Y perm] D L2 R' D");
+        assert!(solution.solves(&cube));
+    }
+
+    #[test]
+    fn solves_random_cubes() {
+        let superflip = "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2";
+        let twists = "R' D' R D R' D' R D L' D' L D L' D' L D";
+        let mut rng = Rng::new(1);
+        let mut cubes = vec![scrambled(superflip), scrambled(twists)];
+        cubes.extend((0..20).map(|_| {
+            let moves: Vec<usize> = (0..25).map(|_| rng.below(18)).collect();
+            scrambled(&solver::to_algorithm(&moves).to_string())
+        }));
+
+        for method in [Method::OldPochmann, Method::M2R2] {
+            for cube in &cubes {
+                let solution = solve(cube, method, &Scheme::speffz()).unwrap();
+                assert!(solution.solves(cube), "{}", cube.to_facelet_string());
+            }
+        }
+    }
+}
@@ -0,0 +1,269 @@
+//! Solves written out step by step, the way they're shared for review:
+//!
+//! ```text
+//! y2 // inspection
+//! D R' F D' // cross
+//! U R U' R' // 1st pair
+//! ```
+//!
+//! Each line is a step, with its moves and a label after `//`. Lines with
+//! only a comment are skipped, and `/* */` comments can span lines.
+
+use crate::{algoritm::Algorithm, session, Cube};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub moves: Algorithm,
+    /// Empty when the line had no comment.
+    pub label: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReconstructionError {
+    pub line: usize,
+    pub moves: String,
+}
+
+impl Display for ReconstructionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: couldn't read the moves `{}`",
+            self.line, self.moves
+        )
+    }
+}
+
+impl Error for ReconstructionError {}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Reconstruction {
+    pub steps: Vec<Step>,
+}
+
+impl Reconstruction {
+    pub fn parse(source: &str) -> Result<Self, ReconstructionError> {
+        let mut steps = Vec::new();
+        for (i, line) in strip_block_comments(source).lines().enumerate() {
+            let (moves, label) = line.split_once("//").unwrap_or((line, ""));
+            let (moves, label) = (moves.trim(), label.trim());
+            if moves.is_empty() {
+                continue;
+            }
+
+            let moves = Algorithm::parse(moves).ok_or_else(|| ReconstructionError {
+                line: i + 1,
+                moves: moves.to_string(),
+            })?;
+            steps.push(Step {
+                moves,
+                label: label.to_string(),
+            });
+        }
+
+        Ok(Self { steps })
+    }
+
+    /// All the steps' moves, one after the other.
+    pub fn algorithm(&self) -> Algorithm {
+        Algorithm::new(
+            self.steps
+                .iter()
+                .flat_map(|step| step.moves.moves.iter().cloned())
+                .collect(),
+        )
+    }
+
+    /// The number of moves, leaving out rotations.
+    pub fn move_count(&self) -> usize {
+        self.steps.iter().map(|step| step.moves.move_count()).sum()
+    }
+
+    /// The cube after the scramble and then the solve.
+    pub fn apply(&self, scramble: &Algorithm) -> Cube {
+        let mut cube = Cube::default();
+        cube.execute_algorithm(scramble.clone());
+        cube.execute_algorithm(self.algorithm());
+        cube
+    }
+
+    /// Whether the reconstruction solves the scramble, in any orientation.
+    pub fn solves(&self, scramble: &Algorithm) -> bool {
+        self.apply(scramble).is_solved()
+    }
+
+    /// Turns per second over a solve taking `time`, unless it took no time.
+    pub fn tps(&self, time: Duration) -> Option<f64> {
+        if time.is_zero() {
+            None
+        } else {
+            Some(self.move_count() as f64 / time.as_secs_f64())
+        }
+    }
+
+    /// A table of the steps with their move counts, and the total with the
+    /// turns per second if the time is known.
+    pub fn report(&self, time: Option<Duration>) -> String {
+        let width = self
+            .steps
+            .iter()
+            .map(|step| step.label.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("total".len());
+
+        let mut report = String::new();
+        for step in &self.steps {
+            let label = if step.label.is_empty() {
+                "-"
+            } else {
+                &step.label
+            };
+            report += &format!(
+                "{:<width$}  {:>3}  {}\n",
+                label,
+                step.moves.move_count(),
+                step.moves,
+                width = width
+            );
+        }
+
+        report += &format!(
+            "{:<width$}  {:>3}",
+            "total",
+            self.move_count(),
+            width = width
+        );
+        if let Some(time) = time {
+            let tps = match self.tps(time) {
+                Some(tps) => format!("{:.2}", tps),
+                None => "-".to_string(),
+            };
+            report += &format!("  in {}, {} TPS", session::format_time(time), tps);
+        }
+        report.push('\n');
+        report
+    }
+}
+
+/// Writes the steps back in the form `parse` reads.
+impl Display for Reconstruction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for step in &self.steps {
+            if step.label.is_empty() {
+                writeln!(f, "{}", step.moves)?;
+            } else {
+                writeln!(f, "{} // {}", step.moves, step.label)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `source` without its `/* */` comments, keeping the line breaks in them so
+/// lines are still counted right. A comment that's never closed is left in,
+/// for the line it starts on to fail.
+fn strip_block_comments(source: &str) -> String {
+    let mut stripped = String::with_capacity(source.len());
+    let mut rest = source;
+    loop {
+        let block = rest.find("/*");
+        let line = rest.find("//");
+        match (block, line) {
+            (Some(start), line) if line.is_none_or(|line| start < line) => {
+                let end = match rest[start + 2..].find("*/") {
+                    Some(len) => start + 2 + len + 2,
+                    None => break,
+                };
+                stripped.push_str(&rest[..start]);
+                stripped.extend(rest[start..end].chars().filter(|&c| c == '\n'));
+                rest = &rest[end..];
+            }
+            (_, Some(start)) => {
+                let end = rest[start..]
+                    .find('\n')
+                    .map_or(rest.len(), |len| start + len);
+                stripped.push_str(&rest[..end]);
+                rest = &rest[end..];
+            }
+            _ => break,
+        }
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOLVE: &str = "\
+// a sexy move scramble
+y2 // inspection
+y2 U R // cross
+// nothing to do here
+U' R' // last layer
+";
+
+    #[test]
+    fn parses_and_checks_solves() {
+        let recon = Reconstruction::parse(SOLVE).unwrap();
+        let scramble = Algorithm::parse("R U R' U'").unwrap();
+
+        assert_eq!(recon.steps.len(), 3);
+        assert_eq!(recon.steps[1].label, "cross");
+        assert_eq!(recon.move_count(), 4);
+        assert!(recon.solves(&scramble));
+        assert!(!recon.solves(&Algorithm::parse("R").unwrap()));
+        assert_eq!(Reconstruction::parse(&recon.to_string()).unwrap(), recon);
+
+        assert_eq!(
+            Reconstruction::parse("R U // ok\nR Q // cross").unwrap_err(),
+            ReconstructionError {
+                line: 2,
+                moves: "R Q".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn block_comments_span_lines() {
+        let recon =
+            Reconstruction::parse("R U R' /* a comment\nthat spans */ U' // sexy\nF /* // */ F'")
+                .unwrap();
+        assert_eq!(recon.steps.len(), 3);
+        assert_eq!(recon.steps[0].moves, Algorithm::parse("R U R'").unwrap());
+        assert_eq!(recon.steps[1].label, "sexy");
+        assert_eq!(recon.steps[2].moves, Algorithm::parse("F F'").unwrap());
+
+        let error = Reconstruction::parse("R // label /* not a comment\nU */").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(
+            Reconstruction::parse("R\nU /* open\nF").unwrap_err().line,
+            2
+        );
+    }
+
+    #[test]
+    fn reports_steps_and_tps() {
+        let recon = Reconstruction::parse(SOLVE).unwrap();
+        assert_eq!(
+            recon.report(Some(Duration::from_secs(2))),
+            "\
+inspection    0  y2
+cross         2  y2 U R
+last layer    2  U' R'
+total         4  in 2.00, 2.00 TPS
+"
+        );
+        assert!(recon.report(None).ends_with("total         4\n"));
+        assert!(recon
+            .report(Some(Duration::ZERO))
+            .ends_with("total         4  in 0.00, - TPS\n"));
+    }
+}
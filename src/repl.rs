@@ -0,0 +1,275 @@
+//! A line based shell keeping a cube in memory.
+
+use crate::{
+    algoritm::{Algorithm, Move},
+    ansi, solver, Cube,
+};
+use std::{
+    fs,
+    io::{self, BufRead, Write},
+};
+
+pub const HELP: &str = "\
+Type moves to turn the cube, or one of these commands:
+  :show          draw the cube
+  :history       print the moves since the last reset or load
+  :undo [n]      undo the last n moves
+  :redo [n]      redo the last n undone moves
+  :reset         go back to a solved cube
+  :solve         solve the cube
+  :save <file>   write the cube and its history to a file
+  :load <file>   read a cube written by :save
+  :help          print this message
+  :quit          leave";
+
+pub enum Outcome {
+    /// Keep going, printing the text.
+    Continue(String),
+    Quit,
+}
+
+/// The shell's state. Undoing applies the inverse of the last move rather
+/// than going back to a copy of the cube.
+#[derive(Clone, Debug, Default)]
+pub struct Repl {
+    cube: Cube,
+    history: Vec<Move>,
+    undone: Vec<Move>,
+    /// Draw the cube with ANSI colours.
+    pub colour: bool,
+}
+
+impl Repl {
+    pub fn new(colour: bool) -> Self {
+        Self {
+            colour,
+            ..Self::default()
+        }
+    }
+
+    pub fn cube(&self) -> &Cube {
+        &self.cube
+    }
+
+    pub fn history(&self) -> Algorithm {
+        Algorithm::new(self.history.clone())
+    }
+
+    fn show(&self) -> String {
+        if self.colour {
+            ansi::net(&self.cube)
+        } else {
+            self.cube.to_string()
+        }
+    }
+
+    fn apply(&mut self, alg: Algorithm) {
+        for mv in alg.moves {
+            self.cube.execute_move(mv.clone());
+            self.history.push(mv);
+        }
+        self.undone.clear();
+    }
+
+    fn undo(&mut self, count: usize) -> usize {
+        let mut done = 0;
+        while done < count {
+            match self.history.pop() {
+                Some(mv) => {
+                    self.cube.execute_move(mv.inverse());
+                    self.undone.push(mv);
+                    done += 1;
+                }
+                None => break,
+            }
+        }
+        done
+    }
+
+    fn redo(&mut self, count: usize) -> usize {
+        let mut done = 0;
+        while done < count {
+            match self.undone.pop() {
+                Some(mv) => {
+                    self.cube.execute_move(mv.clone());
+                    self.history.push(mv);
+                    done += 1;
+                }
+                None => break,
+            }
+        }
+        done
+    }
+
+    fn save(&self, path: &str) -> io::Result<()> {
+        fs::write(
+            path,
+            format!("{}\n{}\n", self.cube.to_facelet_string(), self.history()),
+        )
+    }
+
+    fn load(&mut self, path: &str) -> Result<(), String> {
+        let source =
+            fs::read_to_string(path).map_err(|e| format!("couldn't read {}: {}", path, e))?;
+        let mut lines = source.lines();
+
+        let cube = Cube::parse_facelets(lines.next().unwrap_or(""))
+            .map_err(|e| format!("{}:1: {}", path, e))?;
+        let history = Algorithm::parse(lines.next().unwrap_or(""))
+            .ok_or_else(|| format!("{}:2: couldn't read the history", path))?;
+
+        self.cube = cube;
+        self.history = history.moves;
+        self.undone.clear();
+        Ok(())
+    }
+
+    /// Handles one line of input.
+    pub fn handle(&mut self, line: &str) -> Outcome {
+        let line = line.trim();
+        let mut words = line.split_whitespace();
+        let count = |word: Option<&str>| word.map_or(Ok(1), |n| n.parse::<usize>());
+
+        let out = match words.next() {
+            None => String::new(),
+            Some(":quit") | Some(":q") => return Outcome::Quit,
+            Some(":help") => HELP.to_string(),
+            Some(":show") => self.show(),
+            Some(":history") => self.history().to_string(),
+            Some(":reset") => {
+                *self = Self::new(self.colour);
+                self.show()
+            }
+            Some(":undo") => match count(words.next()) {
+                Ok(n) => format!("undid {} moves\n{}", self.undo(n), self.show()),
+                Err(_) => "usage: :undo [n]".to_string(),
+            },
+            Some(":redo") => match count(words.next()) {
+                Ok(n) => format!("redid {} moves\n{}", self.redo(n), self.show()),
+                Err(_) => "usage: :redo [n]".to_string(),
+            },
+            Some(":solve") => match solver::solve(&self.cube) {
+                Ok(solution) => {
+                    let text = solution.to_string();
+                    self.apply(solution);
+                    format!("{}\n{}", text, self.show())
+                }
+                Err(e) => format!("can't solve: {}", e),
+            },
+            Some(":save") => match words.next() {
+                Some(path) => match self.save(path) {
+                    Ok(()) => format!("saved to {}", path),
+                    Err(e) => format!("couldn't write {}: {}", path, e),
+                },
+                None => "usage: :save <file>".to_string(),
+            },
+            Some(":load") => match words.next() {
+                Some(path) => match self.load(path) {
+                    Ok(()) => self.show(),
+                    Err(e) => e,
+                },
+                None => "usage: :load <file>".to_string(),
+            },
+            Some(command) if command.starts_with(':') => {
+                format!("unknown command {}, try :help", command)
+            }
+            Some(_) => match Algorithm::parse(line) {
+                Some(alg) => {
+                    self.apply(alg);
+                    self.show()
+                }
+                None => format!("couldn't read `{}`", line),
+            },
+        };
+
+        Outcome::Continue(out)
+    }
+}
+
+/// Reads lines from `input` until it ends or `:quit`.
+pub fn run<R: BufRead, W: Write>(repl: &mut Repl, input: R, mut output: W) -> io::Result<()> {
+    write!(output, "{}\n> ", HELP)?;
+    output.flush()?;
+
+    for line in input.lines() {
+        match repl.handle(&line?) {
+            Outcome::Continue(text) => {
+                if !text.is_empty() {
+                    writeln!(output, "{}", text.trim_end())?;
+                }
+            }
+            Outcome::Quit => return Ok(()),
+        }
+        write!(output, "> ")?;
+        output.flush()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undo_and_redo() {
+        let mut repl = Repl::new(false);
+        repl.handle("R U R' U'");
+        repl.handle("F");
+        let turned = repl.cube().clone();
+
+        repl.handle(":undo 5");
+        assert!(repl.cube().is_solved());
+        assert!(repl.history().moves.is_empty());
+
+        repl.handle(":redo 5");
+        assert_eq!(repl.cube(), &turned);
+
+        repl.handle(":undo");
+        repl.handle("B");
+        repl.handle(":redo");
+        assert_eq!(repl.history().to_string(), "R U R' U' B");
+    }
+
+    #[test]
+    fn solve_and_reset() {
+        let mut repl = Repl::new(false);
+        repl.handle("R U2 F'");
+        repl.handle(":solve");
+        assert!(repl.cube().is_solved());
+
+        repl.handle("D");
+        repl.handle(":reset");
+        assert!(repl.cube().is_solved());
+        assert!(repl.history().moves.is_empty());
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!("cube-repl-{}.txt", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut repl = Repl::new(false);
+        repl.handle("R U");
+        repl.handle(&format!(":save {}", path));
+
+        let mut loaded = Repl::new(false);
+        loaded.handle(&format!(":load {}", path));
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(loaded.cube(), repl.cube());
+        loaded.handle(":undo 2");
+        assert!(loaded.cube().is_solved());
+    }
+
+    #[test]
+    fn reads_until_quit() {
+        let mut repl = Repl::new(false);
+        let mut output = Vec::new();
+        run(&mut repl, "R\n:wat\n:quit\nU\n".as_bytes(), &mut output).unwrap();
+
+        assert_eq!(repl.history().to_string(), "R");
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains("unknown command :wat"));
+    }
+}
@@ -0,0 +1,760 @@
+//! The steps of the Roux method: a 1x2x3 block on the left, another on the
+//! right, the top corners (CMLL) and the last six edges (LSE).
+//!
+//! The blocks are built with the moves Roux allows at each step, which turn
+//! the M slice and so its centres, so pieces are followed by their stickers
+//! rather than by [`CubieCube`]: a piece's state is the place of its first
+//! sticker, as in [`CORNER_FACELETS`] and [`EDGE_FACELETS`]. Colours are read
+//! against the first block rather than the centres once it's built. Both
+//! blocks are found with IDA* pruned by tables of their edges and one corner,
+//! and the last six edges with an exact table of all their states.
+
+use crate::{
+    algoritm::{Algorithm, Side},
+    cross,
+    cubie::{CubieCube, StateError, CORNER_FACELETS, EDGE_FACELETS},
+    last_layer::{self, Recognition, RecognitionError},
+    search::{piece_state, MoveSet, STATES},
+    solver::UNKNOWN,
+    Colour, Cube,
+};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    sync::OnceLock,
+};
+
+/// The optimal first block is never longer than this.
+pub const MAX_FIRST_BLOCK: usize = 10;
+/// How long a second block is looked for.
+pub const MAX_SECOND_BLOCK: usize = 16;
+
+const COLOURS: [Colour; 6] = [
+    Colour::White,
+    Colour::Yellow,
+    Colour::Green,
+    Colour::Blue,
+    Colour::Red,
+    Colour::Orange,
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RouxOptions {
+    /// Start every first block with the rotation bringing it to the bottom
+    /// left, rather than building it where it is. The later steps always
+    /// start with the rotation holding the first block there.
+    pub rotate: bool,
+    /// The most solutions to give.
+    pub limit: usize,
+}
+
+impl Default for RouxOptions {
+    fn default() -> Self {
+        Self {
+            rotate: true,
+            limit: 10,
+        }
+    }
+}
+
+/// A way of doing one step.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    /// The rotation it starts with, if any.
+    pub rotation: Algorithm,
+    pub moves: Algorithm,
+}
+
+impl Step {
+    /// The rotation followed by the moves.
+    pub fn algorithm(&self) -> Algorithm {
+        let mut moves = self.rotation.moves.clone();
+        moves.extend(self.moves.moves.iter().cloned());
+        Algorithm::new(moves)
+    }
+}
+
+/// Shows the move count and the algorithm, like `4: z' U' M r2 U`.
+impl Display for Step {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.moves.move_count())?;
+        let algorithm = self.algorithm();
+        if !algorithm.moves.is_empty() {
+            write!(f, ": {}", algorithm)?;
+        }
+        Ok(())
+    }
+}
+
+/// The case of the top corners once both blocks are built.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cmll {
+    /// The rotation holding the first block on the left, if any.
+    pub rotation: Algorithm,
+    pub recognition: Recognition,
+}
+
+impl Cmll {
+    /// The rotation, the AUF and the case's algorithm.
+    pub fn solution(&self) -> Algorithm {
+        let mut moves = self.rotation.moves.clone();
+        moves.extend(self.recognition.solution().moves);
+        Algorithm::new(moves)
+    }
+}
+
+/// Shows the case like `CMLL H after y U: R U2 R' U' R U R' U' R U' R'`,
+/// named as in the COLL set, whose algorithms solve the corners the same.
+impl Display for Cmll {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let recognition = &self.recognition;
+        write!(f, "CMLL {}", recognition.case.name)?;
+        let mut after = self.rotation.moves.clone();
+        after.extend(recognition.auf.moves.iter().cloned());
+        if !after.is_empty() {
+            write!(f, " after {}", Algorithm::new(after))?;
+        }
+        write!(f, ": {}", recognition.case.algorithm)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RouxError {
+    State(StateError),
+    /// The cube can't be held with a solved block on the bottom left.
+    FirstBlock,
+    /// The block opposite the first one isn't solved.
+    SecondBlock,
+    /// The top corners aren't solved.
+    Corners,
+    Case(RecognitionError),
+}
+
+impl Display for RouxError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::State(e) => write!(f, "impossible state: {}", e),
+            Self::FirstBlock => write!(f, "the first block isn't solved"),
+            Self::SecondBlock => write!(f, "the second block isn't solved"),
+            Self::Corners => write!(f, "the top corners aren't solved"),
+            Self::Case(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for RouxError {}
+
+impl From<StateError> for RouxError {
+    fn from(e: StateError) -> Self {
+        Self::State(e)
+    }
+}
+
+/// A 1x2x3 block, by the positions of its edges and corners.
+struct Block {
+    edges: [usize; 3],
+    corners: [usize; 2],
+}
+
+const FIRST_BLOCK: Block = Block {
+    edges: [6, 9, 10],
+    corners: [5, 6],
+};
+
+const SECOND_BLOCK: Block = Block {
+    edges: [4, 8, 11],
+    corners: [4, 7],
+};
+
+/// The states of a block's edges then corners.
+type BlockState = [u8; 5];
+
+impl Block {
+    fn solved(&self) -> BlockState {
+        let [a, b, c] = self.edges.map(|edge| (edge * 2) as u8);
+        let [d, e] = self.corners.map(|corner| (corner * 3) as u8);
+        [a, b, c, d, e]
+    }
+
+    fn read(&self, facelets: &[Colour; 54], frame: &[Colour; 6]) -> Option<BlockState> {
+        let mut state = [0; 5];
+        for (i, edge) in self.edges.iter().enumerate() {
+            state[i] = piece_state(facelets, frame, &EDGE_FACELETS, *edge)?;
+        }
+        for (i, corner) in self.corners.iter().enumerate() {
+            state[3 + i] = piece_state(facelets, frame, &CORNER_FACELETS, *corner)?;
+        }
+        Some(state)
+    }
+}
+
+/// A search over the states of a step.
+trait Tables {
+    type State: Copy;
+
+    fn set(&self) -> &MoveSet;
+    /// At least how many moves `state` is from solved.
+    fn distance(&self, state: &Self::State) -> usize;
+    fn turn(&self, state: &Self::State, m: usize) -> Self::State;
+}
+
+/// A block's moves, and how far its edges and each corner are from solved,
+/// by [`BlockTables::index`].
+struct BlockTables {
+    set: MoveSet,
+    distances: [Vec<u8>; 2],
+}
+
+impl BlockTables {
+    fn new(layers: &str, block: &Block) -> Self {
+        let set = MoveSet::layers(layers);
+        let solved = block.solved();
+        let distances = [0, 1].map(|corner| {
+            let start = Self::index(&solved, corner);
+            let mut table = vec![UNKNOWN; STATES.pow(4)];
+            table[start] = 0;
+
+            let mut frontier = vec![start];
+            let mut depth = 0;
+            while !frontier.is_empty() {
+                let mut next = Vec::new();
+                for i in frontier {
+                    let digits = [3, 2, 1, 0].map(|power| (i / STATES.pow(power) % STATES) as u8);
+                    for m in 0..set.moves.len() {
+                        let j = digits.iter().enumerate().fold(0, |acc, (piece, state)| {
+                            let moves = if piece < 3 { &set.edges } else { &set.corners };
+                            acc * STATES + moves[m][*state as usize] as usize
+                        });
+                        if table[j] == UNKNOWN {
+                            table[j] = depth + 1;
+                            next.push(j);
+                        }
+                    }
+                }
+                frontier = next;
+                depth += 1;
+            }
+            table
+        });
+        Self { set, distances }
+    }
+
+    /// The edges and one of the corners as a base 24 number.
+    fn index(state: &BlockState, corner: usize) -> usize {
+        [state[0], state[1], state[2], state[3 + corner]]
+            .iter()
+            .fold(0, |acc, state| acc * STATES + *state as usize)
+    }
+
+    fn first() -> &'static Self {
+        static TABLES: OnceLock<BlockTables> = OnceLock::new();
+        TABLES.get_or_init(|| Self::new("U R F D L B", &FIRST_BLOCK))
+    }
+
+    fn second() -> &'static Self {
+        static TABLES: OnceLock<BlockTables> = OnceLock::new();
+        TABLES.get_or_init(|| Self::new("R r U M", &SECOND_BLOCK))
+    }
+}
+
+impl Tables for BlockTables {
+    type State = BlockState;
+
+    fn set(&self) -> &MoveSet {
+        &self.set
+    }
+
+    fn distance(&self, state: &BlockState) -> usize {
+        let [a, b] = [0, 1].map(|corner| self.distances[corner][Self::index(state, corner)]);
+        a.max(b) as usize
+    }
+
+    fn turn(&self, state: &BlockState, m: usize) -> BlockState {
+        let mut next = *state;
+        for (i, piece) in next.iter_mut().enumerate() {
+            let moves = if i < 3 {
+                &self.set.edges
+            } else {
+                &self.set.corners
+            };
+            *piece = moves[m][*piece as usize];
+        }
+        next
+    }
+}
+
+/// The edges UR, UF, UL, UB, DF and DB, left to solve after the blocks and
+/// the top corners.
+const LAST_EDGES: [usize; 6] = [0, 1, 2, 3, 5, 7];
+
+/// The faces the M slice centres go around, U, F, D and B.
+const M_FACES: [u8; 4] = [0, 2, 3, 5];
+
+/// The last six edges, the face with the top centre, and the state of the
+/// URF corner, whose place is the AUF.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct LastSix {
+    edges: [u8; 6],
+    centre: u8,
+    corner: u8,
+}
+
+/// 6! edge permutations, 2^6 flips, 4 centres and 4 AUFs.
+const LAST_SIX_STATES: usize = 737_280;
+
+impl LastSix {
+    const SOLVED: Self = Self {
+        edges: [0, 2, 4, 6, 10, 14],
+        centre: 0,
+        corner: 0,
+    };
+
+    /// A number from 0 to [`LAST_SIX_STATES`].
+    fn index(&self) -> usize {
+        let slots = self.edges.map(|state| {
+            LAST_EDGES
+                .iter()
+                .position(|&edge| edge == state as usize / 2)
+                .unwrap()
+        });
+        let mut permutation = 0;
+        for i in 0..6 {
+            let smaller = slots[i + 1..].iter().filter(|&&s| s < slots[i]).count();
+            permutation = permutation * (6 - i) + smaller;
+        }
+        let flips = self
+            .edges
+            .iter()
+            .fold(0, |acc, state| acc * 2 + *state as usize % 2);
+        let centre = M_FACES.iter().position(|&f| f == self.centre).unwrap();
+        ((permutation * 64 + flips) * 4 + centre) * 4 + self.corner as usize / 3
+    }
+}
+
+/// The moves M and U, and how far every state of the last six edges is from
+/// solved.
+struct LastSixTables {
+    set: MoveSet,
+    distance: Vec<u8>,
+}
+
+impl LastSixTables {
+    fn get() -> &'static Self {
+        static TABLES: OnceLock<LastSixTables> = OnceLock::new();
+        TABLES.get_or_init(|| {
+            let mut tables = Self {
+                set: MoveSet::layers("M U"),
+                distance: vec![UNKNOWN; LAST_SIX_STATES],
+            };
+            tables.distance[LastSix::SOLVED.index()] = 0;
+
+            let mut frontier = vec![LastSix::SOLVED];
+            let mut depth = 0;
+            while !frontier.is_empty() {
+                let mut next = Vec::new();
+                for state in frontier {
+                    for m in 0..tables.set.moves.len() {
+                        let turned = tables.turn(&state, m);
+                        let i = turned.index();
+                        if tables.distance[i] == UNKNOWN {
+                            tables.distance[i] = depth + 1;
+                            next.push(turned);
+                        }
+                    }
+                }
+                frontier = next;
+                depth += 1;
+            }
+            tables
+        })
+    }
+}
+
+impl Tables for LastSixTables {
+    type State = LastSix;
+
+    fn set(&self) -> &MoveSet {
+        &self.set
+    }
+
+    fn distance(&self, state: &LastSix) -> usize {
+        self.distance[state.index()] as usize
+    }
+
+    fn turn(&self, state: &LastSix, m: usize) -> LastSix {
+        LastSix {
+            edges: state.edges.map(|edge| self.set.edges[m][edge as usize]),
+            centre: self.set.centres[m][state.centre as usize],
+            corner: self.set.corners[m][state.corner as usize],
+        }
+    }
+}
+
+struct Search<'a, T: Tables> {
+    tables: &'a T,
+    moves: Vec<usize>,
+    found: Vec<Vec<usize>>,
+    limit: usize,
+}
+
+impl<T: Tables> Search<'_, T> {
+    fn search(&mut self, state: T::State, left: usize) {
+        if self.tables.distance(&state) > left || self.found.len() >= self.limit {
+            return;
+        }
+        if left == 0 {
+            self.found.push(self.moves.clone());
+            return;
+        }
+
+        let set = self.tables.set();
+        for m in 0..set.moves.len() {
+            if !set.allowed(m, self.moves.last().copied()) {
+                continue;
+            }
+            self.moves.push(m);
+            self.search(self.tables.turn(&state, m), left - 1);
+            self.moves.pop();
+        }
+    }
+}
+
+/// The shortest ways from `state` to solved, up to `limit` of them, and
+/// none if they're longer than `max`.
+fn solve<T: Tables>(tables: &T, state: T::State, max: usize, limit: usize) -> Vec<Algorithm> {
+    let mut search = Search {
+        tables,
+        moves: Vec::new(),
+        found: Vec::new(),
+        limit,
+    };
+    for depth in tables.distance(&state)..=max {
+        search.search(state, depth);
+        if !search.found.is_empty() {
+            break;
+        }
+    }
+    search
+        .found
+        .iter()
+        .map(|moves| tables.set().algorithm(moves))
+        .collect()
+}
+
+/// Every optimal first block with one of `colours` on the bottom, up to
+/// `options.limit` of them, of whichever colours and sides have the
+/// shortest.
+///
+/// ```
+/// # use cube::{algoritm::Algorithm, roux::{self, RouxOptions}, Colour, Cube};
+/// let mut cube = Cube::default();
+/// cube.execute_algorithm(Algorithm::parse("L U' F D").unwrap());
+///
+/// let blocks = roux::first_block(&cube, &[Colour::Yellow], &RouxOptions::default()).unwrap();
+/// assert_eq!(blocks[0].to_string(), "2: y2 D' B'");
+/// ```
+pub fn first_block(
+    cube: &Cube,
+    colours: &[Colour],
+    options: &RouxOptions,
+) -> Result<Vec<Step>, RouxError> {
+    CubieCube::from_cube(cube)?;
+    let tables = BlockTables::first();
+
+    let mut best: Vec<Step> = Vec::new();
+    for rotation in Algorithm::orientations() {
+        let mut turned = cube.clone();
+        turned.execute_algorithm(rotation.clone());
+        let facelets = turned.facelets();
+        let frame = std::array::from_fn(|face| facelets[face * 9 + 4]);
+        if !colours.contains(&frame[3]) {
+            continue;
+        }
+        let state = FIRST_BLOCK
+            .read(&facelets, &frame)
+            .ok_or(StateError::Centres)?;
+
+        let found = solve(tables, state, MAX_FIRST_BLOCK, options.limit);
+        let length = |steps: &[Step]| steps.first().map(|step| step.moves.move_count());
+        let steps: Vec<Step> = found
+            .into_iter()
+            .map(|moves| {
+                let (rotation, moves) = cross::placed(&rotation, moves, options.rotate);
+                Step { rotation, moves }
+            })
+            .collect();
+        match (length(&steps), length(&best)) {
+            (Some(new), Some(old)) if new < old => best = steps,
+            (Some(new), Some(old)) if new == old => best.extend(steps),
+            (Some(_), None) => best = steps,
+            _ => {}
+        }
+    }
+    best.truncate(options.limit);
+    Ok(best)
+}
+
+/// The colours of the faces in [`Cube::FACELET_ORDER`] going by the first
+/// block, on the bottom left, and the right centre, `None` if they aren't
+/// six different colours.
+fn block_frame(facelets: &[Colour; 54]) -> Option<[Colour; 6]> {
+    let right = facelets[9 + 4];
+    let front = facelets[EDGE_FACELETS[9][0]];
+    let down = facelets[EDGE_FACELETS[6][0]];
+    let left = facelets[36 + 4];
+    let back = facelets[EDGE_FACELETS[10][0]];
+    let known = [right, front, down, left, back];
+    let mut up = COLOURS.iter().filter(|colour| !known.contains(colour));
+    let frame = [*up.next()?, right, front, down, left, back];
+    match up.next() {
+        Some(_) => None,
+        None => Some(frame),
+    }
+}
+
+/// The first way of holding the cube with the first block solved on the
+/// bottom left: the rotation, the turned cube and its colours by face.
+fn holding(cube: &Cube) -> Result<(Algorithm, Cube, [Colour; 6]), RouxError> {
+    CubieCube::from_cube(cube)?;
+    for rotation in Algorithm::orientations() {
+        let mut turned = cube.clone();
+        turned.execute_algorithm(rotation.clone());
+        let facelets = turned.facelets();
+        if let Some(frame) = block_frame(&facelets) {
+            if FIRST_BLOCK.read(&facelets, &frame) == Some(FIRST_BLOCK.solved()) {
+                return Ok((rotation, turned, frame));
+            }
+        }
+    }
+    Err(RouxError::FirstBlock)
+}
+
+/// Holds the cube as [`holding`] does, and checks the second block is
+/// solved too.
+fn holding_blocks(cube: &Cube) -> Result<(Algorithm, Cube, [Colour; 6]), RouxError> {
+    let (rotation, turned, frame) = holding(cube)?;
+    if SECOND_BLOCK.read(&turned.facelets(), &frame) != Some(SECOND_BLOCK.solved()) {
+        return Err(RouxError::SecondBlock);
+    }
+    Ok((rotation, turned, frame))
+}
+
+/// The shortest second blocks in R, r, U and M, up to `options.limit` of
+/// them, once the first block is solved.
+///
+/// ```
+/// # use cube::{algoritm::Algorithm, roux::{self, RouxOptions}, Cube};
+/// let mut cube = Cube::default();
+/// cube.execute_algorithm(Algorithm::parse("R U' r'").unwrap());
+///
+/// let blocks = roux::second_block(&cube, &RouxOptions::default()).unwrap();
+/// assert_eq!(blocks[0].to_string(), "3: r U R'");
+/// ```
+pub fn second_block(cube: &Cube, options: &RouxOptions) -> Result<Vec<Step>, RouxError> {
+    let (rotation, turned, frame) = holding(cube)?;
+    let state = SECOND_BLOCK
+        .read(&turned.facelets(), &frame)
+        .ok_or(StateError::Centres)?;
+    Ok(solve(
+        BlockTables::second(),
+        state,
+        MAX_SECOND_BLOCK,
+        options.limit,
+    )
+    .into_iter()
+    .map(|moves| Step {
+        rotation: rotation.clone(),
+        moves,
+    })
+    .collect())
+}
+
+/// The CMLL case once both blocks are solved, or `None` if the top corners
+/// are solved but for an AUF.
+pub fn recognise_cmll(cube: &Cube) -> Result<Option<Cmll>, RouxError> {
+    let (rotation, turned, frame) = holding_blocks(cube)?;
+    let [up, right, front, _, left, back] = frame;
+    Ok(
+        last_layer::recognise_corners(&turned, up, [front, left, back, right])
+            .map_err(RouxError::Case)?
+            .map(|recognition| Cmll {
+                rotation,
+                recognition,
+            }),
+    )
+}
+
+/// Every optimal way of solving the last six edges with M and U, up to
+/// `options.limit` of them, once the rest is solved.
+///
+/// ```
+/// # use cube::{algoritm::Algorithm, roux::{self, RouxOptions}, Cube};
+/// let mut cube = Cube::default();
+/// cube.execute_algorithm(Algorithm::parse("M2 U M2 U2").unwrap());
+///
+/// let edges = roux::last_six_edges(&cube, &RouxOptions::default()).unwrap();
+/// assert_eq!(edges[0].to_string(), "4: U2 M2 U' M2");
+/// ```
+pub fn last_six_edges(cube: &Cube, options: &RouxOptions) -> Result<Vec<Step>, RouxError> {
+    let (rotation, turned, frame) = holding_blocks(cube)?;
+    let tables = LastSixTables::get();
+    let facelets = turned.facelets();
+    let read = |places: &[[usize; 3]], piece| piece_state(&facelets, &frame, places, piece);
+
+    // The top corners must be solved, but for an AUF.
+    let auf = match read(&CORNER_FACELETS, 0) {
+        Some(state) if state < 12 && state % 3 == 0 => state,
+        _ => return Err(RouxError::Corners),
+    };
+    let u = tables
+        .set
+        .moves
+        .iter()
+        .position(|mv| mv.side == Side::Up)
+        .unwrap();
+    for corner in 1..4 {
+        let mut home = (corner * 3) as u8;
+        for _ in 0..auf / 3 {
+            home = tables.set.corners[u][home as usize];
+        }
+        if read(&CORNER_FACELETS, corner) != Some(home) {
+            return Err(RouxError::Corners);
+        }
+    }
+
+    let mut edges = [0; 6];
+    for (state, edge) in edges.iter_mut().zip(LAST_EDGES) {
+        *state = piece_state(&facelets, &frame, &EDGE_FACELETS, edge).ok_or(StateError::Centres)?;
+    }
+    let centre = (0..6)
+        .find(|face| facelets[face * 9 + 4] == frame[0])
+        .ok_or(StateError::Centres)? as u8;
+    let state = LastSix {
+        edges,
+        centre,
+        corner: auf,
+    };
+    if tables.distance(&state) == UNKNOWN as usize {
+        return Err(StateError::Parity.into());
+    }
+
+    Ok(solve(tables, state, UNKNOWN as usize, options.limit)
+        .into_iter()
+        .map(|moves| Step {
+            rotation: rotation.clone(),
+            moves,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scrambled;
+
+    fn apply(cube: &Cube, alg: Algorithm) -> Cube {
+        let mut cube = cube.clone();
+        cube.execute_algorithm(alg);
+        cube
+    }
+
+    #[test]
+    fn slice_moves_follow_stickers() {
+        let set = MoveSet::layers("M U");
+        // M takes UF to DF, its top sticker going to the front.
+        assert_eq!(set.edges[0][2], 11);
+        assert_eq!(set.centres[0], [2, 1, 3, 5, 4, 0]);
+        assert!(set.corners[0]
+            .iter()
+            .enumerate()
+            .all(|(i, &s)| i == s as usize));
+        assert!(!set.allowed(1, Some(0)));
+        assert!(set.allowed(3, Some(0)));
+    }
+
+    #[test]
+    fn solves_a_scramble_step_by_step() {
+        let options = RouxOptions::default();
+        let mut cube = scrambled("D2 F2 U' B2 R2 B2 R2 L B' D' F D2 F' L' U R' F' D' L2 B2");
+
+        let first = first_block(&cube, &COLOURS, &options).unwrap();
+        assert!(first.len() > 1);
+        let length = first[0].moves.move_count();
+        assert!(first.iter().all(|step| step.moves.move_count() == length));
+        for step in &first {
+            assert!(holding(&apply(&cube, step.algorithm())).is_ok(), "{}", step);
+        }
+        cube = apply(&cube, first[0].algorithm());
+        assert_eq!(
+            first_block(&cube, &COLOURS, &options).unwrap()[0].to_string(),
+            "0"
+        );
+
+        let second = second_block(&cube, &options).unwrap();
+        assert!(second.iter().all(|step| step
+            .moves
+            .moves
+            .iter()
+            .all(|mv| { mv.side == Side::Up || mv.side == Side::Right || mv.side == Side::Left })));
+        cube = apply(&cube, second[0].algorithm());
+        assert!(holding_blocks(&cube).is_ok());
+
+        if let Some(cmll) = recognise_cmll(&cube).unwrap() {
+            cube = apply(&cube, cmll.solution());
+        }
+        assert_eq!(recognise_cmll(&cube), Ok(None));
+
+        let edges = last_six_edges(&cube, &options).unwrap();
+        assert!(!edges.is_empty());
+        for step in &edges {
+            assert!(apply(&cube, step.algorithm()).is_solved(), "{}", step);
+        }
+    }
+
+    #[test]
+    fn reads_the_blocks_rather_than_the_centres() {
+        let options = RouxOptions::default();
+        assert_eq!(
+            second_block(&scrambled("M'"), &options).unwrap()[0].to_string(),
+            "0"
+        );
+        assert_eq!(
+            last_six_edges(&scrambled("M'"), &options).unwrap()[0].to_string(),
+            "1: M"
+        );
+
+        // The blocks are found however the cube is held.
+        let cube = scrambled("x2 y R U M'");
+        let second = second_block(&cube, &options).unwrap();
+        assert!(second[0].moves.move_count() <= 3);
+        assert!(holding_blocks(&apply(&cube, second[0].algorithm())).is_ok());
+
+        // L only turns the first block in place, to be matched by R'.
+        assert_eq!(
+            second_block(&scrambled("L"), &options).unwrap()[0].to_string(),
+            "1: R'"
+        );
+        assert_eq!(
+            second_block(&scrambled("F B' R L' U D'"), &options),
+            Err(RouxError::FirstBlock)
+        );
+        assert_eq!(recognise_cmll(&scrambled("R")), Err(RouxError::SecondBlock));
+        assert_eq!(
+            last_six_edges(&scrambled("R U R' U R U2 R'"), &options),
+            Err(RouxError::Corners)
+        );
+    }
+
+    #[test]
+    fn recognises_cmll_with_the_slice_off() {
+        let sune = "R U R' U R U2 R'";
+        let cube = scrambled(&format!(
+            "{} M' U2",
+            Algorithm::parse(sune).unwrap().inverse()
+        ));
+        let cmll = recognise_cmll(&cube).unwrap().unwrap();
+        let solved = apply(&cube, cmll.solution());
+        assert_eq!(recognise_cmll(&solved), Ok(None));
+        assert!(last_six_edges(&solved, &RouxOptions::default()).is_ok());
+    }
+}
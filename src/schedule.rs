@@ -0,0 +1,294 @@
+//! Spaced repetition for learning algorithms, the SM-2 way: every case is a
+//! card that comes back after an interval growing with how well it went.
+//!
+//! Schedules are plain text files with a card per line, like
+//!
+//! ```text
+//! PLL T | 2.50 | 6 | 2 | 2026-10-19T14:03:12Z
+//! ```
+//!
+//! with the case, its ease, the interval in days, how many times in a row
+//! it went well and when it was last reviewed.
+
+use crate::session::{format_timestamp, parse_timestamp};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    fs, io,
+    path::Path,
+};
+
+pub const DAY: u64 = 86_400;
+
+/// The ease new cards start with.
+pub const EASE: f64 = 2.5;
+/// Ease never drops below this, or hard cards would come back every day.
+pub const MIN_EASE: f64 = 1.3;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Card {
+    pub case: String,
+    pub ease: f64,
+    /// In days.
+    pub interval: u32,
+    /// Reviews in a row that went well.
+    pub repetitions: u32,
+    /// When it was last reviewed, in seconds since the Unix epoch.
+    pub last_review: u64,
+}
+
+impl Card {
+    pub fn new(case: &str) -> Self {
+        Self {
+            case: case.to_string(),
+            ease: EASE,
+            interval: 0,
+            repetitions: 0,
+            last_review: 0,
+        }
+    }
+
+    /// Grades a review from 0, forgotten, to 5, perfect. Anything under 3
+    /// starts the card over.
+    pub fn review(&mut self, quality: u8, now: u64) {
+        let quality = quality.min(5);
+        if quality < 3 {
+            self.repetitions = 0;
+            self.interval = 1;
+        } else {
+            self.repetitions += 1;
+            self.interval = match self.repetitions {
+                1 => 1,
+                2 => 6,
+                _ => (self.interval as f64 * self.ease).round() as u32,
+            };
+        }
+
+        let miss = (5 - quality) as f64;
+        self.ease = (self.ease + 0.1 - miss * (0.08 + miss * 0.02)).max(MIN_EASE);
+        self.last_review = now;
+    }
+
+    /// When the card should be reviewed again.
+    pub fn due(&self) -> u64 {
+        self.last_review + self.interval as u64 * DAY
+    }
+
+    pub fn parse_line(source: &str, line: usize) -> Result<Self, ScheduleError> {
+        let fields: Vec<&str> = source.split('|').map(str::trim).collect();
+        if fields.len() != 5 || fields[0].is_empty() {
+            return Err(ScheduleError::Fields(line, fields.len()));
+        }
+        let field = |i: usize| fields[i].to_string();
+
+        Ok(Self {
+            case: field(0),
+            ease: fields[1]
+                .parse()
+                .ok()
+                .filter(|ease: &f64| ease.is_finite())
+                .ok_or_else(|| ScheduleError::Ease(line, field(1)))?,
+            interval: fields[2]
+                .parse()
+                .map_err(|_| ScheduleError::Interval(line, field(2)))?,
+            repetitions: fields[3]
+                .parse()
+                .map_err(|_| ScheduleError::Repetitions(line, field(3)))?,
+            last_review: parse_timestamp(fields[4])
+                .ok_or_else(|| ScheduleError::Timestamp(line, field(4)))?,
+        })
+    }
+
+    /// The card as a line of a schedule file, without the line break.
+    pub fn to_line(&self) -> String {
+        format!(
+            "{} | {:.2} | {} | {} | {}",
+            self.case,
+            self.ease,
+            self.interval,
+            self.repetitions,
+            format_timestamp(self.last_review)
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScheduleError {
+    /// A line without 5 fields or a case, and how many fields it had.
+    Fields(usize, usize),
+    Ease(usize, String),
+    Interval(usize, String),
+    Repetitions(usize, String),
+    Timestamp(usize, String),
+}
+
+impl ScheduleError {
+    /// The line the error is on, counting from 1.
+    pub fn line(&self) -> usize {
+        match self {
+            Self::Fields(line, _)
+            | Self::Ease(line, _)
+            | Self::Interval(line, _)
+            | Self::Repetitions(line, _)
+            | Self::Timestamp(line, _) => *line,
+        }
+    }
+}
+
+impl Display for ScheduleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (what, source) = match self {
+            Self::Fields(line, count) => {
+                return write!(
+                    f,
+                    "line {}: expected a case and 4 fields separated by `|`, found {} fields",
+                    line, count
+                )
+            }
+            Self::Ease(_, source) => ("ease", source),
+            Self::Interval(_, source) => ("interval", source),
+            Self::Repetitions(_, source) => ("repetitions", source),
+            Self::Timestamp(_, source) => ("timestamp", source),
+        };
+        write!(
+            f,
+            "line {}: couldn't read the {} `{}`",
+            self.line(),
+            what,
+            source
+        )
+    }
+}
+
+impl Error for ScheduleError {}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Schedule {
+    pub cards: Vec<Card>,
+}
+
+impl Schedule {
+    pub fn parse(source: &str) -> Result<Self, ScheduleError> {
+        let mut cards = Vec::new();
+        for (i, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            cards.push(Card::parse_line(line, i + 1)?);
+        }
+
+        Ok(Self { cards })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub fn get(&self, case: &str) -> Option<&Card> {
+        self.cards.iter().find(|card| card.case == case)
+    }
+
+    /// Grades a review of `case`, adding a card for it if it's new.
+    pub fn review(&mut self, case: &str, quality: u8, now: u64) -> &Card {
+        let index = match self.cards.iter().position(|card| card.case == case) {
+            Some(index) => index,
+            None => {
+                self.cards.push(Card::new(case));
+                self.cards.len() - 1
+            }
+        };
+        self.cards[index].review(quality, now);
+        &self.cards[index]
+    }
+
+    /// Which of `cases` to review next: the most overdue one, then the first
+    /// that was never reviewed, then the one due soonest.
+    pub fn next<'a>(&self, cases: &[&'a str], now: u64) -> Option<&'a str> {
+        let due = |case: &str| self.get(case).map(Card::due);
+
+        let overdue = cases
+            .iter()
+            .filter(|case| due(case).is_some_and(|due| due <= now))
+            .min_by_key(|case| due(case));
+        let new = cases.iter().find(|case| due(case).is_none());
+        let soonest = cases.iter().min_by_key(|case| due(case));
+
+        overdue.or(new).or(soonest).copied()
+    }
+}
+
+/// Writes the cards in the form `parse` reads.
+impl Display for Schedule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for card in &self.cards {
+            writeln!(f, "{}", card.to_line())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intervals_grow_with_good_reviews() {
+        let mut card = Card::new("PLL T");
+        let mut intervals = Vec::new();
+        for day in 0..4 {
+            card.review(4, day * DAY);
+            intervals.push(card.interval);
+        }
+        assert_eq!(intervals, vec![1, 6, 15, 38]);
+        assert_eq!(card.ease, EASE);
+        assert_eq!(card.due(), 3 * DAY + 38 * DAY);
+
+        card.review(5, 0);
+        assert!((card.ease - 2.6).abs() < 1e-9);
+        card.review(1, 0);
+        assert_eq!((card.interval, card.repetitions), (1, 0));
+        for _ in 0..10 {
+            card.review(0, 0);
+        }
+        assert_eq!(card.ease, MIN_EASE);
+    }
+
+    #[test]
+    fn picks_what_is_due() {
+        let mut schedule = Schedule::default();
+        schedule.review("PLL T", 4, 0);
+        schedule.review("PLL Y", 4, DAY / 2);
+        schedule.review("PLL Y", 4, DAY);
+
+        let cases = ["PLL Aa", "PLL T", "PLL Y"];
+        assert_eq!(schedule.next(&cases, 2 * DAY), Some("PLL T"));
+        assert_eq!(schedule.next(&cases, 0), Some("PLL Aa"));
+        assert_eq!(schedule.next(&cases[1..], 0), Some("PLL T"));
+        assert_eq!(schedule.next(&[], 0), None);
+    }
+
+    #[test]
+    fn round_trips_and_reports_bad_lines() {
+        let mut schedule = Schedule::default();
+        schedule.review("ZBLL T3/5", 3, 1_792_418_592);
+        schedule.review("OLL 27", 5, 1_792_418_600);
+        let source = schedule.to_string();
+        assert!(source.starts_with("ZBLL T3/5 | 2.36 | 1 | 1 | 2026-10-19T"));
+        assert_eq!(Schedule::parse(&source).unwrap().to_string(), source);
+
+        let error = |source| Schedule::parse(source).unwrap_err().to_string();
+        assert_eq!(
+            error("# cards\nPLL T | 2.5 | 1"),
+            "line 2: expected a case and 4 fields separated by `|`, found 3 fields"
+        );
+        assert_eq!(
+            error("PLL T | easy | 1 | 1 | 2026-10-19T14:03:12Z"),
+            "line 1: couldn't read the ease `easy`"
+        );
+        assert_eq!(
+            error("PLL T | 2.5 | 1 | 1 | yesterday"),
+            "line 1: couldn't read the timestamp `yesterday`"
+        );
+    }
+}
@@ -0,0 +1,95 @@
+//! Random state scrambles, made by solving a uniformly random cube and
+//! inverting the solution.
+
+use crate::{
+    algoritm::Algorithm,
+    cubie::{parity, CubieCube, CORNERS, EDGES},
+    solver,
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A small xorshift generator, good enough for scrambles.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // Zero is a fixed point of xorshift.
+        Self(seed.max(1))
+    }
+
+    /// Seeded from the clock.
+    pub fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Self::new(nanos ^ 0x9e37_79b9_7f4a_7c15)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A number in `0..n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// A uniformly random state that can be reached from solved.
+pub fn random_state(rng: &mut Rng) -> CubieCube {
+    let mut cubie = CubieCube::default();
+
+    rng.shuffle(&mut cubie.cp);
+    rng.shuffle(&mut cubie.ep);
+    if parity(&cubie.cp) != parity(&cubie.ep) {
+        cubie.ep.swap(0, 1);
+    }
+
+    for i in 0..CORNERS - 1 {
+        cubie.co[i] = rng.below(3) as u8;
+    }
+    cubie.co[CORNERS - 1] = (3 - cubie.co.iter().sum::<u8>() % 3) % 3;
+    for i in 0..EDGES - 1 {
+        cubie.eo[i] = rng.below(2) as u8;
+    }
+    cubie.eo[EDGES - 1] = cubie.eo.iter().sum::<u8>() % 2;
+
+    cubie
+}
+
+/// A scramble bringing a solved cube into a random state.
+pub fn scramble(rng: &mut Rng) -> Algorithm {
+    let state = random_state(rng);
+    let solution = solver::solve(&state.to_cube()).expect("random states are solvable");
+    solution.inverse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cube;
+
+    #[test]
+    fn scramble_reaches_the_state() {
+        let mut rng = Rng::new(42);
+        let state = random_state(&mut rng);
+        assert_eq!(state.verify(), Ok(()));
+
+        let mut rng = Rng::new(42);
+        let mut cube = Cube::default();
+        cube.execute_algorithm(scramble(&mut rng));
+
+        assert_eq!(cube, state.to_cube());
+    }
+}
@@ -0,0 +1,496 @@
+//! Searching for algorithms in a restricted set of moves, like `<R, U>`,
+//! `<R, U, F>` or `<M, U>`, that solve some of the pieces.
+//!
+//! Slice and wide moves turn centres, so pieces are followed by their
+//! stickers rather than by [`CubieCube`]: a piece's state is the place of its
+//! first sticker, as in [`CORNER_FACELETS`] and [`EDGE_FACELETS`], going by
+//! the colours the centres had at the start. The search is IDA*, pruned by
+//! how many moves each piece that has to be solved is from its place.
+
+use crate::{
+    algoritm::{Algorithm, Move, MoveType},
+    cubie::{
+        CubieCube, StateError, CORNERS, CORNER_FACELETS, CORNER_NAMES, EDGES, EDGE_FACELETS,
+        EDGE_NAMES,
+    },
+    solver::UNKNOWN,
+    Colour, Cube,
+};
+use std::fmt::{self, Display, Formatter};
+
+/// The place and twist of a corner, `position * 3 + twist`, or the place
+/// and flip of an edge, `position * 2 + flip`.
+pub(crate) const STATES: usize = 24;
+
+/// Moves with where each moves every piece state and centre, and which
+/// layer each turns, numbered in the order the layers first appear.
+pub(crate) struct MoveSet {
+    pub(crate) moves: Vec<Move>,
+    layers: Vec<usize>,
+    pub(crate) corners: Vec<[u8; STATES]>,
+    pub(crate) edges: Vec<[u8; STATES]>,
+    /// The face each centre ends up on, faces in [`Cube::FACELET_ORDER`].
+    pub(crate) centres: Vec<[u8; 6]>,
+}
+
+impl MoveSet {
+    /// Every turn of the `layers`, like `R r U M`, numbered
+    /// `layer * 3 + quarter turns - 1`.
+    pub(crate) fn layers(layers: &str) -> Self {
+        Self::new(turns(&Algorithm::parse(layers).expect("layers parse")))
+    }
+
+    pub(crate) fn new(moves: Vec<Move>) -> Self {
+        let corner_states = states(&CORNER_FACELETS);
+        let edge_states = states(&EDGE_FACELETS);
+        let mut set = Self {
+            moves: Vec::new(),
+            layers: Vec::new(),
+            corners: Vec::new(),
+            edges: Vec::new(),
+            centres: Vec::new(),
+        };
+        let mut layers: Vec<&Move> = Vec::new();
+        for mv in &moves {
+            let layer = layers
+                .iter()
+                .position(|other| other.side == mv.side && other.layers == mv.layers)
+                .unwrap_or_else(|| {
+                    layers.push(mv);
+                    layers.len() - 1
+                });
+            set.layers.push(layer);
+
+            // Follow every sticker by painting it differently from the rest.
+            let mut to = [0; 54];
+            for (facelet, next) in to.iter_mut().enumerate() {
+                let mut facelets = [Colour::White; 54];
+                facelets[facelet] = Colour::Yellow;
+                let mut cube = Cube::from_facelets(&facelets);
+                cube.execute_move(mv.clone());
+                *next = cube
+                    .facelets()
+                    .iter()
+                    .position(|colour| *colour == Colour::Yellow)
+                    .unwrap();
+            }
+
+            set.corners.push(std::array::from_fn(|state| {
+                corner_states[to[CORNER_FACELETS[state / 3][state % 3]]]
+            }));
+            set.edges.push(std::array::from_fn(|state| {
+                edge_states[to[EDGE_FACELETS[state / 2][state % 2]]]
+            }));
+            set.centres
+                .push(std::array::from_fn(|face| (to[face * 9 + 4] / 9) as u8));
+        }
+        set.moves = moves;
+        set
+    }
+
+    /// Whether move `m` may follow `last`: not on the same layer, and on the
+    /// same axis only in one order, since those moves commute.
+    pub(crate) fn allowed(&self, m: usize, last: Option<usize>) -> bool {
+        let last = match last {
+            Some(last) => last,
+            None => return true,
+        };
+        let (layer, other) = (self.layers[m], self.layers[last]);
+        let (side, other_side) = (&self.moves[m].side, &self.moves[last].side);
+        layer != other && ((side != other_side && *side != other_side.opposite()) || layer > other)
+    }
+
+    pub(crate) fn algorithm(&self, moves: &[usize]) -> Algorithm {
+        Algorithm::new(moves.iter().map(|&m| self.moves[m].clone()).collect())
+    }
+}
+
+/// Every turn of each move's layer: clockwise, half and counterclockwise.
+pub fn turns(layers: &Algorithm) -> Vec<Move> {
+    layers
+        .moves
+        .iter()
+        .flat_map(|layer| {
+            (1..4).map(move |turns| Move {
+                ty: MoveType::from_quarter_turns(turns).unwrap(),
+                ..layer.clone()
+            })
+        })
+        .collect()
+}
+
+/// The state of the piece whose sticker is at each facelet, `u8::MAX` for
+/// facelets of other kinds of pieces.
+fn states<const N: usize>(facelets: &[[usize; N]]) -> [u8; 54] {
+    let mut states = [UNKNOWN; 54];
+    for (position, stickers) in facelets.iter().enumerate() {
+        for (i, &facelet) in stickers.iter().enumerate() {
+            states[facelet] = (position * N + i) as u8;
+        }
+    }
+    states
+}
+
+/// The state of the piece that belongs at `piece` with the colours of
+/// `frame`, one per face, or `None` if there's no such piece.
+pub(crate) fn piece_state<const N: usize>(
+    facelets: &[Colour; 54],
+    frame: &[Colour; 6],
+    places: &[[usize; N]],
+    piece: usize,
+) -> Option<u8> {
+    let wanted = places[piece].map(|facelet| frame[facelet / 9]);
+    for (position, stickers) in places.iter().enumerate() {
+        for turn in 0..N {
+            if (0..N).all(|i| facelets[stickers[(turn + i) % N]] == wanted[i]) {
+                return Some((position * N + turn) as u8);
+            }
+        }
+    }
+    None
+}
+
+/// The pieces an algorithm has to solve.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mask {
+    pub corners: [bool; CORNERS],
+    pub edges: [bool; EDGES],
+    pub centres: bool,
+}
+
+impl Mask {
+    pub fn all() -> Self {
+        Self {
+            corners: [true; CORNERS],
+            edges: [true; EDGES],
+            centres: true,
+        }
+    }
+
+    pub fn none() -> Self {
+        Self {
+            corners: [false; CORNERS],
+            edges: [false; EDGES],
+            centres: false,
+        }
+    }
+
+    /// The first two layers with the top on `U`, and the centres.
+    pub fn f2l() -> Self {
+        let mut mask = Self::all();
+        mask.corners[..4].fill(false);
+        mask.edges[..4].fill(false);
+        mask
+    }
+
+    /// Reads pieces separated by spaces or commas, named like `UF` or `URF`
+    /// in any order of faces, with `centres`, and `all` or `f2l` for
+    /// [`Mask::all`] or [`Mask::f2l`].
+    ///
+    /// ```
+    /// # use cube::search::Mask;
+    /// let mask = Mask::parse("f2l, UF FUR").unwrap();
+    /// assert_eq!(mask.to_string(), "UF DR DF DL DB FR FL BL BR URF DFR DLF DBL DRB centres");
+    /// ```
+    pub fn parse(source: &str) -> Option<Self> {
+        let sorted = |name: &str| {
+            let mut letters: Vec<char> = name.to_ascii_uppercase().chars().collect();
+            letters.sort_unstable();
+            letters
+        };
+
+        let mut mask = Self::none();
+        for word in source.split([' ', ',']).filter(|word| !word.is_empty()) {
+            match word {
+                "all" => mask = Self::all(),
+                "f2l" => {
+                    let f2l = Self::f2l();
+                    mask.corners = std::array::from_fn(|i| mask.corners[i] || f2l.corners[i]);
+                    mask.edges = std::array::from_fn(|i| mask.edges[i] || f2l.edges[i]);
+                    mask.centres = true;
+                }
+                "centres" => mask.centres = true,
+                name => {
+                    let letters = sorted(name);
+                    if let Some(i) = EDGE_NAMES.iter().position(|n| sorted(n) == letters) {
+                        mask.edges[i] = true;
+                    } else if let Some(i) = CORNER_NAMES.iter().position(|n| sorted(n) == letters) {
+                        mask.corners[i] = true;
+                    } else {
+                        return None;
+                    }
+                }
+            }
+        }
+        Some(mask)
+    }
+}
+
+/// Names the pieces, edges then corners, like `UF URF centres`.
+impl Display for Mask {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut names = EDGE_NAMES
+            .iter()
+            .zip(self.edges)
+            .chain(CORNER_NAMES.iter().zip(self.corners))
+            .filter(|(_, masked)| *masked)
+            .map(|(name, _)| *name)
+            .chain(self.centres.then_some("centres"));
+        if let Some(first) = names.next() {
+            write!(f, "{}", first)?;
+        }
+        for name in names {
+            write!(f, " {}", name)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// The longest algorithm to look for.
+    pub max_length: usize,
+    /// The most algorithms to give.
+    pub limit: usize,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            max_length: 8,
+            limit: 10,
+        }
+    }
+}
+
+/// Every piece's state, and the faces of the up and front centres.
+#[derive(Clone, Copy)]
+struct State {
+    corners: [u8; CORNERS],
+    edges: [u8; EDGES],
+    centres: [u8; 2],
+}
+
+struct Search<'a> {
+    set: &'a MoveSet,
+    mask: &'a Mask,
+    goal: &'a dyn Fn(&Cube) -> bool,
+    start: &'a Cube,
+    /// How many moves each piece is from each state.
+    corner_distances: Vec<[u8; STATES]>,
+    edge_distances: Vec<[u8; STATES]>,
+    centre_distances: [u8; 36],
+    moves: Vec<usize>,
+    found: Vec<Algorithm>,
+    limit: usize,
+}
+
+impl Search<'_> {
+    fn turn(&self, state: &State, m: usize) -> State {
+        State {
+            corners: state.corners.map(|s| self.set.corners[m][s as usize]),
+            edges: state.edges.map(|s| self.set.edges[m][s as usize]),
+            centres: state.centres.map(|face| self.set.centres[m][face as usize]),
+        }
+    }
+
+    /// At least how many moves it takes to solve the masked pieces.
+    fn distance(&self, state: &State) -> usize {
+        let corners = (0..CORNERS)
+            .filter(|&i| self.mask.corners[i])
+            .map(|i| self.corner_distances[i][state.corners[i] as usize]);
+        let edges = (0..EDGES)
+            .filter(|&i| self.mask.edges[i])
+            .map(|i| self.edge_distances[i][state.edges[i] as usize]);
+        let centres = self
+            .mask
+            .centres
+            .then(|| self.centre_distances[(state.centres[0] * 6 + state.centres[1]) as usize]);
+        corners.chain(edges).chain(centres).max().unwrap_or(0) as usize
+    }
+
+    fn at_goal(&self, state: &State) -> bool {
+        if self.distance(state) != 0 {
+            return false;
+        }
+        let mut cube = self.start.clone();
+        cube.execute_algorithm(self.set.algorithm(&self.moves));
+        (self.goal)(&cube)
+    }
+
+    fn search(&mut self, state: State, left: usize) {
+        if self.distance(&state) > left || self.found.len() >= self.limit {
+            return;
+        }
+        // Longer algorithms that pass through the goal are shorter ones
+        // with something that undoes itself after.
+        if self.at_goal(&state) {
+            if left == 0 {
+                self.found.push(self.set.algorithm(&self.moves));
+            }
+            return;
+        }
+        if left == 0 {
+            return;
+        }
+
+        for m in 0..self.set.moves.len() {
+            if !self.set.allowed(m, self.moves.last().copied()) {
+                continue;
+            }
+            self.moves.push(m);
+            self.search(self.turn(&state, m), left - 1);
+            self.moves.pop();
+        }
+    }
+}
+
+/// How many of `moves` each state is from `solved`, following states by
+/// `table`.
+fn distances<const N: usize>(moves: &[[u8; N]], solved: usize) -> [u8; N] {
+    let mut distances = [UNKNOWN; N];
+    distances[solved] = 0;
+    let mut frontier = vec![solved];
+    let mut depth = 0;
+    while !frontier.is_empty() {
+        let mut next = Vec::new();
+        for state in frontier {
+            for row in moves {
+                let to = row[state] as usize;
+                if distances[to] == UNKNOWN {
+                    distances[to] = depth + 1;
+                    next.push(to);
+                }
+            }
+        }
+        frontier = next;
+        depth += 1;
+    }
+    distances
+}
+
+/// Every algorithm of `moves`, up to `options.max_length` long, that solves
+/// the pieces in `mask` and reaches `goal`, shortest first, up to
+/// `options.limit` of them. Algorithms reaching the goal part way through
+/// are left out.
+///
+/// ```
+/// # use cube::{algoritm::Algorithm, search::{self, Mask, SearchOptions}, Cube};
+/// let mut cube = Cube::default();
+/// cube.execute_algorithm(Algorithm::parse("R U R' U'").unwrap());
+///
+/// let moves = search::turns(&Algorithm::parse("R U").unwrap());
+/// let options = SearchOptions { max_length: 4, limit: 10 };
+/// let found = search::find(&cube, &moves, &Mask::all(), &|_| true, &options).unwrap();
+/// assert_eq!(found[0].to_string(), "U R U' R'");
+/// ```
+pub fn find(
+    cube: &Cube,
+    moves: &[Move],
+    mask: &Mask,
+    goal: &dyn Fn(&Cube) -> bool,
+    options: &SearchOptions,
+) -> Result<Vec<Algorithm>, StateError> {
+    CubieCube::from_cube(cube)?;
+    let set = MoveSet::new(moves.to_vec());
+    let facelets = cube.facelets();
+    let frame: [Colour; 6] = std::array::from_fn(|face| facelets[face * 9 + 4]);
+
+    let mut state = State {
+        corners: [0; CORNERS],
+        edges: [0; EDGES],
+        centres: [0, 2],
+    };
+    for (i, corner) in state.corners.iter_mut().enumerate() {
+        *corner =
+            piece_state(&facelets, &frame, &CORNER_FACELETS, i).ok_or(StateError::Corner(i))?;
+    }
+    for (i, edge) in state.edges.iter_mut().enumerate() {
+        *edge = piece_state(&facelets, &frame, &EDGE_FACELETS, i).ok_or(StateError::Edge(i))?;
+    }
+
+    // Both centres, as `up * 6 + front`.
+    let centre_moves: Vec<[u8; 36]> = set
+        .centres
+        .iter()
+        .map(|row| std::array::from_fn(|i| row[i / 6] * 6 + row[i % 6]))
+        .collect();
+    let mut search = Search {
+        set: &set,
+        mask,
+        goal,
+        start: cube,
+        corner_distances: (0..CORNERS)
+            .map(|i| distances(&set.corners, i * 3))
+            .collect(),
+        edge_distances: (0..EDGES).map(|i| distances(&set.edges, i * 2)).collect(),
+        centre_distances: distances(&centre_moves, 2),
+        moves: Vec::new(),
+        found: Vec::new(),
+        limit: options.limit,
+    };
+    for depth in 0..=options.max_length {
+        search.search(state, depth);
+    }
+    Ok(search.found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scrambled;
+
+    fn layers(layers: &str) -> Vec<Move> {
+        turns(&Algorithm::parse(layers).unwrap())
+    }
+
+    #[test]
+    fn finds_algorithms_in_the_moves_given() {
+        let options = SearchOptions {
+            max_length: 7,
+            limit: 100,
+        };
+        let cube = scrambled("F R U R' U' F'");
+        let found = find(&cube, &layers("R U F"), &Mask::all(), &|_| true, &options).unwrap();
+        assert_eq!(found[0].to_string(), "F U R U' R' F'");
+        for alg in &found {
+            let mut solved = cube.clone();
+            solved.execute_algorithm(alg.clone());
+            assert!(solved.is_solved(), "{}", alg);
+            assert!(alg
+                .moves
+                .iter()
+                .all(|mv| "RUF".contains(&mv.to_string()[..1])));
+        }
+
+        // M2 U M2 U2 M2 U M2 undoes itself in a different order too.
+        let cube = scrambled("M2 U M2 U2 M2 U M2");
+        let found = find(&cube, &layers("M U"), &Mask::all(), &|_| true, &options).unwrap();
+        let shown: Vec<String> = found.iter().map(|alg| alg.to_string()).collect();
+        assert!(
+            shown.contains(&"M2 U M2 U2 M2 U M2".to_string()),
+            "{:?}",
+            shown
+        );
+    }
+
+    #[test]
+    fn ignores_pieces_outside_the_mask() {
+        let options = SearchOptions::default();
+        let cube = scrambled("U");
+        let found = find(&cube, &layers("R U"), &Mask::f2l(), &|_| true, &options).unwrap();
+        assert_eq!(found[0].to_string(), "");
+
+        // A goal can ask for more than the mask.
+        let solved = |cube: &Cube| cube.is_solved();
+        let found = find(&cube, &layers("R U"), &Mask::f2l(), &solved, &options).unwrap();
+        assert_eq!(found[0].to_string(), "U'");
+
+        assert_eq!(
+            Mask::parse("UF FRU centres").unwrap().to_string(),
+            "UF URF centres"
+        );
+        assert_eq!(Mask::parse("UFL"), Some(Mask::parse("LUF").unwrap()));
+        assert_eq!(Mask::parse("UD"), None);
+    }
+}
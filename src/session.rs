@@ -0,0 +1,654 @@
+//! Timed solves and their statistics.
+//!
+//! A session is kept as text, one solve per line with fields separated by
+//! `|`: the time in milliseconds, the penalty (`OK`, `+2` or `DNF`), when
+//! the solve was done in UTC, the scramble, the reconstruction if there is
+//! one, and a comment.
+//!
+//! ```text
+//! 12345 | OK  | 2026-10-19T14:03:12Z | R U2 F' ... |  |
+//! 13102 | +2  | 2026-10-19T14:04:40Z | D' L2 B ... |  | slow cross
+//! 11020 | DNF | 2026-10-19T14:05:51Z | F R' U ... | D R' F D' ... |
+//! ```
+
+use crate::algoritm::Algorithm;
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    fs,
+    io::{self, Write},
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// How long inspection lasts before penalties start.
+pub const INSPECTION: Duration = Duration::from_secs(15);
+/// Going past this in inspection makes the solve a DNF.
+pub const INSPECTION_LIMIT: Duration = Duration::from_secs(17);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Penalty {
+    None,
+    PlusTwo,
+    Dnf,
+}
+
+impl Penalty {
+    fn parse(source: &str) -> Option<Self> {
+        match source {
+            "OK" => Some(Self::None),
+            "+2" => Some(Self::PlusTwo),
+            "DNF" => Some(Self::Dnf),
+            _ => None,
+        }
+    }
+
+    /// The WCA penalty for starting after `inspection`.
+    pub fn from_inspection(inspection: Duration) -> Self {
+        if inspection <= INSPECTION {
+            Self::None
+        } else if inspection <= INSPECTION_LIMIT {
+            Self::PlusTwo
+        } else {
+            Self::Dnf
+        }
+    }
+}
+
+/// A result that counts: a time, or a DNF, which is worse than any time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Time {
+    Finished(Duration),
+    Dnf,
+}
+
+impl Display for Penalty {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => write!(f, "OK"),
+            Self::PlusTwo => write!(f, "+2"),
+            Self::Dnf => write!(f, "DNF"),
+        }
+    }
+}
+
+impl Display for Time {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Finished(time) => write!(f, "{}", format_time(*time)),
+            Self::Dnf => write!(f, "DNF"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solve {
+    pub scramble: Algorithm,
+    /// The time on the timer, without the penalty.
+    pub time: Duration,
+    pub penalty: Penalty,
+    /// When the solve was done, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub comment: String,
+    pub reconstruction: Option<Algorithm>,
+}
+
+impl Solve {
+    /// A solve done just now, without a comment or reconstruction.
+    pub fn new(scramble: Algorithm, time: Duration, penalty: Penalty) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+
+        Self {
+            scramble,
+            time,
+            penalty,
+            timestamp,
+            comment: String::new(),
+            reconstruction: None,
+        }
+    }
+
+    /// Reads a line written by `to_line`, reporting errors against `line`.
+    pub fn parse_line(source: &str, line: usize) -> Result<Self, SessionError> {
+        let fields: Vec<&str> = source.splitn(6, '|').map(str::trim).collect();
+        if fields.len() != 6 {
+            return Err(SessionError::Fields(line, fields.len()));
+        }
+        let field = |i: usize| fields[i].to_string();
+
+        let time = fields[0]
+            .parse()
+            .map(Duration::from_millis)
+            .map_err(|_| SessionError::Time(line, field(0)))?;
+        let penalty =
+            Penalty::parse(fields[1]).ok_or_else(|| SessionError::Penalty(line, field(1)))?;
+        let timestamp =
+            parse_timestamp(fields[2]).ok_or_else(|| SessionError::Timestamp(line, field(2)))?;
+        let scramble =
+            Algorithm::parse(fields[3]).ok_or_else(|| SessionError::Scramble(line, field(3)))?;
+        let reconstruction = match fields[4] {
+            "" => None,
+            source => Some(
+                Algorithm::parse(source)
+                    .ok_or_else(|| SessionError::Reconstruction(line, field(4)))?,
+            ),
+        };
+
+        Ok(Self {
+            scramble,
+            time,
+            penalty,
+            timestamp,
+            comment: field(5),
+            reconstruction,
+        })
+    }
+
+    /// The solve as a line of a session file, without the line break.
+    pub fn to_line(&self) -> String {
+        let reconstruction = self
+            .reconstruction
+            .as_ref()
+            .map_or(String::new(), Algorithm::to_string);
+        let line = format!(
+            "{} | {:<3} | {} | {} | {} | {}",
+            self.time.as_millis(),
+            self.penalty,
+            format_timestamp(self.timestamp),
+            self.scramble,
+            reconstruction,
+            // A line break would start a new solve.
+            self.comment.replace('\n', " ")
+        );
+        line.trim_end().to_string()
+    }
+
+    /// The time that counts.
+    pub fn result(&self) -> Time {
+        match self.penalty {
+            Penalty::None => Time::Finished(self.time),
+            Penalty::PlusTwo => Time::Finished(self.time + Duration::from_secs(2)),
+            Penalty::Dnf => Time::Dnf,
+        }
+    }
+}
+
+/// Shows the result the way timers do: `12.34`, `13.34+` or `DNF(12.34)`.
+impl Display for Solve {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (self.penalty, self.result()) {
+            (Penalty::PlusTwo, result) => write!(f, "{}+", result),
+            (_, Time::Finished(time)) => write!(f, "{}", format_time(time)),
+            (_, Time::Dnf) => write!(f, "DNF({})", format_time(self.time)),
+        }
+    }
+}
+
+/// Formats a time in hundredths, with minutes once there are any.
+pub fn format_time(time: Duration) -> String {
+    let hundredths = time.as_millis() / 10;
+    let (minutes, seconds) = (hundredths / 6000, hundredths % 6000);
+    if minutes > 0 {
+        format!("{}:{:02}.{:02}", minutes, seconds / 100, seconds % 100)
+    } else {
+        format!("{}.{:02}", seconds / 100, seconds % 100)
+    }
+}
+
+/// Reads a time written by `format_time`, like `9.87` or `1:23.05`.
+pub fn parse_time(source: &str) -> Option<Duration> {
+    let (minutes, seconds) = match source.split_once(':') {
+        Some((minutes, seconds)) => (minutes.parse::<u64>().ok()?, seconds),
+        None => (0, source),
+    };
+    let seconds = seconds.parse::<f64>().ok()?;
+    if !seconds.is_finite() || seconds < 0.0 || (minutes > 0 && seconds >= 60.0) {
+        return None;
+    }
+
+    let millis = minutes * 60_000 + (seconds * 1000.0).round() as u64;
+    Some(Duration::from_millis(millis))
+}
+
+/// Formats seconds since the Unix epoch as a UTC date and time, like
+/// `2026-10-19T14:03:12Z`.
+pub fn format_timestamp(timestamp: u64) -> String {
+    let (days, seconds) = (timestamp / 86_400, timestamp % 86_400);
+
+    // Howard Hinnant's civil_from_days, counting from 0000-03-01.
+    let days = days + 719_468;
+    let (era, day_of_era) = (days / 146_097, days % 146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + (month <= 2) as u64;
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Reads a time written by `format_timestamp`.
+pub fn parse_timestamp(source: &str) -> Option<u64> {
+    let source = source.strip_suffix('Z')?;
+    let (date, time) = source.split_once('T')?;
+    let number = |part: Option<&str>| part?.parse::<u64>().ok();
+
+    let mut date = date.split('-');
+    let (year, month, day) = (
+        number(date.next())?,
+        number(date.next())?,
+        number(date.next())?,
+    );
+    let mut time = time.split(':');
+    let (hour, minute, second) = (
+        number(time.next())?,
+        number(time.next())?,
+        number(time.next())?,
+    );
+    if date.next().is_some() || time.next().is_some() {
+        return None;
+    }
+    if year < 1970 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    if hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    // The inverse of format_timestamp, again counting from March.
+    let year = year - (month <= 2) as u64;
+    let (era, year_of_era) = (year / 400, year % 400);
+    let shifted_month = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * shifted_month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let timestamp = days * 86_400 + hour * 3600 + minute * 60 + second;
+    // Catches days past the end of the month, like February 30th.
+    if format_timestamp(timestamp) != format!("{}Z", source) {
+        return None;
+    }
+    Some(timestamp)
+}
+
+/// The WCA average: the best and worst 5% (rounded up) are dropped and the
+/// rest averaged to the nearest hundredth, so more DNFs than dropped solves
+/// make a DNF. `None` if nothing is left after trimming.
+pub fn average(times: &[Time]) -> Option<Time> {
+    let trim = (times.len() * 5).div_ceil(100);
+    if times.len() <= 2 * trim {
+        return None;
+    }
+    let mut sorted = times.to_vec();
+    sorted.sort();
+
+    let counting = &sorted[trim..times.len() - trim];
+    let mut total = Duration::from_secs(0);
+    for time in counting {
+        match time {
+            Time::Finished(time) => total += *time,
+            Time::Dnf => return Some(Time::Dnf),
+        }
+    }
+
+    let hundredth = Duration::from_millis(10).as_nanos();
+    let count = counting.len() as u128;
+    let hundredths = (total.as_nanos() * 2 / (hundredth * count)).div_ceil(2);
+    Some(Time::Finished(Duration::from_millis(
+        hundredths as u64 * 10,
+    )))
+}
+
+/// The positions where a new best was set, with the time.
+pub fn personal_bests(times: &[Time]) -> Vec<(usize, Time)> {
+    let mut bests: Vec<(usize, Time)> = Vec::new();
+    for (i, time) in times.iter().enumerate() {
+        let improves = match bests.last() {
+            Some((_, best)) => time < best,
+            None => *time != Time::Dnf,
+        };
+        if improves {
+            bests.push((i, *time));
+        }
+    }
+    bests
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SessionError {
+    /// A line with the wrong number of fields, and how many it had.
+    Fields(usize, usize),
+    Time(usize, String),
+    Penalty(usize, String),
+    Timestamp(usize, String),
+    Scramble(usize, String),
+    Reconstruction(usize, String),
+}
+
+impl SessionError {
+    /// The line the error is on, counting from 1.
+    pub fn line(&self) -> usize {
+        match self {
+            Self::Fields(line, _)
+            | Self::Time(line, _)
+            | Self::Penalty(line, _)
+            | Self::Timestamp(line, _)
+            | Self::Scramble(line, _)
+            | Self::Reconstruction(line, _) => *line,
+        }
+    }
+}
+
+impl Display for SessionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (what, source) = match self {
+            Self::Fields(line, count) => {
+                return write!(
+                    f,
+                    "line {}: expected 6 fields separated by `|`, found {}",
+                    line, count
+                )
+            }
+            Self::Time(_, source) => ("time", source),
+            Self::Penalty(_, source) => ("penalty", source),
+            Self::Timestamp(_, source) => ("timestamp", source),
+            Self::Scramble(_, source) => ("scramble", source),
+            Self::Reconstruction(_, source) => ("reconstruction", source),
+        };
+        write!(
+            f,
+            "line {}: couldn't read the {} `{}`",
+            self.line(),
+            what,
+            source
+        )
+    }
+}
+
+impl Error for SessionError {}
+
+/// Solves in the order they were done.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Session {
+    pub solves: Vec<Solve>,
+}
+
+impl Session {
+    pub fn new(solves: Vec<Solve>) -> Self {
+        Self { solves }
+    }
+
+    /// Reads a session written by `Display`. Blank lines and lines starting
+    /// with `#` are skipped.
+    pub fn parse(source: &str) -> Result<Self, SessionError> {
+        let mut solves = Vec::new();
+        for (i, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            solves.push(Solve::parse_line(line, i + 1)?);
+        }
+
+        Ok(Self::new(solves))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    /// Adds one solve to the end of a session file, creating it if needed,
+    /// so nothing is lost if the program stops.
+    pub fn append<P: AsRef<Path>>(path: P, solve: &Solve) -> io::Result<()> {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        writeln!(file, "{}", solve.to_line())
+    }
+
+    pub fn results(&self) -> Vec<Time> {
+        self.solves.iter().map(Solve::result).collect()
+    }
+
+    /// The mean of the finished solves, DNF if none finished.
+    pub fn mean(&self) -> Option<Time> {
+        if self.solves.is_empty() {
+            return None;
+        }
+
+        let finished: Vec<Duration> = self
+            .results()
+            .into_iter()
+            .filter_map(|time| match time {
+                Time::Finished(time) => Some(time),
+                Time::Dnf => None,
+            })
+            .collect();
+        if finished.is_empty() {
+            return Some(Time::Dnf);
+        }
+
+        let total: Duration = finished.iter().sum();
+        Some(Time::Finished(total / finished.len() as u32))
+    }
+
+    pub fn best(&self) -> Option<Time> {
+        self.results().into_iter().min()
+    }
+
+    pub fn worst(&self) -> Option<Time> {
+        self.results().into_iter().max()
+    }
+
+    /// The average of the last `count` solves, if there are that many.
+    pub fn current_average(&self, count: usize) -> Option<Time> {
+        let results = self.results();
+        let start = results.len().checked_sub(count)?;
+        average(&results[start..])
+    }
+
+    /// The average of `count` ending at each solve, `None` for the first
+    /// solves where there aren't enough yet.
+    pub fn rolling_averages(&self, count: usize) -> Vec<Option<Time>> {
+        let results = self.results();
+        (0..results.len())
+            .map(|end| {
+                let start = (end + 1).checked_sub(count)?;
+                average(&results[start..=end])
+            })
+            .collect()
+    }
+
+    /// The best average of `count` in the session.
+    pub fn best_average(&self, count: usize) -> Option<Time> {
+        self.rolling_averages(count).into_iter().flatten().min()
+    }
+}
+
+impl Display for Session {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for solve in &self.solves {
+            writeln!(f, "{}", solve.to_line())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn times(source: &[f64]) -> Vec<Time> {
+        source
+            .iter()
+            .map(|&secs| {
+                if secs < 0.0 {
+                    Time::Dnf
+                } else {
+                    Time::Finished(Duration::from_secs_f64(secs))
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn penalties_and_formatting() {
+        let secs = Duration::from_secs_f64;
+        assert_eq!(Penalty::from_inspection(secs(14.9)), Penalty::None);
+        assert_eq!(Penalty::from_inspection(secs(16.0)), Penalty::PlusTwo);
+        assert_eq!(Penalty::from_inspection(secs(17.5)), Penalty::Dnf);
+
+        assert_eq!(format_time(Duration::from_millis(9_876)), "9.87");
+        assert_eq!(format_time(Duration::from_millis(83_050)), "1:23.05");
+        assert_eq!(parse_time("1:23.05"), Some(Duration::from_millis(83_050)));
+        assert_eq!(parse_time("1:75"), None);
+
+        let mut solve = Solve::new(
+            Algorithm::parse("R U").unwrap(),
+            Duration::from_millis(12_340),
+            Penalty::None,
+        );
+        assert_eq!(solve.to_string(), "12.34");
+        solve.penalty = Penalty::PlusTwo;
+        assert_eq!(solve.to_string(), "14.34+");
+        solve.penalty = Penalty::Dnf;
+        assert_eq!(solve.to_string(), "DNF(12.34)");
+    }
+
+    #[test]
+    fn wca_averages() {
+        let secs = |secs| Time::Finished(Duration::from_secs_f64(secs));
+        let average = |times: &[Time]| average(times).unwrap();
+        assert_eq!(average(&times(&[10.0, 12.0, 11.0, 30.0, 9.0])), secs(11.0));
+        assert_eq!(average(&times(&[10.0, 12.0, 11.0, -1.0, 9.0])), secs(11.0));
+        assert_eq!(average(&times(&[10.0, -1.0, 11.0, -1.0, 9.0])), Time::Dnf);
+        // 30.02 / 3 rounds up to 10.01.
+        assert_eq!(
+            average(&times(&[9.0, 10.0, 10.01, 10.01, 20.0])),
+            secs(10.01)
+        );
+
+        // An ao100 drops five solves at either end, so five DNFs still count.
+        let mut hundred = vec![Time::Dnf; 5];
+        hundred.extend((0..95).map(|_| secs(10.0)));
+        assert_eq!(average(&hundred), secs(10.0));
+        hundred[5] = Time::Dnf;
+        assert_eq!(average(&hundred), Time::Dnf);
+
+        // One solve is trimmed from either end of anything under 21.
+        assert_eq!(super::average(&[]), None);
+        assert_eq!(super::average(&times(&[10.0])), None);
+        assert_eq!(super::average(&times(&[10.0, 11.0])), None);
+        assert_eq!(
+            super::average(&times(&[10.0, 11.0, 12.0])),
+            Some(secs(11.0))
+        );
+
+        assert_eq!(
+            personal_bests(&times(&[-1.0, 12.0, 13.0, 11.0, 11.0, 9.0])),
+            vec![(1, secs(12.0)), (3, secs(11.0)), (5, secs(9.0))]
+        );
+    }
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(951_825_600), "2000-02-29T12:00:00Z");
+        assert_eq!(format_timestamp(1_792_418_592), "2026-10-19T14:03:12Z");
+        for &timestamp in [0, 951_825_600, 1_792_418_592, 4_102_444_799].iter() {
+            assert_eq!(
+                parse_timestamp(&format_timestamp(timestamp)),
+                Some(timestamp)
+            );
+        }
+        assert_eq!(parse_timestamp("2026-02-30T00:00:00Z"), None);
+        assert_eq!(parse_timestamp("2026-10-19 14:03:12"), None);
+    }
+
+    #[test]
+    fn session_round_trips_and_reports_bad_lines() {
+        let source = "\
+12340 | OK | 2026-10-19T14:03:12Z | R U | |
+# a comment
+
+12340 | +2 | 2026-10-19T14:04:00Z | F2 | F2 | easy | really
+9990 | DNF | 2026-10-19T14:05:00Z | D' | |
+10000 | OK | 2026-10-19T14:06:00Z | B | |
+11000 | OK | 2026-10-19T14:07:00Z | L | |
+";
+        let session = Session::parse(source).unwrap();
+        assert_eq!(session.solves.len(), 5);
+        let second = &session.solves[1];
+        assert_eq!(second.to_string(), "14.34+");
+        assert_eq!(second.comment, "easy | really");
+        assert_eq!(second.reconstruction, Algorithm::parse("F2"));
+        assert_eq!(Session::parse(&session.to_string()).unwrap(), session);
+
+        assert_eq!(session.best().unwrap().to_string(), "10.00");
+        assert_eq!(session.worst(), Some(Time::Dnf));
+        assert_eq!(session.mean().unwrap().to_string(), "11.92");
+        assert_eq!(session.current_average(5).unwrap().to_string(), "12.56");
+        assert_eq!(session.current_average(12), None);
+        for count in 0..3 {
+            assert_eq!(session.current_average(count), None);
+            assert_eq!(session.best_average(count), None);
+        }
+
+        let bad = |source: &str| Session::parse(source).unwrap_err().to_string();
+        assert_eq!(
+            Session::parse("12340 | OK | 2026-10-19T14:03:12Z | R | |\n1x | OK | x | R | |"),
+            Err(SessionError::Time(2, "1x".to_string()))
+        );
+        assert_eq!(
+            bad("\n1 | OK | 2026-10-19T14:03:12Z | R Q | |"),
+            "line 2: couldn't read the scramble `R Q`"
+        );
+        assert_eq!(
+            bad("1 | +3 | 2026-10-19T14:03:12Z | R | |"),
+            "line 1: couldn't read the penalty `+3`"
+        );
+        assert_eq!(
+            bad("1 | OK | 2026-10-19T14:03:12Z | R | R X |"),
+            "line 1: couldn't read the reconstruction `R X`"
+        );
+        assert_eq!(
+            bad("1 | OK | R"),
+            "line 1: expected 6 fields separated by `|`, found 3"
+        );
+    }
+
+    #[test]
+    fn appends_to_files() {
+        let path = std::env::temp_dir().join(format!("cube-session-{}.txt", std::process::id()));
+        let mut solve = Solve::new(
+            Algorithm::parse("R U R'").unwrap(),
+            Duration::from_millis(8_765),
+            Penalty::None,
+        );
+        solve.comment = "first\nline".to_string();
+        Session::append(&path, &solve).unwrap();
+        Session::append(&path, &solve).unwrap();
+        let loaded = Session::parse(&fs::read_to_string(&path).unwrap()).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        solve.comment = "first line".to_string();
+        assert_eq!(loaded.solves, vec![solve.clone(), solve]);
+    }
+}
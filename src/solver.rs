@@ -0,0 +1,472 @@
+//! Kociemba's two-phase algorithm.
+//!
+//! Phase one brings the cube into the group generated by U, D, R2, L2, F2
+//! and B2, where every piece is oriented and the middle layer edges are in
+//! the middle layer. Phase two solves it using only those moves. Both phases
+//! are IDA* searches over small coordinates, pruned by tables that are built
+//! the first time the solver is used.
+
+use crate::{
+    algoritm::{Algorithm, Move, MoveType},
+    cubie::{CubieCube, StateError, CORNERS, EDGES},
+    Cube,
+};
+use std::sync::OnceLock;
+
+const TWISTS: usize = 2187;
+const FLIPS: usize = 2048;
+const SLICES: usize = 495;
+const CORNER_PERMS: usize = 40320;
+const EDGE_PERMS: usize = 40320;
+const SLICE_PERMS: usize = 24;
+
+/// Moves are numbered `face * 3 + quarter turns - 1`, faces in
+/// [`Cube::FACELET_ORDER`].
+pub(crate) const MOVES: usize = 18;
+/// The moves allowed in phase two.
+const PHASE2_MOVES: [usize; 10] = [0, 1, 2, 9, 10, 11, 4, 7, 13, 16];
+
+pub(crate) const UNKNOWN: u8 = u8::MAX;
+
+/// The longest solution the solver aims for before accepting longer ones.
+pub const DEFAULT_MAX_LENGTH: usize = 23;
+
+fn choose(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+fn twist(c: &CubieCube) -> usize {
+    c.co[..CORNERS - 1]
+        .iter()
+        .fold(0, |acc, o| acc * 3 + *o as usize)
+}
+
+fn set_twist(c: &mut CubieCube, mut twist: usize) {
+    let mut sum = 0;
+    for i in (0..CORNERS - 1).rev() {
+        c.co[i] = (twist % 3) as u8;
+        sum += c.co[i];
+        twist /= 3;
+    }
+    c.co[CORNERS - 1] = (3 - sum % 3) % 3;
+}
+
+fn flip(c: &CubieCube) -> usize {
+    c.eo[..EDGES - 1]
+        .iter()
+        .fold(0, |acc, o| acc * 2 + *o as usize)
+}
+
+fn set_flip(c: &mut CubieCube, mut flip: usize) {
+    let mut sum = 0;
+    for i in (0..EDGES - 1).rev() {
+        c.eo[i] = (flip % 2) as u8;
+        sum += c.eo[i];
+        flip /= 2;
+    }
+    c.eo[EDGES - 1] = sum % 2;
+}
+
+/// Where the four middle layer edges are, zero when they're in the middle layer.
+fn slice(c: &CubieCube) -> usize {
+    let mut index = 0;
+    let mut seen = 0;
+    for j in (0..EDGES).rev() {
+        if c.ep[j] >= 8 {
+            seen += 1;
+            index += choose(EDGES - 1 - j, seen);
+        }
+    }
+    index
+}
+
+fn set_slice(c: &mut CubieCube, mut index: usize) {
+    let mut left = 4;
+    let (mut slice_edge, mut other_edge) = (8, 0);
+    for j in 0..EDGES {
+        if left > 0 && choose(EDGES - 1 - j, left) <= index {
+            index -= choose(EDGES - 1 - j, left);
+            left -= 1;
+            c.ep[j] = slice_edge;
+            slice_edge += 1;
+        } else {
+            c.ep[j] = other_edge;
+            other_edge += 1;
+        }
+    }
+}
+
+fn perm_index(perm: &[u8]) -> usize {
+    let mut index = 0;
+    for i in 0..perm.len() {
+        let smaller = perm[i + 1..].iter().filter(|p| **p < perm[i]).count();
+        index = index * (perm.len() - i) + smaller;
+    }
+    index
+}
+
+fn set_perm(perm: &mut [u8], mut index: usize) {
+    let n = perm.len();
+    let mut digits = vec![0; n];
+    for i in (0..n).rev() {
+        digits[i] = index % (n - i);
+        index /= n - i;
+    }
+    let mut unused: Vec<u8> = (0..n as u8).collect();
+    for i in 0..n {
+        perm[i] = unused.remove(digits[i]);
+    }
+}
+
+/// Builds the table of a coordinate after every move in `moves`, for all
+/// `size` values it takes. `set` and `get` convert between it and pieces.
+fn move_table<S, G>(size: usize, moves: &[usize], set: S, get: G) -> Vec<Vec<u16>>
+where
+    S: Fn(&mut CubieCube, usize),
+    G: Fn(&CubieCube) -> usize,
+{
+    (0..size)
+        .map(|value| {
+            let mut cubie = CubieCube::default();
+            set(&mut cubie, value);
+            moves
+                .iter()
+                .map(|m| {
+                    let mut turned = cubie.clone();
+                    for _ in 0..=m % 3 {
+                        turned = turned.multiply(CubieCube::face_turn(m / 3));
+                    }
+                    get(&turned) as u16
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Breadth first search from the solved state over a pair of coordinates,
+/// giving the number of moves needed to solve both.
+fn prune_table(a: &[Vec<u16>], b: &[Vec<u16>], moves: usize) -> Vec<u8> {
+    let size_b = b.len();
+    let mut table = vec![UNKNOWN; a.len() * size_b];
+    table[0] = 0;
+
+    let mut depth = 0;
+    let mut found = true;
+    while found {
+        found = false;
+        for i in 0..table.len() {
+            if table[i] != depth {
+                continue;
+            }
+            let (x, y) = (i / size_b, i % size_b);
+            for m in 0..moves {
+                let j = a[x][m] as usize * size_b + b[y][m] as usize;
+                if table[j] == UNKNOWN {
+                    table[j] = depth + 1;
+                    found = true;
+                }
+            }
+        }
+        depth += 1;
+    }
+
+    table
+}
+
+struct Tables {
+    twist: Vec<Vec<u16>>,
+    flip: Vec<Vec<u16>>,
+    slice: Vec<Vec<u16>>,
+    corners: Vec<Vec<u16>>,
+    edges: Vec<Vec<u16>>,
+    slice_perm: Vec<Vec<u16>>,
+    twist_slice: Vec<u8>,
+    flip_slice: Vec<u8>,
+    corners_slice: Vec<u8>,
+    edges_slice: Vec<u8>,
+}
+
+impl Tables {
+    fn new() -> Self {
+        let all: Vec<_> = (0..MOVES).collect();
+
+        let twist = move_table(TWISTS, &all, set_twist, twist);
+        let flip = move_table(FLIPS, &all, set_flip, flip);
+        let slice = move_table(SLICES, &all, set_slice, slice);
+        let corners = move_table(
+            CORNER_PERMS,
+            &PHASE2_MOVES,
+            |c, i| set_perm(&mut c.cp, i),
+            |c| perm_index(&c.cp),
+        );
+        let edges = move_table(
+            EDGE_PERMS,
+            &PHASE2_MOVES,
+            |c, i| set_perm(&mut c.ep[..8], i),
+            |c| perm_index(&c.ep[..8]),
+        );
+        let slice_perm = move_table(
+            SLICE_PERMS,
+            &PHASE2_MOVES,
+            |c, i| {
+                set_perm(&mut c.ep[8..], i);
+                c.ep[8..].iter_mut().for_each(|e| *e += 8);
+            },
+            |c| perm_index(&c.ep[8..]),
+        );
+
+        let twist_slice = prune_table(&twist, &slice, MOVES);
+        let flip_slice = prune_table(&flip, &slice, MOVES);
+        let corners_slice = prune_table(&corners, &slice_perm, PHASE2_MOVES.len());
+        let edges_slice = prune_table(&edges, &slice_perm, PHASE2_MOVES.len());
+
+        Self {
+            twist,
+            flip,
+            slice,
+            corners,
+            edges,
+            slice_perm,
+            twist_slice,
+            flip_slice,
+            corners_slice,
+            edges_slice,
+        }
+    }
+
+    fn get() -> &'static Self {
+        static TABLES: OnceLock<Tables> = OnceLock::new();
+        TABLES.get_or_init(Tables::new)
+    }
+}
+
+/// Whether `m` may follow `last`: never the same face twice, and opposite
+/// faces only in one order since they commute.
+pub(crate) fn allowed(m: usize, last: Option<usize>) -> bool {
+    match last {
+        Some(last) => {
+            let (face, last) = (m / 3, last / 3);
+            face != last && face + 3 != last
+        }
+        None => true,
+    }
+}
+
+struct Search<'a> {
+    tables: &'a Tables,
+    start: CubieCube,
+    max_length: usize,
+    moves: Vec<usize>,
+    phase1_length: usize,
+}
+
+impl<'a> Search<'a> {
+    fn phase1(&mut self, twist: usize, flip: usize, slice: usize, left: usize) -> bool {
+        let t = self.tables;
+        if left == 0 {
+            if twist != 0 || flip != 0 || slice != 0 {
+                return false;
+            }
+            // Ending on a phase two move means a shorter phase one was
+            // already tried.
+            if let Some(last) = self.moves.last() {
+                if PHASE2_MOVES.contains(last) {
+                    return false;
+                }
+            }
+            return self.start_phase2();
+        }
+
+        let estimate =
+            t.twist_slice[twist * SLICES + slice].max(t.flip_slice[flip * SLICES + slice]);
+        if estimate as usize > left {
+            return false;
+        }
+
+        for m in 0..MOVES {
+            if !allowed(m, self.moves.last().copied()) {
+                continue;
+            }
+            self.moves.push(m);
+            if self.phase1(
+                t.twist[twist][m] as usize,
+                t.flip[flip][m] as usize,
+                t.slice[slice][m] as usize,
+                left - 1,
+            ) {
+                return true;
+            }
+            self.moves.pop();
+        }
+
+        false
+    }
+
+    fn start_phase2(&mut self) -> bool {
+        let mut cubie = self.start.clone();
+        for m in self.moves.iter() {
+            for _ in 0..=m % 3 {
+                cubie = cubie.multiply(CubieCube::face_turn(m / 3));
+            }
+        }
+        let corners = perm_index(&cubie.cp);
+        let edges = perm_index(&cubie.ep[..8]);
+        let slice_perm = perm_index(&cubie.ep[8..]);
+
+        let limit = self.max_length.saturating_sub(self.moves.len()).min(18);
+        self.phase1_length = self.moves.len();
+        for depth in 0..=limit {
+            if self.phase2(corners, edges, slice_perm, depth) {
+                return true;
+            }
+            self.moves.truncate(self.phase1_length);
+        }
+
+        false
+    }
+
+    fn phase2(&mut self, corners: usize, edges: usize, slice_perm: usize, left: usize) -> bool {
+        let t = self.tables;
+        if left == 0 {
+            return corners == 0 && edges == 0 && slice_perm == 0;
+        }
+
+        let estimate = t.corners_slice[corners * SLICE_PERMS + slice_perm]
+            .max(t.edges_slice[edges * SLICE_PERMS + slice_perm]);
+        if estimate as usize > left {
+            return false;
+        }
+
+        for (i, m) in PHASE2_MOVES.iter().enumerate() {
+            // The first move may turn the same face as the last move of phase
+            // one, the two are merged afterwards.
+            let last = self.moves[self.phase1_length..].last().copied();
+            if !allowed(*m, last) {
+                continue;
+            }
+            self.moves.push(*m);
+            if self.phase2(
+                t.corners[corners][i] as usize,
+                t.edges[edges][i] as usize,
+                t.slice_perm[slice_perm][i] as usize,
+                left - 1,
+            ) {
+                return true;
+            }
+            self.moves.pop();
+        }
+
+        false
+    }
+}
+
+/// The face turns numbered like [`MOVES`].
+pub(crate) fn to_algorithm(moves: &[usize]) -> Algorithm {
+    Algorithm::new(
+        moves
+            .iter()
+            .map(|m| {
+                let side = Cube::FACELET_ORDER[m / 3].clone();
+                let ty =
+                    MoveType::from_quarter_turns((m % 3 + 1) as u8).unwrap_or(MoveType::Normal);
+                Move::new(side, ty)
+            })
+            .collect(),
+    )
+}
+
+/// Finds a solution of at most `max_length` moves, if there is one the
+/// two-phase search reaches.
+pub fn solve_within(cubie: &CubieCube, max_length: usize) -> Option<Algorithm> {
+    let tables = Tables::get();
+    let mut search = Search {
+        tables,
+        start: cubie.clone(),
+        max_length,
+        moves: Vec::new(),
+        phase1_length: 0,
+    };
+    let (twist, flip, slice) = (twist(cubie), flip(cubie), slice(cubie));
+
+    for depth in 0..=max_length.min(12) {
+        if search.phase1(twist, flip, slice, depth) {
+            return Some(to_algorithm(&search.moves).simplify());
+        }
+    }
+
+    None
+}
+
+/// Solves `cube`, usually in 23 moves or less.
+///
+/// ```
+/// # use cube::{algoritm::Algorithm, solver, Cube};
+/// let mut cube = Cube::default();
+/// cube.execute_algorithm(Algorithm::parse("R U R' U'").unwrap());
+///
+/// let solution = solver::solve(&cube).unwrap();
+/// cube.execute_algorithm(solution);
+/// assert!(cube.is_solved());
+/// ```
+pub fn solve(cube: &Cube) -> Result<Algorithm, StateError> {
+    let cubie = CubieCube::from_cube(cube)?;
+
+    let mut max_length = DEFAULT_MAX_LENGTH;
+    loop {
+        if let Some(solution) = solve_within(&cubie, max_length) {
+            return Ok(solution);
+        }
+        max_length += 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coordinates_round_trip() {
+        let mut cubie = CubieCube::default();
+        for value in [0, 1, 100, 494].iter() {
+            set_slice(&mut cubie, *value);
+            assert_eq!(slice(&cubie), *value);
+        }
+        for value in [0, 7, 2186].iter() {
+            set_twist(&mut cubie, *value);
+            assert_eq!(twist(&cubie), *value);
+        }
+        for value in [0, 5039, 40319].iter() {
+            set_perm(&mut cubie.cp, *value);
+            assert_eq!(perm_index(&cubie.cp), *value);
+        }
+    }
+
+    #[test]
+    fn solves_scrambles() {
+        let scrambles = [
+            "",
+            "R U R' U'",
+            "F R",
+            "D2 F2 U' B2 R2 B2 R2 L B' D' F D2 F' L' U R' F' D' L2 B2",
+            "L2 U2 F2 L2 D B2 D' R2 U2 B2 U' R' B' U F' D' R2 U' L U2 R'",
+        ];
+
+        for scramble in scrambles.iter() {
+            let mut cube = Cube::default();
+            cube.execute_algorithm(Algorithm::parse(scramble).unwrap());
+
+            let solution = solve(&cube).unwrap();
+            cube.execute_algorithm(solution.clone());
+
+            assert!(cube.is_solved(), "{} with {}", scramble, solution);
+            assert!(solution.moves.len() <= 25, "{}", solution);
+        }
+
+        let mut cube = Cube::default();
+        cube.execute_algorithm(Algorithm::parse("F R").unwrap());
+        assert_eq!(solve(&cube).unwrap().to_string(), "R' F'");
+    }
+}
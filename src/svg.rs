@@ -0,0 +1,233 @@
+//! SVG rendering of cube states, written by hand so no external crates are needed.
+
+use crate::{algoritm::Side, Colour, Cube, Face, Line};
+use std::fmt::Write;
+
+/// The fill used for every [`Colour`], as any valid SVG colour value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Palette {
+    pub white: String,
+    pub yellow: String,
+    pub green: String,
+    pub blue: String,
+    pub red: String,
+    pub orange: String,
+}
+
+impl Palette {
+    pub fn colour(&self, colour: Colour) -> &str {
+        match colour {
+            Colour::White => &self.white,
+            Colour::Yellow => &self.yellow,
+            Colour::Green => &self.green,
+            Colour::Blue => &self.blue,
+            Colour::Red => &self.red,
+            Colour::Orange => &self.orange,
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            white: "#ffffff".to_string(),
+            yellow: "#ffd500".to_string(),
+            green: "#009b48".to_string(),
+            blue: "#0046ad".to_string(),
+            red: "#b71234".to_string(),
+            orange: "#ff5800".to_string(),
+        }
+    }
+}
+
+/// Sizes are in SVG user units.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SvgOptions {
+    /// Width and height of a single sticker.
+    pub sticker_size: u32,
+    /// Space between two stickers of the same face.
+    pub gap: u32,
+    /// Space between two faces, also used as the margin around the image.
+    pub face_gap: u32,
+    /// Colour drawn behind the stickers, `None` leaves it transparent.
+    pub background: Option<String>,
+    pub palette: Palette,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            sticker_size: 30,
+            gap: 2,
+            face_gap: 6,
+            background: Some("#000000".to_string()),
+            palette: Palette::default(),
+        }
+    }
+}
+
+impl SvgOptions {
+    fn face_size(&self) -> u32 {
+        3 * self.sticker_size + 2 * self.gap
+    }
+}
+
+struct Canvas<'a> {
+    options: &'a SvgOptions,
+    body: String,
+}
+
+impl<'a> Canvas<'a> {
+    fn new(options: &'a SvgOptions) -> Self {
+        Self {
+            options,
+            body: String::new(),
+        }
+    }
+
+    fn rect(&mut self, x: u32, y: u32, width: u32, height: u32, colour: Colour) {
+        // Writing to a `String` can't fail.
+        let _ = writeln!(
+            self.body,
+            r#"  <rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+            x,
+            y,
+            width,
+            height,
+            self.options.palette.colour(colour)
+        );
+    }
+
+    fn face(&mut self, x: u32, y: u32, face: &Face) {
+        let pitch = self.options.sticker_size + self.options.gap;
+        let size = self.options.sticker_size;
+
+        for (i, colour) in face.stickers().iter().enumerate() {
+            let (row, col) = (i as u32 / 3, i as u32 % 3);
+            self.rect(x + col * pitch, y + row * pitch, size, size, *colour);
+        }
+    }
+
+    fn finish(self, width: u32, height: u32) -> String {
+        let mut out = String::new();
+
+        let _ = writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+            width, height
+        );
+        if let Some(background) = &self.options.background {
+            let _ = writeln!(
+                out,
+                r#"  <rect width="{}" height="{}" fill="{}"/>"#,
+                width, height, background
+            );
+        }
+        out.push_str(&self.body);
+        out.push_str("</svg>\n");
+
+        out
+    }
+}
+
+/// Renders the unfolded net, laid out like `Display for Cube`.
+///
+/// ```
+/// # use cube::{svg, Cube};
+/// let svg = svg::net(&Cube::default(), &svg::SvgOptions::default());
+/// assert!(svg.starts_with("<svg"));
+/// ```
+pub fn net(cube: &Cube, options: &SvgOptions) -> String {
+    let pitch = options.face_size() + options.face_gap;
+    let margin = options.face_gap;
+    let mut canvas = Canvas::new(options);
+
+    let layout = [
+        (Side::Up, 1, 0),
+        (Side::Left, 0, 1),
+        (Side::Front, 1, 1),
+        (Side::Right, 2, 1),
+        (Side::Back, 3, 1),
+        (Side::Down, 1, 2),
+    ];
+
+    for (side, col, row) in layout.iter() {
+        canvas.face(
+            margin + col * pitch,
+            margin + row * pitch,
+            cube.get_face(side.clone()),
+        );
+    }
+
+    canvas.finish(4 * pitch + margin, 3 * pitch + margin)
+}
+
+/// Renders the up face seen from above, with the upper stickers of the four
+/// side faces drawn as thin strips around it, as used for last layer diagrams.
+pub fn top_face(cube: &Cube, options: &SvgOptions) -> String {
+    let size = options.sticker_size;
+    let pitch = size + options.gap;
+    let depth = (size / 3).max(1);
+    let margin = options.face_gap;
+    // Where the up face starts, on both axes.
+    let start = margin + depth + options.gap;
+    let end = start + options.face_size() + options.gap;
+    let mut canvas = Canvas::new(options);
+
+    canvas.face(start, start, &cube.up);
+
+    // Upper lines are stored clockwise as seen from each side face, which
+    // runs right to left along the back and the right when seen from above.
+    let Line(b0, b1, b2) = cube.back.get_upper_line();
+    let Line(r0, r1, r2) = cube.right.get_upper_line();
+    let Line(l0, l1, l2) = cube.left.get_upper_line();
+    let Line(f0, f1, f2) = cube.front.get_upper_line();
+    let back = [b2, b1, b0];
+    let right = [r2, r1, r0];
+    let left = [l0, l1, l2];
+    let front = [f0, f1, f2];
+
+    for i in 0..3 {
+        let offset = start + i as u32 * pitch;
+        canvas.rect(offset, margin, size, depth, back[i]);
+        canvas.rect(offset, end, size, depth, front[i]);
+        canvas.rect(margin, offset, depth, size, left[i]);
+        canvas.rect(end, offset, depth, size, right[i]);
+    }
+
+    let total = end + depth + margin;
+    canvas.finish(total, total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algoritm::Algorithm;
+
+    #[test]
+    fn net_has_every_sticker() {
+        let svg = net(&Cube::default(), &SvgOptions::default());
+        let options = SvgOptions::default();
+
+        // One background plus 54 stickers.
+        assert_eq!(svg.matches("<rect").count(), 55);
+        assert_eq!(svg.matches(&options.palette.white).count(), 9);
+        assert_eq!(svg.matches(&options.palette.green).count(), 9);
+    }
+
+    #[test]
+    fn top_face_shows_side_stickers() {
+        let options = SvgOptions {
+            background: None,
+            ..SvgOptions::default()
+        };
+        let mut cube = Cube::default();
+        cube.execute_algorithm(Algorithm::parse("R").unwrap());
+
+        let svg = top_face(&cube, &options);
+
+        assert_eq!(svg.matches("<rect").count(), 9 + 12);
+        // R brings three green stickers up, and the front strip loses one.
+        assert_eq!(svg.matches(&options.palette.green).count(), 2 + 3);
+    }
+}
@@ -0,0 +1,158 @@
+//! Raw terminal input through termios, without external crates.
+
+use std::io::{self, Read};
+
+#[cfg(target_os = "linux")]
+mod sys {
+    pub type Flag = u32;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct Termios {
+        pub iflag: Flag,
+        pub oflag: Flag,
+        pub cflag: Flag,
+        pub lflag: Flag,
+        pub line: u8,
+        pub cc: [u8; 32],
+        pub ispeed: u32,
+        pub ospeed: u32,
+    }
+
+    pub const ISIG: Flag = 0o1;
+    pub const ICANON: Flag = 0o2;
+    pub const ECHO: Flag = 0o10;
+    pub const ICRNL: Flag = 0o400;
+    pub const IXON: Flag = 0o2000;
+    pub const VTIME: usize = 5;
+    pub const VMIN: usize = 6;
+}
+
+#[cfg(target_os = "macos")]
+mod sys {
+    pub type Flag = u64;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct Termios {
+        pub iflag: Flag,
+        pub oflag: Flag,
+        pub cflag: Flag,
+        pub lflag: Flag,
+        pub cc: [u8; 20],
+        pub ispeed: u64,
+        pub ospeed: u64,
+    }
+
+    pub const ISIG: Flag = 0x80;
+    pub const ICANON: Flag = 0x100;
+    pub const ECHO: Flag = 0x8;
+    pub const ICRNL: Flag = 0x100;
+    pub const IXON: Flag = 0x200;
+    pub const VTIME: usize = 17;
+    pub const VMIN: usize = 16;
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+mod imp {
+    use super::sys::*;
+    use std::io;
+
+    const STDIN: i32 = 0;
+    const TCSANOW: i32 = 0;
+
+    extern "C" {
+        fn tcgetattr(fd: i32, termios: *mut Termios) -> i32;
+        fn tcsetattr(fd: i32, action: i32, termios: *const Termios) -> i32;
+    }
+
+    pub struct Saved(Termios);
+
+    pub fn enable(tenths: u8) -> io::Result<Saved> {
+        // SAFETY: `Termios` matches the C layout on this platform and
+        // tcgetattr fills it in completely when it succeeds.
+        let mut termios: Termios = unsafe { std::mem::zeroed() };
+        if unsafe { tcgetattr(STDIN, &mut termios) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let saved = Saved(termios);
+
+        termios.lflag &= !(ICANON | ECHO | ISIG);
+        termios.iflag &= !(IXON | ICRNL);
+        termios.cc[VMIN] = 0;
+        termios.cc[VTIME] = tenths;
+        set(&termios)?;
+
+        Ok(saved)
+    }
+
+    pub fn restore(saved: &Saved) -> io::Result<()> {
+        set(&saved.0)
+    }
+
+    fn set(termios: &Termios) -> io::Result<()> {
+        // SAFETY: the pointer is to a valid, initialised `Termios`.
+        if unsafe { tcsetattr(STDIN, TCSANOW, termios) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+mod imp {
+    use std::io;
+
+    pub struct Saved;
+
+    pub fn enable(_tenths: u8) -> io::Result<Saved> {
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            "raw terminal mode isn't supported on this platform",
+        ))
+    }
+
+    pub fn restore(_saved: &Saved) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Keeps the terminal in raw mode until dropped: keys arrive one at a time
+/// without echo, and Ctrl-C arrives as a key instead of a signal.
+pub struct RawMode {
+    saved: imp::Saved,
+}
+
+impl RawMode {
+    /// Reads give up after `tenths` tenths of a second without a key, so
+    /// callers can redraw in between.
+    pub fn enable(tenths: u8) -> io::Result<Self> {
+        Ok(Self {
+            saved: imp::enable(tenths)?,
+        })
+    }
+
+    /// The next key, or `None` when none was pressed in time.
+    pub fn read_key<R: Read>(&self, input: &mut R) -> io::Result<Option<u8>> {
+        let mut key = [0];
+        match input.read(&mut key)? {
+            0 => Ok(None),
+            _ => Ok(Some(key[0])),
+        }
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        // There's no one left to report a failure to.
+        let _ = imp::restore(&self.saved);
+    }
+}
+
+pub const CLEAR: &str = "\x1b[2J\x1b[H";
+pub const ALTERNATE_SCREEN: &str = "\x1b[?1049h\x1b[?25l";
+pub const MAIN_SCREEN: &str = "\x1b[?25h\x1b[?1049l";
+
+pub const CTRL_C: u8 = 3;
+pub const ESCAPE: u8 = 27;
+pub const BACKSPACE: u8 = 127;
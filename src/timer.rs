@@ -0,0 +1,180 @@
+//! A speedcubing timer with WCA inspection.
+
+use crate::{
+    algoritm::Algorithm,
+    ansi,
+    scramble::{self, Rng},
+    session::{self, Penalty, Solve, INSPECTION},
+    term::{self, RawMode},
+    Cube,
+};
+use std::{
+    io::{self, Read, Write},
+    time::Instant,
+};
+
+const HELP: &str = "space: start inspection, start and stop the solve  esc: quit";
+
+/// How many of the latest solves are listed under the timer.
+const SHOWN: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    Ready,
+    Inspecting(Instant),
+    Solving { start: Instant, penalty: Penalty },
+}
+
+/// The timer's state, driven by `press` so it can be tested without a
+/// terminal or a clock.
+pub struct Timer {
+    rng: Rng,
+    scramble: Algorithm,
+    state: State,
+    solves: Vec<Solve>,
+}
+
+impl Timer {
+    pub fn new(mut rng: Rng) -> Self {
+        Self {
+            scramble: scramble::scramble(&mut rng),
+            rng,
+            state: State::Ready,
+            solves: Vec::new(),
+        }
+    }
+
+    /// The scramble for the next solve.
+    pub fn scramble(&self) -> &Algorithm {
+        &self.scramble
+    }
+
+    pub fn solves(&self) -> &[Solve] {
+        &self.solves
+    }
+
+    /// Handles the spacebar: starts inspection, then the solve, then stops
+    /// it, returning the recorded solve.
+    pub fn press(&mut self, now: Instant) -> Option<&Solve> {
+        match self.state {
+            State::Ready => self.state = State::Inspecting(now),
+            State::Inspecting(start) => {
+                self.state = State::Solving {
+                    start: now,
+                    penalty: Penalty::from_inspection(now - start),
+                }
+            }
+            State::Solving { start, penalty } => {
+                let scramble = scramble::scramble(&mut self.rng);
+                self.solves.push(Solve::new(
+                    std::mem::replace(&mut self.scramble, scramble),
+                    now - start,
+                    penalty,
+                ));
+                self.state = State::Ready;
+                return self.solves.last();
+            }
+        }
+        None
+    }
+
+    pub fn frame(&self, now: Instant, colour: bool) -> String {
+        let mut cube = Cube::default();
+        cube.execute_algorithm(self.scramble.clone());
+        let cube = if colour {
+            ansi::net(&cube)
+        } else {
+            cube.to_string()
+        };
+
+        let status = match self.state {
+            State::Ready => "ready".to_string(),
+            State::Inspecting(start) => {
+                let inspection = now - start;
+                match Penalty::from_inspection(inspection) {
+                    Penalty::None => {
+                        let left = INSPECTION - inspection;
+                        format!("inspecting {}", left.as_secs() + 1)
+                    }
+                    Penalty::PlusTwo => "inspecting +2".to_string(),
+                    Penalty::Dnf => "inspecting DNF".to_string(),
+                }
+            }
+            State::Solving { start, .. } => session::format_time(now - start),
+        };
+
+        let mut latest = String::new();
+        for (i, solve) in self.solves.iter().enumerate().rev().take(SHOWN) {
+            latest += &format!("{:>4}. {}\n", i + 1, solve);
+        }
+
+        format!(
+            "{}\n\n{}\n\n{}\n\n{}\n{}",
+            self.scramble, cube, status, latest, HELP
+        )
+    }
+}
+
+/// Runs the timer until the user quits, returning the recorded solves.
+/// `input` has to be the terminal's stdin, since that's what gets switched
+/// to raw mode. `recorded` is called with each solve as it's done.
+pub fn run<R, W, F>(mut input: R, mut out: W, mut recorded: F) -> io::Result<Vec<Solve>>
+where
+    R: Read,
+    W: Write,
+    F: FnMut(&Solve) -> io::Result<()>,
+{
+    let raw = RawMode::enable(1)?;
+    let mut timer = Timer::new(Rng::from_time());
+
+    write!(out, "{}", term::ALTERNATE_SCREEN)?;
+    let result = (|| loop {
+        let frame = timer.frame(Instant::now(), true);
+        write!(out, "{}{}", term::CLEAR, frame)?;
+        out.flush()?;
+
+        match raw.read_key(&mut input)? {
+            Some(b' ') => {
+                if let Some(solve) = timer.press(Instant::now()) {
+                    recorded(solve)?;
+                }
+            }
+            Some(term::ESCAPE) | Some(term::CTRL_C) => return Ok(()),
+            _ => {}
+        }
+    })();
+    write!(out, "{}", term::MAIN_SCREEN)?;
+    out.flush()?;
+
+    result.map(|()| timer.solves)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn records_solves_with_penalties() {
+        let mut timer = Timer::new(Rng::new(7));
+        let scramble = timer.scramble().clone();
+        let start = Instant::now();
+        let at = |secs: f64| start + Duration::from_secs_f64(secs);
+
+        assert!(timer.press(at(0.0)).is_none());
+        assert!(timer.frame(at(3.5), false).contains("inspecting 12"));
+        assert!(timer.press(at(16.0)).is_none());
+        let solve = timer.press(at(26.0)).unwrap().clone();
+
+        assert_eq!(solve.scramble, scramble);
+        assert_eq!(solve.time, Duration::from_secs(10));
+        assert_eq!(solve.penalty, Penalty::PlusTwo);
+        assert_ne!(timer.scramble(), &scramble);
+
+        timer.press(at(30.0));
+        timer.press(at(48.0));
+        timer.press(at(50.0));
+        assert_eq!(timer.solves()[1].penalty, Penalty::Dnf);
+        assert!(timer.frame(at(51.0), false).contains("2. DNF(2.00)"));
+    }
+}
@@ -0,0 +1,766 @@
+//! Drilling last layer cases: each drill scrambles into a case with a random
+//! AUF before it, times the solve and checks the moves it was solved with.
+//!
+//! OLL, PLL and COLL cases come from the [`Database`]. ZBLL cases aren't
+//! there, so those drills are random last layers with oriented edges, named
+//! after their COLL case and how the edges are permuted once the corners are
+//! solved, like `T3/5`.
+//!
+//! With a [`Schedule`], the next case is the one due for review, and every
+//! attempt is graded into it.
+
+use crate::{
+    algoritm::{Algorithm, Move, MoveType, Side},
+    ansi,
+    cubie::{parity, CubieCube},
+    database::{Database, Entry, Set},
+    last_layer,
+    schedule::Schedule,
+    scramble::Rng,
+    session, solver,
+    term::{self, RawMode},
+    tui, Cube,
+};
+use std::{
+    fmt::{self, Display, Formatter},
+    io::{self, Read, Write},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+const HELP: &str = "\
+space: start and stop the timer  solve with the keyboard, or type the moves
+when it's stopped  esc: quit";
+
+/// Correct attempts this fast are graded perfect, and up to twice this good.
+const FAST: Duration = Duration::from_secs(2);
+
+/// How many random ZBLLs to go through looking for the one due, and then
+/// for the one asked for, since some come up rarely.
+const ZBLL_TRIES: usize = 5000;
+
+/// The PLLs that only move edges, to mix into COLL drills.
+const EDGE_PLLS: [&str; 4] = ["Ua", "Ub", "H", "Z"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Training {
+    Oll,
+    Pll,
+    Coll,
+    Zbll,
+}
+
+impl Training {
+    pub fn parse(source: &str) -> Option<Self> {
+        match source {
+            "oll" => Some(Self::Oll),
+            "pll" => Some(Self::Pll),
+            "coll" => Some(Self::Coll),
+            "zbll" => Some(Self::Zbll),
+            _ => None,
+        }
+    }
+
+    /// What has to be solved for a drill to be done.
+    fn goal(&self) -> Set {
+        match self {
+            Self::Oll => Set::Oll,
+            Self::Pll | Self::Zbll => Set::Pll,
+            Self::Coll => Set::Coll,
+        }
+    }
+}
+
+impl Display for Training {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Oll => write!(f, "OLL"),
+            Self::Pll => write!(f, "PLL"),
+            Self::Coll => write!(f, "COLL"),
+            Self::Zbll => write!(f, "ZBLL"),
+        }
+    }
+}
+
+/// One case to solve.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Drill {
+    pub case: String,
+    pub scramble: Algorithm,
+    /// The cube after the scramble.
+    pub state: Cube,
+    /// A way to solve it, to show after a miss.
+    pub solution: Algorithm,
+}
+
+/// How a case has gone so far.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CaseStats {
+    pub case: String,
+    pub attempts: usize,
+    pub correct: usize,
+    /// The time of the correct attempts together.
+    pub time: Duration,
+}
+
+impl CaseStats {
+    /// The share of correct attempts, from 0 to 1.
+    pub fn accuracy(&self) -> f64 {
+        self.correct as f64 / self.attempts.max(1) as f64
+    }
+
+    /// The mean time of the correct attempts.
+    pub fn average(&self) -> Option<Duration> {
+        match self.correct {
+            0 => None,
+            correct => Some(self.time / correct as u32),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Phase {
+    Ready,
+    Solving {
+        start: Instant,
+        cube: Cube,
+        moves: Vec<Move>,
+    },
+    Typing {
+        time: Duration,
+        text: String,
+        unreadable: bool,
+    },
+    Done {
+        time: Duration,
+        correct: bool,
+    },
+}
+
+/// The trainer's state, driven by `key` so it can be tested without a
+/// terminal or a clock.
+pub struct Trainer {
+    training: Training,
+    /// The case asked for, or any.
+    only: Option<String>,
+    rng: Rng,
+    drill: Drill,
+    phase: Phase,
+    stats: Vec<CaseStats>,
+    schedule: Option<Schedule>,
+    /// A moment as an instant and in seconds since the Unix epoch, to date
+    /// reviews.
+    clock: (Instant, u64),
+}
+
+impl Trainer {
+    /// A trainer for `training`, drilling only `case` if it's given. `None`
+    /// if there's no such case.
+    pub fn new(training: Training, case: Option<&str>, rng: Rng) -> Option<Self> {
+        if let Some(case) = case {
+            let coll = match (training, case.split_once('/')) {
+                (Training::Zbll, Some((coll, edges))) => {
+                    let edges: usize = edges.parse().ok()?;
+                    if !(1..=12).contains(&edges) {
+                        return None;
+                    }
+                    coll
+                }
+                _ => case,
+            };
+            Database::builtin().get(training_set(training), coll)?;
+        }
+
+        let mut trainer = Self {
+            training,
+            only: case.map(str::to_string),
+            rng,
+            drill: Drill {
+                case: String::new(),
+                scramble: Algorithm::new(Vec::new()),
+                state: Cube::default(),
+                solution: Algorithm::new(Vec::new()),
+            },
+            phase: Phase::Ready,
+            stats: Vec::new(),
+            schedule: None,
+            clock: (
+                Instant::now(),
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |since| since.as_secs()),
+            ),
+        };
+        trainer.drill = trainer.next_drill();
+        Some(trainer)
+    }
+
+    /// Picks cases by `schedule` from now on, taking it to be `now` seconds
+    /// since the Unix epoch.
+    pub fn with_schedule(mut self, schedule: Schedule, now: u64) -> Self {
+        self.schedule = Some(schedule);
+        self.clock = (Instant::now(), now);
+        self.drill = self.next_drill();
+        self
+    }
+
+    pub fn schedule(&self) -> Option<&Schedule> {
+        self.schedule.as_ref()
+    }
+
+    pub fn drill(&self) -> &Drill {
+        &self.drill
+    }
+
+    /// Every case drilled so far, in the order they first came up.
+    pub fn stats(&self) -> &[CaseStats] {
+        &self.stats
+    }
+
+    /// Whether `solution` solves the current drill.
+    pub fn solves(&self, solution: &Algorithm) -> bool {
+        let mut cube = self.drill.state.clone();
+        cube.execute_algorithm(solution.clone());
+        cube.reorient();
+        last_layer::is_done(&cube, self.training.goal())
+    }
+
+    fn next_drill(&mut self) -> Drill {
+        let target = self.scheduled_case().or_else(|| self.only.clone());
+        let mut tries = 0;
+        loop {
+            let (case, state, solution) = match self.training {
+                Training::Zbll => match self.zbll_case() {
+                    Some(drill) => drill,
+                    None => continue,
+                },
+                training => self.database_case(training, target.as_deref()),
+            };
+
+            tries += 1;
+            let wanted = match (&target, &self.only) {
+                (Some(target), _) if tries < ZBLL_TRIES => is_case(&case, target),
+                (_, Some(only)) if tries < 2 * ZBLL_TRIES => is_case(&case, only),
+                _ => true,
+            };
+            if !wanted {
+                continue;
+            }
+
+            let scramble = solver::solve(&state)
+                .expect("drills are solvable")
+                .inverse();
+            return Drill {
+                case,
+                scramble,
+                state,
+                solution,
+            };
+        }
+    }
+
+    /// How cases are called in the schedule, like `PLL T`.
+    fn card(&self, case: &str) -> String {
+        format!("{} {}", self.training, case)
+    }
+
+    fn timestamp(&self, now: Instant) -> u64 {
+        self.clock.1 + now.saturating_duration_since(self.clock.0).as_secs()
+    }
+
+    /// The case the schedule wants next, if there is one. New ZBLLs can't
+    /// be listed, so those only come back once they're due.
+    fn scheduled_case(&mut self) -> Option<String> {
+        let schedule = self.schedule.as_ref()?;
+        let now = self.timestamp(Instant::now());
+
+        let mut cards: Vec<String> = match self.training {
+            Training::Zbll => schedule
+                .cards
+                .iter()
+                .filter(|card| card.due() <= now)
+                .map(|card| card.case.clone())
+                .collect(),
+            training => Database::builtin()
+                .set(training_set(training))
+                .map(|entry| self.card(&entry.name))
+                .collect(),
+        };
+        let prefix = self.card("");
+        cards.retain(|card| {
+            card.strip_prefix(&prefix)
+                .is_some_and(|case| self.only.as_ref().is_none_or(|only| is_case(case, only)))
+        });
+        // So new cases come in a random order.
+        self.rng.shuffle(&mut cards);
+
+        let cards: Vec<&str> = cards.iter().map(String::as_str).collect();
+        let schedule = self.schedule.as_ref()?;
+        schedule
+            .next(&cards, now)
+            .map(|card| card[prefix.len()..].to_string())
+    }
+
+    fn random_auf(&mut self) -> Algorithm {
+        let auf =
+            MoveType::from_quarter_turns(self.rng.below(4) as u8).map(|ty| Move::new(Side::Up, ty));
+        Algorithm::new(auf.into_iter().collect())
+    }
+
+    fn database_case(
+        &mut self,
+        training: Training,
+        target: Option<&str>,
+    ) -> (String, Cube, Algorithm) {
+        let database = Database::builtin();
+        let set = training_set(training);
+        let cases: Vec<&Entry> = database
+            .set(set)
+            .filter(|entry| target.is_none_or(|target| entry.name == target))
+            .collect();
+        let case = cases[self.rng.below(cases.len())];
+
+        // Whatever the case leaves alone is random too, so it can't be
+        // told by anything else.
+        let others: Vec<&Entry> = match training {
+            Training::Oll => database.set(Set::Pll).collect(),
+            Training::Coll => EDGE_PLLS
+                .iter()
+                .filter_map(|name| database.get(Set::Pll, name))
+                .collect(),
+            _ => Vec::new(),
+        };
+        let mut state = Cube::default();
+        state.execute_algorithm(self.random_auf());
+        if !others.is_empty() {
+            let other = others[self.rng.below(others.len())];
+            state.execute_algorithm(other.algorithm.inverse());
+        }
+        state.execute_algorithm(case.algorithm.inverse());
+        state.execute_algorithm(self.random_auf());
+        state.reorient();
+
+        let recognition =
+            last_layer::recognise_as(&state, set).expect("database cases are recognised");
+        (case.name.clone(), state, recognition.solution())
+    }
+
+    /// A random last layer with oriented edges, `None` if it came out solved.
+    fn zbll_case(&mut self) -> Option<(String, Cube, Algorithm)> {
+        let mut pieces = CubieCube::default();
+        self.rng.shuffle(&mut pieces.cp[..4]);
+        self.rng.shuffle(&mut pieces.ep[..4]);
+        if parity(&pieces.cp) != parity(&pieces.ep) {
+            pieces.ep.swap(0, 1);
+        }
+        for i in 0..3 {
+            pieces.co[i] = self.rng.below(3) as u8;
+        }
+        pieces.co[3] = (3 - pieces.co.iter().sum::<u8>() % 3) % 3;
+        let state = pieces.to_cube();
+
+        let mut cube = state.clone();
+        let mut solution = Vec::new();
+        let coll = last_layer::recognise_as(&cube, Set::Coll).ok();
+        if let Some(coll) = &coll {
+            cube.execute_algorithm(coll.solution());
+            cube.reorient();
+            solution.extend(coll.solution().moves);
+        }
+        let pll = last_layer::recognise(&cube).ok()?;
+        if let Some(pll) = &pll {
+            solution.extend(pll.solution().moves);
+        }
+
+        let case = match (coll, pll) {
+            (Some(coll), _) => format!("{}/{}", coll.case.name, edge_permutation(&cube)),
+            (None, Some(pll)) => pll.case.name.clone(),
+            (None, None) => return None,
+        };
+        Some((case, state, Algorithm::new(solution)))
+    }
+
+    /// Checks and records the attempt, `None` if the solution wasn't given.
+    fn finish(&mut self, solution: Option<&Algorithm>, time: Duration, now: Instant) {
+        let correct = solution.is_some_and(|solution| self.solves(solution));
+
+        let quality = match (solution, correct) {
+            (None, _) => 0,
+            (Some(_), false) => 1,
+            _ if time <= FAST => 5,
+            _ if time <= 2 * FAST => 4,
+            _ => 3,
+        };
+        let (card, timestamp) = (self.card(&self.drill.case), self.timestamp(now));
+        if let Some(schedule) = &mut self.schedule {
+            schedule.review(&card, quality, timestamp);
+        }
+
+        let case = &self.drill.case;
+        let index = match self.stats.iter().position(|stats| stats.case == *case) {
+            Some(index) => index,
+            None => {
+                self.stats.push(CaseStats {
+                    case: case.clone(),
+                    attempts: 0,
+                    correct: 0,
+                    time: Duration::from_secs(0),
+                });
+                self.stats.len() - 1
+            }
+        };
+        let stats = &mut self.stats[index];
+        stats.attempts += 1;
+        if correct {
+            stats.correct += 1;
+            stats.time += time;
+        }
+
+        self.phase = Phase::Done { time, correct };
+    }
+
+    /// Handles a key, returning `false` when it's time to quit.
+    pub fn key(&mut self, key: u8, now: Instant) -> bool {
+        if key == term::ESCAPE || key == term::CTRL_C {
+            return false;
+        }
+
+        match &mut self.phase {
+            Phase::Ready => {
+                if key == b' ' {
+                    self.phase = Phase::Solving {
+                        start: now,
+                        cube: self.drill.state.clone(),
+                        moves: Vec::new(),
+                    };
+                }
+            }
+            Phase::Solving { start, cube, moves } => {
+                let time = now - *start;
+                if key == b' ' {
+                    if moves.is_empty() {
+                        self.phase = Phase::Typing {
+                            time,
+                            text: String::new(),
+                            unreadable: false,
+                        };
+                    } else {
+                        let solution = Algorithm::new(moves.clone());
+                        self.finish(Some(&solution), time, now);
+                    }
+                } else if let Some(mv) = tui::key_move(key) {
+                    moves.push(mv.clone());
+                    cube.execute_move(mv);
+
+                    let mut oriented = cube.clone();
+                    oriented.reorient();
+                    if last_layer::is_done(&oriented, self.training.goal()) {
+                        let solution = Algorithm::new(moves.clone());
+                        self.finish(Some(&solution), time, now);
+                    }
+                }
+            }
+            Phase::Typing {
+                time,
+                text,
+                unreadable,
+            } => match key {
+                b'\r' | b'\n' => {
+                    let time = *time;
+                    if text.trim().is_empty() {
+                        self.finish(None, time, now);
+                    } else {
+                        match Algorithm::parse(text) {
+                            Some(solution) => self.finish(Some(&solution), time, now),
+                            None => *unreadable = true,
+                        }
+                    }
+                }
+                term::BACKSPACE => {
+                    text.pop();
+                }
+                b' '..=b'~' => {
+                    text.push(key as char);
+                    *unreadable = false;
+                }
+                _ => {}
+            },
+            Phase::Done { .. } => {
+                if key == b' ' {
+                    self.drill = self.next_drill();
+                    self.phase = Phase::Ready;
+                }
+            }
+        }
+        true
+    }
+
+    pub fn frame(&self, now: Instant, colour: bool) -> String {
+        let cube = match &self.phase {
+            Phase::Solving { cube, .. } => cube,
+            _ => &self.drill.state,
+        };
+        let cube = if colour {
+            ansi::net(cube)
+        } else {
+            cube.to_string()
+        };
+
+        let status = match &self.phase {
+            Phase::Ready => "ready".to_string(),
+            Phase::Solving { start, moves, .. } => format!(
+                "{}  {}",
+                session::format_time(now - *start),
+                Algorithm::new(moves.clone())
+            ),
+            Phase::Typing {
+                time,
+                text,
+                unreadable,
+            } => format!(
+                "{}  solved with: {}_{}",
+                session::format_time(*time),
+                text,
+                if *unreadable {
+                    "  (couldn't read that)"
+                } else {
+                    ""
+                }
+            ),
+            Phase::Done { time, correct } => {
+                let verdict = if *correct {
+                    format!("right in {}", session::format_time(*time))
+                } else {
+                    format!("that doesn't solve it, try {}", self.drill.solution)
+                };
+                let mut stats = self
+                    .stats
+                    .iter()
+                    .find(|stats| stats.case == self.drill.case)
+                    .map_or(String::new(), |stats| {
+                        format!("\n{} of {} right", stats.correct, stats.attempts)
+                    });
+                let card = self.card(&self.drill.case);
+                if let Some(card) = self.schedule().and_then(|schedule| schedule.get(&card)) {
+                    stats += &match card.interval {
+                        1 => ", again tomorrow".to_string(),
+                        days => format!(", again in {} days", days),
+                    };
+                }
+                format!(
+                    "{} {}: {}{}",
+                    self.training, self.drill.case, verdict, stats
+                )
+            }
+        };
+
+        format!(
+            "{} trainer\n{}\n\n{}\n\n{}\n\n{}",
+            self.training, self.drill.scramble, cube, status, HELP
+        )
+    }
+
+    /// A table of every case with its attempts, accuracy and average time.
+    pub fn report(&self) -> String {
+        let width = self
+            .stats
+            .iter()
+            .map(|stats| stats.case.len())
+            .max()
+            .unwrap_or(0)
+            .max("case".len());
+
+        let mut report = format!("{:<width$}  tries  right  average\n", "case", width = width);
+        for stats in &self.stats {
+            report += &format!(
+                "{:<width$}  {:>5}  {:>4.0}%  {:>7}\n",
+                stats.case,
+                stats.attempts,
+                stats.accuracy() * 100.0,
+                stats
+                    .average()
+                    .map_or("-".to_string(), session::format_time),
+                width = width
+            );
+        }
+        report
+    }
+}
+
+/// The database set cases of `training` are named from.
+fn training_set(training: Training) -> Set {
+    match training {
+        Training::Oll => Set::Oll,
+        Training::Pll => Set::Pll,
+        Training::Coll | Training::Zbll => Set::Coll,
+    }
+}
+
+/// Whether `case` is `wanted`, or one of its ZBLLs if that's a COLL case.
+fn is_case(case: &str, wanted: &str) -> bool {
+    case == wanted
+        || case
+            .strip_prefix(wanted)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Which of the 12 ways the top edges can be permuted the cube is in once
+/// its corners are turned into place, counting from 1.
+fn edge_permutation(cube: &Cube) -> usize {
+    let mut pieces = CubieCube::from_cube(cube).expect("drills are solvable");
+    while pieces.cp[..4] != [0, 1, 2, 3] {
+        pieces = pieces.multiply(CubieCube::face_turn(0));
+    }
+
+    let mut even = Vec::new();
+    for a in 0..4u8 {
+        for b in 0..4u8 {
+            for c in 0..4u8 {
+                for d in 0..4u8 {
+                    let perm = [a, b, c, d];
+                    let distinct = (0..4).all(|i| (0..i).all(|j| perm[i] != perm[j]));
+                    if distinct && !parity(&perm) {
+                        even.push(perm);
+                    }
+                }
+            }
+        }
+    }
+    even.iter()
+        .position(|perm| *perm == pieces.ep[..4])
+        .map_or(0, |i| i + 1)
+}
+
+/// Runs the trainer until the user quits. `input` has to be the terminal's
+/// stdin, since that's what gets switched to raw mode.
+pub fn run<R: Read, W: Write>(mut input: R, mut out: W, trainer: &mut Trainer) -> io::Result<()> {
+    let raw = RawMode::enable(1)?;
+
+    write!(out, "{}", term::ALTERNATE_SCREEN)?;
+    let result = (|| loop {
+        write!(
+            out,
+            "{}{}",
+            term::CLEAR,
+            trainer.frame(Instant::now(), true)
+        )?;
+        out.flush()?;
+
+        if let Some(key) = raw.read_key(&mut input)? {
+            if !trainer.key(key, Instant::now()) {
+                return Ok(());
+            }
+        }
+    })();
+    write!(out, "{}", term::MAIN_SCREEN)?;
+    out.flush()?;
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drills_set_up_their_case() {
+        for training in [Training::Oll, Training::Pll, Training::Coll, Training::Zbll].iter() {
+            let trainer = Trainer::new(*training, None, Rng::new(3)).unwrap();
+            let drill = trainer.drill();
+
+            let mut cube = Cube::default();
+            cube.execute_algorithm(drill.scramble.clone());
+            assert_eq!(cube, drill.state, "{}", training);
+            assert!(
+                trainer.solves(&drill.solution),
+                "{} {}",
+                training,
+                drill.case
+            );
+            assert!(!trainer.solves(&Algorithm::new(Vec::new())));
+        }
+
+        let trainer = Trainer::new(Training::Zbll, Some("T3"), Rng::new(5)).unwrap();
+        assert!(trainer.drill().case.starts_with("T3/"));
+        let trainer = Trainer::new(Training::Zbll, Some("T3/12"), Rng::new(5)).unwrap();
+        assert_eq!(trainer.drill().case, "T3/12");
+        for case in ["T3/0", "T3/13", "T3/x", "T3/"].iter() {
+            assert!(Trainer::new(Training::Zbll, Some(case), Rng::new(5)).is_none());
+        }
+        assert!(Trainer::new(Training::Pll, Some("X"), Rng::new(5)).is_none());
+    }
+
+    #[test]
+    fn keys_time_and_check_attempts() {
+        let mut trainer = Trainer::new(Training::Pll, Some("T"), Rng::new(9)).unwrap();
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        trainer.key(b' ', at(0));
+        trainer.key(b' ', at(2));
+        for key in "R Q".bytes() {
+            trainer.key(key, at(3));
+        }
+        trainer.key(b'\r', at(4));
+        assert!(trainer.frame(at(4), false).contains("couldn't read that"));
+        for _ in 0..3 {
+            trainer.key(term::BACKSPACE, at(4));
+        }
+        for key in trainer.drill().solution.to_string().bytes() {
+            trainer.key(key, at(5));
+        }
+        trainer.key(b'\r', at(6));
+        assert!(trainer.frame(at(6), false).contains("PLL T: right in 2.00"));
+
+        // Solving with the keyboard, but only turning R.
+        trainer.key(b' ', at(7));
+        trainer.key(b' ', at(8));
+        trainer.key(b'i', at(9));
+        trainer.key(b' ', at(10));
+        assert!(trainer
+            .frame(at(10), false)
+            .contains("that doesn't solve it"));
+
+        let stats = &trainer.stats()[0];
+        assert_eq!((stats.attempts, stats.correct), (2, 1));
+        assert_eq!(stats.accuracy(), 0.5);
+        assert_eq!(stats.average(), Some(Duration::from_secs(2)));
+        assert_eq!(
+            trainer.report(),
+            "case  tries  right  average\nT         2    50%     2.00\n"
+        );
+        assert!(!trainer.key(term::ESCAPE, at(11)));
+    }
+
+    #[test]
+    fn drills_what_is_due() {
+        let now = 1_792_418_592;
+        let mut schedule = Schedule::default();
+        schedule.review("PLL Y", 4, now - 3 * crate::schedule::DAY);
+        for case in Database::builtin().set(Set::Pll) {
+            if case.name != "Y" {
+                schedule.review(&format!("PLL {}", case.name), 4, now);
+            }
+        }
+
+        let mut trainer = Trainer::new(Training::Pll, None, Rng::new(4))
+            .unwrap()
+            .with_schedule(schedule, now);
+        assert_eq!(trainer.drill().case, "Y");
+
+        let start = Instant::now();
+        trainer.key(b' ', start);
+        trainer.key(b' ', start + Duration::from_secs(1));
+        for key in trainer.drill().solution.to_string().bytes() {
+            trainer.key(key, start + Duration::from_secs(2));
+        }
+        trainer.key(b'\r', start + Duration::from_secs(3));
+        assert!(trainer
+            .frame(start, false)
+            .contains("1 of 1 right, again in 6 days"));
+
+        let card = trainer.schedule().unwrap().get("PLL Y").unwrap();
+        assert_eq!((card.interval, card.repetitions), (6, 2));
+        assert!((card.ease - 2.6).abs() < 1e-9);
+        assert!(card.last_review >= now);
+    }
+}
@@ -0,0 +1,232 @@
+//! A full screen cube simulator turned from the keyboard.
+
+use crate::{
+    algoritm::{Move, MoveType, Side},
+    ansi,
+    iso::{self, IsoOptions},
+    scramble::{self, Rng},
+    term::{self, RawMode},
+    Cube,
+};
+use std::{
+    io::{self, Read, Write},
+    time::{Duration, Instant},
+};
+
+/// The usual keyboard cubing layout, where the right hand turns R with I
+/// and K and the index fingers turn U with J and F.
+pub fn key_move(key: u8) -> Option<Move> {
+    use MoveType::*;
+    use Side::*;
+
+    let (side, ty) = match key.to_ascii_lowercase() {
+        b'i' => (Right, Normal),
+        b'k' => (Right, Prime),
+        b'j' => (Up, Normal),
+        b'f' => (Up, Prime),
+        b'h' => (Front, Normal),
+        b'g' => (Front, Prime),
+        b'd' => (Left, Normal),
+        b'e' => (Left, Prime),
+        b's' => (Down, Normal),
+        b'l' => (Down, Prime),
+        b'w' => (Back, Normal),
+        b'o' => (Back, Prime),
+        _ => return None,
+    };
+
+    Some(Move::new(side, ty))
+}
+
+const HELP: &str = "\
+I/K R R'  J/F U U'  H/G F F'  D/E L L'  S/L D D'  W/O B B'
+space scramble  backspace reset  tab switch view  esc quit";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum View {
+    Net,
+    Iso,
+}
+
+/// The simulator's state, separate from the terminal so it can be tested.
+pub struct Simulator {
+    cube: Cube,
+    view: View,
+    moves: usize,
+    /// Set when the first move after a scramble is made.
+    started: Option<Instant>,
+    /// Set when a scrambled cube gets solved.
+    finished: Option<Duration>,
+    scrambled: bool,
+    rng: Rng,
+}
+
+impl Simulator {
+    pub fn new(rng: Rng) -> Self {
+        Self {
+            cube: Cube::default(),
+            view: View::Net,
+            moves: 0,
+            started: None,
+            finished: None,
+            scrambled: false,
+            rng,
+        }
+    }
+
+    pub fn cube(&self) -> &Cube {
+        &self.cube
+    }
+
+    pub fn moves(&self) -> usize {
+        self.moves
+    }
+
+    fn reset(&mut self, cube: Cube) {
+        self.cube = cube;
+        self.moves = 0;
+        self.started = None;
+        self.finished = None;
+    }
+
+    pub fn scramble(&mut self) {
+        let mut cube = Cube::default();
+        cube.execute_algorithm(scramble::scramble(&mut self.rng));
+        self.reset(cube);
+        self.scrambled = true;
+    }
+
+    /// How long the current solve has taken.
+    pub fn elapsed(&self) -> Duration {
+        match (self.finished, self.started) {
+            (Some(time), _) => time,
+            (None, Some(start)) => start.elapsed(),
+            (None, None) => Duration::from_secs(0),
+        }
+    }
+
+    /// Handles a key, returning `false` when it's time to quit.
+    pub fn key(&mut self, key: u8) -> bool {
+        match key {
+            term::ESCAPE | term::CTRL_C => return false,
+            b' ' => self.scramble(),
+            term::BACKSPACE => {
+                self.reset(Cube::default());
+                self.scrambled = false;
+            }
+            b'\t' => {
+                self.view = match self.view {
+                    View::Net => View::Iso,
+                    View::Iso => View::Net,
+                }
+            }
+            _ => {
+                if let Some(mv) = key_move(key) {
+                    self.turn(mv);
+                }
+            }
+        }
+        true
+    }
+
+    pub fn turn(&mut self, mv: Move) {
+        if self.finished.is_some() {
+            return;
+        }
+        if self.scrambled && self.started.is_none() {
+            self.started = Some(Instant::now());
+        }
+
+        self.cube.execute_move(mv);
+        self.moves += 1;
+
+        if self.scrambled && self.cube.is_solved() {
+            self.finished = Some(self.elapsed());
+            self.scrambled = false;
+        }
+    }
+
+    pub fn frame(&self, colour: bool) -> String {
+        let cube = match (self.view, colour) {
+            (View::Net, true) => ansi::net(&self.cube),
+            (View::Net, false) => self.cube.to_string(),
+            (View::Iso, colour) => iso::render(&self.cube, &IsoOptions { colour, back: true }),
+        };
+        let time = self.elapsed();
+        let status = match self.finished {
+            Some(_) => "  solved!",
+            None if self.scrambled && self.started.is_none() => "  timer starts on the first move",
+            None => "",
+        };
+
+        format!(
+            "{}\nmoves: {}  time: {}.{:02}{}\n\n{}\n",
+            cube,
+            self.moves,
+            time.as_secs(),
+            time.subsec_millis() / 10,
+            status,
+            HELP
+        )
+    }
+}
+
+/// Runs the simulator until the user quits. `input` has to be the terminal's
+/// stdin, since that's what gets switched to raw mode.
+pub fn run<R: Read, W: Write>(mut input: R, mut out: W) -> io::Result<()> {
+    let raw = RawMode::enable(1)?;
+    let mut sim = Simulator::new(Rng::from_time());
+
+    write!(out, "{}", term::ALTERNATE_SCREEN)?;
+    let result = (|| loop {
+        // Raw mode only leaves output processing on, so lines still return.
+        write!(out, "{}{}", term::CLEAR, sim.frame(true))?;
+        out.flush()?;
+
+        if let Some(key) = raw.read_key(&mut input)? {
+            if !sim.key(key) {
+                return Ok(());
+            }
+        }
+    })();
+    write!(out, "{}", term::MAIN_SCREEN)?;
+    out.flush()?;
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_turn_the_cube() {
+        let mut sim = Simulator::new(Rng::new(1));
+        for key in b"ijkf".iter() {
+            sim.key(*key);
+        }
+
+        let mut expected = Cube::default();
+        expected.execute_algorithm(crate::algoritm::Algorithm::parse("R U R' U'").unwrap());
+        assert_eq!(sim.cube(), &expected);
+        assert_eq!(sim.moves(), 4);
+
+        assert!(sim.key(b'x'));
+        assert!(!sim.key(term::ESCAPE));
+    }
+
+    #[test]
+    fn timer_stops_when_solved() {
+        let mut sim = Simulator::new(Rng::new(1));
+        sim.reset(Cube::default());
+        sim.scrambled = true;
+        sim.turn(Move::new(Side::Right, MoveType::Normal));
+        assert!(sim.started.is_some());
+        assert!(sim.finished.is_none());
+
+        sim.turn(Move::new(Side::Right, MoveType::Prime));
+        assert!(sim.finished.is_some());
+        assert!(sim.frame(false).contains("moves: 2"));
+        assert!(sim.frame(false).contains("solved!"));
+    }
+}
@@ -0,0 +1,386 @@
+//! Edge orientation and the first step of ZZ, EOLine or EOCross, for
+//! planning them in inspection.
+//!
+//! An edge is bad when it's flipped relative to the front and back: an odd
+//! number of F and B quarter turns away from solved. Once every edge is
+//! good, the rest of ZZ is solved with R, U and L, which keep them good.
+//! EOLine also solves DF and DB, and EOCross the four edges of the cross.
+//!
+//! Both are found with IDA* over the flips of all edges and the places of
+//! the bottom ones. EOLine is pruned by an exact table of the flips with DF
+//! and DB; EOCross by that, the same for DR and DL, and the cross table.
+
+use crate::{
+    algoritm::{Algorithm, Side},
+    cross::{self, CrossOptions, CROSS_EDGES},
+    cubie::{CubieCube, StateError, EDGES, EDGE_NAMES},
+    solver::{self, MOVES, UNKNOWN},
+    Colour, Cube,
+};
+use std::{
+    fmt::{self, Display, Formatter},
+    sync::OnceLock,
+};
+
+/// The flips of the first 11 edges, the last following from them.
+const FLIPS: usize = 2048;
+
+/// Two edges' places and flips, each as `position * 2 + flip`.
+const PAIRS: usize = 576;
+
+/// The longest EOLine or EOCross looked for.
+pub const MAX_LENGTH: usize = 12;
+
+/// The edges that are flipped relative to the front and back.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EdgeOrientation {
+    /// The positions of the bad edges, numbered as in [`crate::cubie`].
+    pub bad: Vec<usize>,
+}
+
+impl EdgeOrientation {
+    pub fn of(cube: &Cube) -> Result<Self, StateError> {
+        let cubie = CubieCube::from_cube(cube)?;
+        Ok(Self {
+            bad: (0..EDGES).filter(|&i| cubie.eo[i] == 1).collect(),
+        })
+    }
+}
+
+/// Shows how many edges are bad and where, like `2 bad: UF DB`.
+impl Display for EdgeOrientation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bad", self.bad.len())?;
+        for (i, position) in self.bad.iter().enumerate() {
+            write!(
+                f,
+                "{}{}",
+                if i == 0 { ": " } else { " " },
+                EDGE_NAMES[*position]
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Which of the first step of ZZ to solve.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Goal {
+    /// Every edge good, with DF and DB solved.
+    EoLine,
+    /// Every edge good, with the cross solved.
+    EoCross,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    /// The colour on the bottom.
+    pub colour: Colour,
+    /// The rotation it starts with, if any.
+    pub rotation: Algorithm,
+    pub moves: Algorithm,
+}
+
+impl Solution {
+    /// The rotation followed by the moves.
+    pub fn algorithm(&self) -> Algorithm {
+        let mut moves = self.rotation.moves.clone();
+        moves.extend(self.moves.moves.iter().cloned());
+        Algorithm::new(moves)
+    }
+}
+
+/// Shows the colour, the move count and the algorithm, like
+/// `Y 4: y F L2 D R`.
+impl Display for Solution {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.colour, self.moves.move_count())?;
+        let algorithm = self.algorithm();
+        if !algorithm.moves.is_empty() {
+            write!(f, ": {}", algorithm)?;
+        }
+        Ok(())
+    }
+}
+
+fn flips(cubie: &CubieCube) -> usize {
+    cubie.eo[..EDGES - 1]
+        .iter()
+        .fold(0, |acc, flip| acc * 2 + *flip as usize)
+}
+
+/// Where each flip goes with each move, and how far the flips with DF and
+/// DB, and with DR and DL, are from solved, by [`pair`].
+struct Tables {
+    flip_moves: Vec<[u16; MOVES]>,
+    line: Vec<u8>,
+    side_line: Vec<u8>,
+}
+
+impl Tables {
+    fn new() -> Self {
+        let flip_moves: Vec<[u16; MOVES]> = (0..FLIPS)
+            .map(|flip| {
+                let mut cubie = CubieCube::default();
+                let mut sum = 0;
+                for i in 0..EDGES - 1 {
+                    cubie.eo[i] = (flip >> (EDGES - 2 - i) & 1) as u8;
+                    sum += cubie.eo[i];
+                }
+                cubie.eo[EDGES - 1] = sum % 2;
+
+                let mut row = [0; MOVES];
+                for (m, next) in row.iter_mut().enumerate() {
+                    let mut turned = cubie.clone();
+                    for _ in 0..=m % 3 {
+                        turned = turned.multiply(CubieCube::face_turn(m / 3));
+                    }
+                    *next = flips(&turned) as u16;
+                }
+                row
+            })
+            .collect();
+
+        let edge_moves = cross::edge_moves();
+        let distances = |edges: [usize; 2]| {
+            let solved = pair(0, edges.map(|edge| (edge * 2) as u8));
+            let mut table = vec![UNKNOWN; FLIPS * PAIRS];
+            table[solved] = 0;
+
+            let mut frontier = vec![solved];
+            let mut depth = 0;
+            while !frontier.is_empty() {
+                let mut next = Vec::new();
+                for i in frontier {
+                    let (flip, a, b) = (i / PAIRS, i / 24 % 24, i % 24);
+                    for m in 0..MOVES {
+                        let j = pair(flip_moves[flip][m], [edge_moves[m][a], edge_moves[m][b]]);
+                        if table[j] == UNKNOWN {
+                            table[j] = depth + 1;
+                            next.push(j);
+                        }
+                    }
+                }
+                frontier = next;
+                depth += 1;
+            }
+            table
+        };
+
+        Self {
+            line: distances([5, 7]),
+            side_line: distances([4, 6]),
+            flip_moves,
+        }
+    }
+
+    fn get() -> &'static Self {
+        static TABLES: OnceLock<Tables> = OnceLock::new();
+        TABLES.get_or_init(Tables::new)
+    }
+}
+
+/// The number of the flips with the states of two edges.
+fn pair(flip: u16, [a, b]: [u8; 2]) -> usize {
+    (flip as usize * 24 + a as usize) * 24 + b as usize
+}
+
+/// The flips, and the states of the cross edges in [`CROSS_EDGES`] order.
+#[derive(Clone, Copy)]
+struct State {
+    flip: u16,
+    edges: [u8; 4],
+}
+
+struct Search<'a> {
+    tables: &'a Tables,
+    goal: Goal,
+    moves: Vec<usize>,
+    found: Vec<Vec<usize>>,
+    limit: usize,
+}
+
+impl Search<'_> {
+    fn distance(&self, state: &State) -> usize {
+        let [dr, df, dl, db] = state.edges;
+        let line = self.tables.line[pair(state.flip, [df, db])];
+        match self.goal {
+            Goal::EoLine => line as usize,
+            Goal::EoCross => line
+                .max(self.tables.side_line[pair(state.flip, [dr, dl])])
+                .max(cross::cross_distance(cross::placement(&state.edges)))
+                as usize,
+        }
+    }
+
+    fn search(&mut self, state: State, left: usize) {
+        if self.distance(&state) > left || self.found.len() >= self.limit {
+            return;
+        }
+        if left == 0 {
+            self.found.push(self.moves.clone());
+            return;
+        }
+
+        for (m, edge_moves) in cross::edge_moves().iter().enumerate() {
+            if !solver::allowed(m, self.moves.last().copied()) {
+                continue;
+            }
+            self.moves.push(m);
+            let next = State {
+                flip: self.tables.flip_moves[state.flip as usize][m],
+                edges: state.edges.map(|edge| edge_moves[edge as usize]),
+            };
+            self.search(next, left - 1);
+            self.moves.pop();
+        }
+    }
+}
+
+/// Every optimal EOLine or EOCross with one of `colours` on the bottom, up
+/// to `options.limit` of them, of whichever colours and axes have the
+/// shortest. Turning the cube around by y2 keeps the same edges bad and the
+/// same line, so only two ways of holding each colour are tried.
+///
+/// ```
+/// # use cube::{algoritm::Algorithm, cross::CrossOptions, zz::{self, Goal}, Colour, Cube};
+/// let mut cube = Cube::default();
+/// cube.execute_algorithm(Algorithm::parse("F R").unwrap());
+///
+/// let options = CrossOptions::default();
+/// let lines = zz::solve(&cube, &[Colour::Yellow], Goal::EoLine, &options).unwrap();
+/// assert_eq!(lines[0].to_string(), "Y 1: y F'");
+/// ```
+pub fn solve(
+    cube: &Cube,
+    colours: &[Colour],
+    goal: Goal,
+    options: &CrossOptions,
+) -> Result<Vec<Solution>, StateError> {
+    let tables = Tables::get();
+    let mut best: Vec<Solution> = Vec::new();
+    for (i, rotation) in Algorithm::orientations().into_iter().enumerate() {
+        // The orientations go through y, y2 and y' for each bottom colour.
+        if i % 4 >= 2 {
+            continue;
+        }
+        let mut turned = cube.clone();
+        turned.execute_algorithm(rotation.clone());
+        let colour = turned.get_face(Side::Down).stickers()[4];
+        if !colours.contains(&colour) {
+            continue;
+        }
+
+        let cubie = CubieCube::from_cube(&turned)?;
+        let state = State {
+            flip: flips(&cubie) as u16,
+            edges: CROSS_EDGES.map(|edge| cross::edge_state(&cubie, edge)),
+        };
+        let mut search = Search {
+            tables,
+            goal,
+            moves: Vec::new(),
+            found: Vec::new(),
+            limit: options.limit,
+        };
+        for depth in 0..=MAX_LENGTH {
+            search.search(state, depth);
+            if !search.found.is_empty() {
+                break;
+            }
+        }
+
+        let solutions: Vec<Solution> = search
+            .found
+            .iter()
+            .map(|moves| {
+                let (rotation, moves) =
+                    cross::placed(&rotation, solver::to_algorithm(moves), options.rotate);
+                Solution {
+                    colour,
+                    rotation,
+                    moves,
+                }
+            })
+            .collect();
+        let length = |solutions: &[Solution]| solutions.first().map(|s| s.moves.move_count());
+        match (length(&solutions), length(&best)) {
+            (Some(new), Some(old)) if new < old => best = solutions,
+            // Without the rotations, two ways of holding the cube can give
+            // the same moves.
+            (Some(new), Some(old)) if new == old => {
+                for solution in solutions {
+                    if !best.iter().any(|s| s.algorithm() == solution.algorithm()) {
+                        best.push(solution);
+                    }
+                }
+            }
+            (Some(_), None) => best = solutions,
+            _ => {}
+        }
+    }
+    best.truncate(options.limit);
+    Ok(best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scrambled;
+
+    #[test]
+    fn reports_bad_edges() {
+        assert_eq!(scrambled("R U L D").edge_orientation().unwrap().bad, vec![]);
+        let bad = scrambled("F").edge_orientation().unwrap();
+        assert_eq!(bad.to_string(), "4 bad: UF DF FR FL");
+        // Turning the cube makes the left and right edges the front ones.
+        assert_eq!(
+            scrambled("R y").edge_orientation().unwrap().to_string(),
+            "4 bad: UF DF FR FL"
+        );
+    }
+
+    #[test]
+    fn finds_optimal_eolines_and_eocrosses() {
+        let cube = scrambled("D2 F2 U' B2 R2 B2 R2 L B' D' F D2 F' L' U");
+        let options = CrossOptions {
+            rotate: true,
+            limit: 100,
+        };
+
+        for goal in [Goal::EoLine, Goal::EoCross] {
+            let solutions = solve(&cube, &[Colour::Yellow], goal, &options).unwrap();
+            assert!(!solutions.is_empty());
+            let length = solutions[0].moves.move_count();
+            for solution in &solutions {
+                assert_eq!(solution.moves.move_count(), length);
+                let mut solved = cube.clone();
+                solved.execute_algorithm(solution.algorithm());
+                assert!(solved.edge_orientation().unwrap().bad.is_empty());
+
+                let cubie = CubieCube::from_cube(&solved).unwrap();
+                let edges: &[usize] = match goal {
+                    Goal::EoLine => &[5, 7],
+                    Goal::EoCross => &CROSS_EDGES,
+                };
+                assert!(
+                    edges.iter().all(|&e| cubie.ep[e] as usize == e),
+                    "{}",
+                    solution
+                );
+            }
+        }
+
+        let options = CrossOptions {
+            rotate: false,
+            limit: 10,
+        };
+        let lines = solve(
+            &scrambled("F R"),
+            &[Colour::Yellow],
+            Goal::EoCross,
+            &options,
+        );
+        assert_eq!(lines.unwrap()[0].to_string(), "Y 2: R' F'");
+    }
+}